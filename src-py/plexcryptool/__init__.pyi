@@ -6,5 +6,6 @@ plexcryptool.plexcryptool is direct access to the shared library, do not use it.
 from . import binary
 from . import math
 from . import algo
+from . import protocol
 from . import cplex
 from . import scripts
@@ -2,3 +2,12 @@
 various algorithms implemented
 """
 from . import feistel0 as feistel0
+from . import wiener as wiener
+from . import rsa as rsa
+from . import rsa_attacks as rsa_attacks
+from . import bleichenbacher as bleichenbacher
+from . import oaep as oaep
+from . import manger as manger
+from . import rabin as rabin
+from . import paillier as paillier
+from . import elgamal as elgamal
@@ -0,0 +1,46 @@
+"""
+# bleichenbacher's attack on PKCS#1 v1.5
+
+Decrypt a ciphertext with an oracle that tells whether a ciphertext decrypts to a PKCS#1 v1.5
+conforming block 00 02 PS 00 M. The oracle can be a python function, like the padding_check of
+the rsa_padding script.
+
+All attacks return (m, queries, rounds), rounds is a list of (s_i, queries, intervals) with
+the intervals [a, b] that still contain m * s_0 after iteration i.
+
+___
+@Author:     Christoph J. Scherr <software@cscherr.de>
+@License:    MIT
+@Source:     <https://git.cscherr.de/PlexSheep/plexcryptool/>
+"""
+from typing import Callable
+
+from .rsa import RsaPrivateKey
+
+def attack(n: int, e: int, c: int, oracle: Callable[[int], bool], verbose: bool = False) -> tuple[int, int, list[tuple[int, int, list[tuple[int, int]]]]]:
+    """
+    decrypt c with a padding oracle
+
+    raises a ValueError if the modulus is shorter than 11 bytes or the oracle raises an exception
+    """
+    ...
+
+def attack_local(key: RsaPrivateKey, c: int, strict: bool = False, verbose: bool = False) -> tuple[int, int, list[tuple[int, int, list[tuple[int, int]]]]]:
+    """
+    decrypt c with a local oracle that knows the private key
+
+    :param strict the oracle also checks the padding bytes and the 00 separator
+    """
+    ...
+
+def pad(message: bytes, k: int) -> int:
+    """
+    pad message to a PKCS#1 v1.5 encryption block of k bytes
+    """
+    ...
+
+def unpad(m: int, k: int) -> bytes:
+    """
+    remove the PKCS#1 v1.5 padding, raises a ValueError if m is not conforming
+    """
+    ...
@@ -0,0 +1,133 @@
+"""
+# elgamal encryption and signatures
+
+Encryption is (c1, c2) = (g^k, m * h^k) with the public key h = g^x, decryption is
+m = c2 * (c1^x)^-1. A signature for m is (R, s) with R = g^k and s = k^-1 * (m - x * f(R)) mod q,
+where f(R) is R itself in a field and the x coordinate on a curve.
+
+Ciphertexts are malleable, and two signatures with the same k give away the private key.
+
+___
+@Author:     Christoph J. Scherr <software@cscherr.de>
+@License:    MIT
+@Source:     <https://git.cscherr.de/PlexSheep/plexcryptool/>
+"""
+from ..math.ecc import EllipticCurve, EllipticCurvePoint
+
+class ElGamalField:
+    """
+    elgamal in the multiplicative group of a prime field
+    """
+    p: int
+    g: int
+    order: int
+
+    def __init__(self, p: int, g: int, order: int | None = None) -> None:
+        """
+        g has order p - 1 if no order is given
+        """
+        ...
+
+    def keygen(self, verbose: bool = False) -> tuple[int, int]:
+        """
+        generate a key pair (x, h)
+        """
+        ...
+
+    def encrypt(self, h: int, m: int, k: int | None = None, verbose: bool = False) -> tuple[int, int]:
+        """
+        encrypt m to (c1, c2), with a random k if none is given
+        """
+        ...
+
+    def decrypt(self, x: int, c1: int, c2: int, verbose: bool = False) -> int:
+        """
+        decrypt (c1, c2)
+        """
+        ...
+
+    def maul(self, c1: int, c2: int, t: int, verbose: bool = False) -> tuple[int, int]:
+        """
+        change (c1, c2) so that it decrypts to m * t
+        """
+        ...
+
+    def sign(self, x: int, m: int, k: int | None = None, verbose: bool = False) -> tuple[int, int]:
+        """
+        sign m to (r, s), with a random k if none is given
+        """
+        ...
+
+    def verify(self, h: int, m: int, r: int, s: int, verbose: bool = False) -> bool:
+        """
+        verify the signature (r, s) for m
+        """
+        ...
+
+    def recover_key(self, h: int, m1: int, sig1: tuple[int, int], m2: int, sig2: tuple[int, int],
+                    verbose: bool = False) -> tuple[int, int]:
+        """
+        recover (k, x) from two signatures sig1 = (r, s1) and sig2 = (r, s2) with the same k
+        """
+        ...
+
+class ElGamalCurve:
+    """
+    elgamal on an elliptic curve
+    """
+    g: EllipticCurvePoint
+    order: int
+
+    def __init__(self, curve: EllipticCurve, g: EllipticCurvePoint, order: int) -> None:
+        """
+        g is a point of the curve with the given order
+        """
+        ...
+
+    def keygen(self, verbose: bool = False) -> tuple[int, EllipticCurvePoint]:
+        """
+        generate a key pair (x, H)
+        """
+        ...
+
+    def encrypt(self, h: EllipticCurvePoint, m: EllipticCurvePoint, k: int | None = None,
+                verbose: bool = False) -> tuple[EllipticCurvePoint, EllipticCurvePoint]:
+        """
+        encrypt the point m to (C1, C2), with a random k if none is given
+        """
+        ...
+
+    def decrypt(self, x: int, c1: EllipticCurvePoint, c2: EllipticCurvePoint,
+                verbose: bool = False) -> EllipticCurvePoint:
+        """
+        decrypt (C1, C2)
+        """
+        ...
+
+    def maul(self, c1: EllipticCurvePoint, c2: EllipticCurvePoint, t: EllipticCurvePoint,
+             verbose: bool = False) -> tuple[EllipticCurvePoint, EllipticCurvePoint]:
+        """
+        change (C1, C2) so that it decrypts to M + T
+        """
+        ...
+
+    def sign(self, x: int, m: int, k: int | None = None,
+             verbose: bool = False) -> tuple[EllipticCurvePoint, int]:
+        """
+        sign m to (R, s), with a random k if none is given
+        """
+        ...
+
+    def verify(self, h: EllipticCurvePoint, m: int, r: EllipticCurvePoint, s: int,
+               verbose: bool = False) -> bool:
+        """
+        verify the signature (R, s) for m
+        """
+        ...
+
+    def recover_key(self, h: EllipticCurvePoint, m1: int, sig1: tuple[EllipticCurvePoint, int], m2: int,
+                    sig2: tuple[EllipticCurvePoint, int], verbose: bool = False) -> tuple[int, int]:
+        """
+        recover (k, x) from two signatures sig1 = (R, s1) and sig2 = (R, s2) with the same k
+        """
+        ...
@@ -0,0 +1,20 @@
+"""
+# manger's attack on RSA-OAEP
+
+Decrypt a ciphertext with an oracle that tells whether a ciphertext decrypts to a number below
+B = 2^(8(k-1)), i.e. whether the first byte of the OAEP encoding is 00.
+
+___
+@Author:     Christoph J. Scherr <software@cscherr.de>
+@License:    MIT
+@Source:     <https://git.cscherr.de/PlexSheep/plexcryptool/>
+"""
+from .rsa import RsaPrivateKey
+
+def attack_local(key: RsaPrivateKey, c: int, verbose: bool = False) -> tuple[int, int]:
+    """
+    decrypt c with a local oracle that knows the private key, returns (m, queries)
+
+    raises a ValueError if c does not decrypt to a number below B
+    """
+    ...
@@ -0,0 +1,35 @@
+"""
+# RSA-OAEP
+
+OAEP encoding and decoding with MGF1 over SHA-1 or SHA-256 as in RFC 8017. Encryption and
+decryption are methods of the RSA key types.
+
+___
+@Author:     Christoph J. Scherr <software@cscherr.de>
+@License:    MIT
+@Source:     <https://git.cscherr.de/PlexSheep/plexcryptool/>
+"""
+
+def mgf1(seed: bytes, length: int, hash: str = "sha256") -> bytes:
+    """
+    mask generation function MGF1, hash is "sha1" or "sha256"
+    """
+    ...
+
+def encode(message: bytes, k: int, label: bytes = b"", hash: str = "sha256", verbose: bool = False) -> bytes:
+    """
+    OAEP encoding for a key of k bytes with a random seed
+    """
+    ...
+
+def encode_block(message: bytes, l_hash: bytes, seed: bytes, k: int, hash: str = "sha256", verbose: bool = False) -> bytes:
+    """
+    OAEP encoding with an explicit label hash and seed, they may have any length
+    """
+    ...
+
+def decode(em: bytes, k: int, label: bytes = b"", hash: str = "sha256", verbose: bool = False) -> bytes:
+    """
+    OAEP decoding, raises a ValueError if the encoding is invalid
+    """
+    ...
@@ -0,0 +1,106 @@
+"""
+# paillier cryptosystem
+
+Encryption is c = g^m * r^n mod n² with g = n + 1 and a random r, decryption is
+m = L(c^λ mod n²) * μ mod n with L(u) = (u - 1) / n. Ciphertexts can be added, multiplied with a
+plaintext scalar and re-randomised without the private key, which is enough for e-voting.
+
+___
+@Author:     Christoph J. Scherr <software@cscherr.de>
+@License:    MIT
+@Source:     <https://git.cscherr.de/PlexSheep/plexcryptool/>
+"""
+def tally(key: PaillierPrivateKey, votes: list[int], candidates: int, verbose: bool = False) -> list[int]:
+    """
+    tally an election, every vote is the index of a candidate
+
+    all votes are encrypted on their own, only the product of the ballots is decrypted. Returns
+    the number of votes for each candidate.
+    """
+    ...
+
+class PaillierPublicKey:
+    """
+    a public paillier key, g is always n + 1
+    """
+    n: int
+    g: int
+
+    def __init__(self, n: int) -> None:
+        """
+        constructor
+        """
+        ...
+
+    def encrypt(self, m: int, r: int | None = None, verbose: bool = False) -> int:
+        """
+        c = g^m * r^n mod n², with a random r if none is given
+        """
+        ...
+
+    def add(self, c1: int, c2: int, verbose: bool = False) -> int:
+        """
+        E(m1 + m2) from E(m1) and E(m2)
+        """
+        ...
+
+    def scalar_mul(self, c: int, k: int, verbose: bool = False) -> int:
+        """
+        E(k * m) from E(m), k is taken mod n so a negative k subtracts
+        """
+        ...
+
+    def rerandomize(self, c: int, verbose: bool = False) -> int:
+        """
+        E(m) with fresh randomness
+        """
+        ...
+
+    def sum(self, cs: list[int], verbose: bool = False) -> int:
+        """
+        homomorphic sum of all ciphertexts
+        """
+        ...
+
+    def __str__(self) -> str: ...
+
+    def __repr__(self) -> str: ...
+
+class PaillierPrivateKey:
+    """
+    a private paillier key
+    """
+    n: int
+    lambda_: int
+    mu: int
+    p: int
+    q: int
+
+    def __init__(self, p: int, q: int, verbose: bool = False) -> None:
+        """
+        make a key from two primes, λ = lcm(p-1, q-1) and μ = L(g^λ mod n²)^-1 mod n
+        """
+        ...
+
+    @staticmethod
+    def generate(bits: int, verbose: bool = False) -> PaillierPrivateKey:
+        """
+        generate a new key with a modulus of bits bits
+        """
+        ...
+
+    def public_key(self) -> PaillierPublicKey:
+        """
+        the public part of the key
+        """
+        ...
+
+    def decrypt(self, c: int, verbose: bool = False) -> int:
+        """
+        m = L(c^λ mod n²) * μ mod n
+        """
+        ...
+
+    def __str__(self) -> str: ...
+
+    def __repr__(self) -> str: ...
@@ -0,0 +1,97 @@
+"""
+# rabin cryptosystem
+
+Encryption is c = m² mod n with n = pq and p = q = 3 (mod 4), decryption gives four roots. An
+optional redundancy repeats the lowest bits of m, so that the right root can be chosen. Without
+it, a decryption oracle factors n with a chosen ciphertext attack.
+
+___
+@Author:     Christoph J. Scherr <software@cscherr.de>
+@License:    MIT
+@Source:     <https://git.cscherr.de/PlexSheep/plexcryptool/>
+"""
+def add_redundancy(m: int, redundancy: int) -> int:
+    """
+    repeat the lowest redundancy bits of m: m * 2^r + (m mod 2^r)
+    """
+    ...
+
+def strip_redundancy(x: int, redundancy: int) -> int | None:
+    """
+    remove the redundancy of x, None if the lowest bits are not repeated
+    """
+    ...
+
+def attack_local(key: RabinPrivateKey, redundancy: int = 0, verbose: bool = False) -> tuple[int, int, int]:
+    """
+    factor n with a chosen ciphertext attack against a local decryption oracle
+
+    returns p, q and the number of queries. With redundancy the oracle only answers for roots
+    with a valid pattern and the attack fails with a ValueError.
+    """
+    ...
+
+class RabinPublicKey:
+    """
+    a public rabin key
+    """
+    n: int
+
+    def __init__(self, n: int) -> None:
+        """
+        constructor
+        """
+        ...
+
+    def encrypt(self, m: int, redundancy: int = 0, verbose: bool = False) -> int:
+        """
+        c = m² mod n, raises a ValueError if m with redundancy is not in [0, n)
+        """
+        ...
+
+    def __str__(self) -> str: ...
+
+    def __repr__(self) -> str: ...
+
+class RabinPrivateKey:
+    """
+    a private rabin key
+    """
+    n: int
+    p: int
+    q: int
+
+    def __init__(self, p: int, q: int, verbose: bool = False) -> None:
+        """
+        make a key from two primes that are 3 mod 4
+        """
+        ...
+
+    @staticmethod
+    def generate(bits: int, verbose: bool = False) -> RabinPrivateKey:
+        """
+        generate a new key with a modulus of bits bits
+        """
+        ...
+
+    def public_key(self) -> RabinPublicKey:
+        """
+        the public part of the key
+        """
+        ...
+
+    def decrypt(self, c: int, verbose: bool = False) -> list[int]:
+        """
+        all four roots of c, sorted
+        """
+        ...
+
+    def decrypt_redundant(self, c: int, redundancy: int, verbose: bool = False) -> int:
+        """
+        the root with a valid redundancy, without the redundancy
+        """
+        ...
+
+    def __str__(self) -> str: ...
+
+    def __repr__(self) -> str: ...
@@ -0,0 +1,104 @@
+"""
+# textbook RSA
+
+Key generation, encryption, decryption, signatures and decryption with the chinese remainder
+theorem. There is no padding, do not use this for anything but exercises.
+
+___
+@Author:     Christoph J. Scherr <software@cscherr.de>
+@License:    MIT
+@Source:     <https://git.cscherr.de/PlexSheep/plexcryptool/>
+"""
+def random_prime(bits: int, verbose: bool = False) -> int:
+    """
+    a random prime with exactly bits bits, the two highest bits are set
+    """
+    ...
+
+class RsaPublicKey:
+    """
+    a public RSA key (n, e)
+    """
+    n: int
+    e: int
+
+    def __init__(self, n: int, e: int) -> None:
+        """
+        constructor
+        """
+        ...
+
+    def encrypt(self, m: int, verbose: bool = False) -> int:
+        """
+        c = m^e mod n, raises a ValueError if m is not in [0, n)
+        """
+        ...
+
+    def verify(self, m: int, s: int, verbose: bool = False) -> bool:
+        """
+        check s^e = m (mod n)
+        """
+        ...
+
+    def encrypt_oaep(self, message: bytes, label: bytes = b"", hash: str = "sha256", verbose: bool = False) -> int:
+        """
+        encrypt a message with OAEP padding, hash is "sha1" or "sha256"
+        """
+        ...
+
+    def __str__(self) -> str: ...
+
+    def __repr__(self) -> str: ...
+
+class RsaPrivateKey:
+    """
+    a private RSA key
+    """
+    n: int
+    e: int
+    d: int
+    p: int
+    q: int
+
+    def __init__(self, p: int, q: int, e: int = 65537, verbose: bool = False) -> None:
+        """
+        make a key from its primes, d is the inverse of e mod (p-1)(q-1)
+        """
+        ...
+
+    @staticmethod
+    def generate(bits: int, e: int = 65537, verbose: bool = False) -> RsaPrivateKey:
+        """
+        generate a new key with a modulus of bits bits
+        """
+        ...
+
+    def public_key(self) -> RsaPublicKey:
+        """
+        the public part of the key
+        """
+        ...
+
+    def decrypt(self, c: int, verbose: bool = False) -> int:
+        """
+        decrypt c with the chinese remainder theorem
+
+        :param verbose print dp, dq, qInv and the two half size exponentiations
+        """
+        ...
+
+    def sign(self, m: int, verbose: bool = False) -> int:
+        """
+        s = m^d mod n, calculated with the chinese remainder theorem
+        """
+        ...
+
+    def decrypt_oaep(self, c: int, label: bytes = b"", hash: str = "sha256", verbose: bool = False) -> bytes:
+        """
+        decrypt an OAEP padded ciphertext, raises a ValueError if the padding is invalid
+        """
+        ...
+
+    def __str__(self) -> str: ...
+
+    def __repr__(self) -> str: ...
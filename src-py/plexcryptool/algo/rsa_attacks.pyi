@@ -0,0 +1,34 @@
+"""
+# classic attacks on textbook RSA
+
+Common modulus, håstad's broadcast attack, fermat factorisation and the recovery of p and q from
+a known private exponent.
+
+___
+@Author:     Christoph J. Scherr <software@cscherr.de>
+@License:    MIT
+@Source:     <https://git.cscherr.de/PlexSheep/plexcryptool/>
+"""
+def common_modulus(n: int, e1: int, c1: int, e2: int, c2: int, verbose: bool = False) -> int:
+    """
+    recover m from c1 = m^e1 mod n and c2 = m^e2 mod n, gcd(e1, e2) must be 1
+    """
+    ...
+
+def hastad(ciphertexts: list[int], moduli: list[int], e: int, verbose: bool = False) -> int:
+    """
+    recover m from c_i = m^e mod n_i, usually e ciphertexts are needed
+    """
+    ...
+
+def fermat(n: int, max_steps: int = 1048576, verbose: bool = False) -> tuple[int, int]:
+    """
+    factor n = p * q if |p - q| is small, returns (p, q) with p >= q
+    """
+    ...
+
+def factor_with_d(n: int, e: int, d: int, verbose: bool = False) -> tuple[int, int]:
+    """
+    recover (p, q) with p >= q from the private exponent d
+    """
+    ...
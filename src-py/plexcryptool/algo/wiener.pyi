@@ -0,0 +1,22 @@
+"""
+# wiener's attack on RSA
+
+Recovers the private key from an RSA public key (n, e) if d < n^(1/4) / 3, by testing the
+convergents of the continued fraction of e/n.
+
+___
+@Author:     Christoph J. Scherr <software@cscherr.de>
+@License:    MIT
+@Source:     <https://git.cscherr.de/PlexSheep/plexcryptool/>
+"""
+def wiener(n: int, e: int, verbose: bool = False) -> tuple[int, int, int]:
+    """
+    wiener's attack
+
+    returns (d, p, q), raises a ValueError if no convergent revealed d
+
+    :param n the RSA modulus
+    :param e the public exponent
+    :param verbose print the table of convergents
+    """
+    ...
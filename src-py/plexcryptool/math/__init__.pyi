@@ -6,4 +6,16 @@ Funcionality for math things. Contains tedious algorithms like binary exponentia
 from . import modexp as modexp
 from . import modred as modred
 from . import pm1 as pm1
-
+from . import pp1 as pp1
+from . import biggallois as biggallois
+from . import primality as primality
+from . import factorise as factorise
+from . import ecm as ecm
+from . import crt as crt
+from . import indexcalculus as indexcalculus
+from . import arith as arith
+from . import jacobi as jacobi
+from . import contfrac as contfrac
+from . import lll as lll
+from . import coppersmith as coppersmith
+from . import modsqrt as modsqrt
@@ -0,0 +1,48 @@
+"""
+number theoretic functions
+
+Euler's totient, Carmichael's function, multiplicative orders and primitive roots.
+
+___
+@Author:     Christoph J. Scherr <software@cscherr.de>
+@License:    MIT
+@Source:     <https://git.cscherr.de/PlexSheep/plexcryptool/>
+"""
+
+def totient(n: int, verbose: bool = False) -> int:
+    """
+    Euler's totient φ(n)
+    """
+    ...
+
+def carmichael(n: int, verbose: bool = False) -> int:
+    """
+    Carmichael's function λ(n)
+    """
+    ...
+
+def multiplicative_order(a: int, n: int, verbose: bool = False) -> int:
+    """
+    the smallest k > 0 with a^k = 1 (mod n)
+
+    raises a ValueError if gcd(a, n) != 1
+    """
+    ...
+
+def has_primitive_roots(n: int) -> bool:
+    """
+    test if n is 1, 2, 4, p^k or 2p^k
+    """
+    ...
+
+def primitive_root(n: int, verbose: bool = False) -> int:
+    """
+    the smallest primitive root mod n
+    """
+    ...
+
+def primitive_roots(n: int, verbose: bool = False) -> list[int]:
+    """
+    all primitive roots mod n, sorted
+    """
+    ...
@@ -0,0 +1,110 @@
+"""
+gallois field calculations with arbitrary precision
+
+Same as the GalloisFiled class, but not limited to 128 bit. Use this for fields like the 256 bit
+prime fields behind P-256 or secp256k1.
+
+___
+@Author:     Christoph J. Scherr <software@cscherr.de>
+@License:    MIT
+@Source:     <https://git.cscherr.de/PlexSheep/plexcryptool/>
+"""
+
+class BigGalloisField:
+    def __init__(self, base: int, verbose: bool = False, relation: int | None = None) -> None:
+        """
+        Create a new Gallois field
+
+        :param base number of elements, a prime or a power of two
+        :param verbose print steps
+        :param relation irreducible polynomial for non prime bases
+        """
+        ...
+
+    def reduce(self, n: int) -> int:
+        """
+        reduce the given number to fit into the field
+        """
+        ...
+
+    def add(self, a: int, b: int) -> int:
+        """
+        add two elements
+        """
+        ...
+
+    def sub(self, a: int, b: int) -> int:
+        """
+        subtract two elements
+        """
+        ...
+
+    def mul(self, a: int, b: int) -> int:
+        """
+        multiply two elements
+        """
+        ...
+
+    def pow(self, base: int, exp: int) -> int:
+        """
+        calculate base ** exp in the field
+        """
+        ...
+
+    def a_inverse(self, n: int) -> int:
+        """
+        find the additive inverse of n
+        """
+        ...
+
+    def inverse(self, n: int) -> int:
+        """
+        find the multiplicative inverse of n, raises ValueError if there is none
+        """
+        ...
+
+    def divide(self, a: int, b: int) -> int:
+        """
+        divide a by b, raises ValueError for b = 0
+        """
+        ...
+
+    def sqrt(self, a: int) -> tuple[int, int]:
+        """
+        find both square roots of a, raises ValueError if there is none
+        """
+        ...
+
+    def display(self, n: int) -> str:
+        """
+        display an element, as polynomial for non prime bases
+        """
+        ...
+
+    def dlog(self, g: int, h: int, verbose: bool = False) -> int:
+        """
+        find x with g^x = h using baby-step giant-step, raises ValueError if there is none
+        """
+        ...
+
+    def pohlig_hellman(self, g: int, h: int, verbose: bool = False) -> int:
+        """
+        find x with g^x = h using pohlig-hellman, raises ValueError if there is none
+
+        works well if the order of the field minus one is smooth
+        """
+        ...
+
+    def rho_dlog(self, g: int, h: int, order: int | None = None, verbose: bool = False) -> int:
+        """
+        find x with g^x = h using pollard's rho, raises ValueError if there is none
+
+        order is the order of g, defaults to the order of the multiplicative group
+        """
+        ...
+
+    def kangaroo(self, g: int, h: int, a: int, b: int, verbose: bool = False) -> int:
+        """
+        find x in [a, b] with g^x = h using pollard's kangaroo, raises ValueError if there is none
+        """
+        ...
@@ -0,0 +1,33 @@
+"""
+continued fractions
+
+Expand a rational number a/b into its partial quotients [a_0; a_1, ..., a_m], calculate the
+convergents h_i/k_i and reconstruct a fraction from its partial quotients.
+
+___
+@Author:     Christoph J. Scherr <software@cscherr.de>
+@License:    MIT
+@Source:     <https://git.cscherr.de/PlexSheep/plexcryptool/>
+"""
+
+def continued_fraction(a: int, b: int, verbose: bool = False) -> list[int]:
+    """
+    expand a/b into a continued fraction
+
+    raises a ValueError if b is zero
+
+    :param verbose print the steps of the euclidean algorithm
+    """
+    ...
+
+def convergents(quotients: list[int]) -> list[tuple[int, int]]:
+    """
+    all convergents (h_i, k_i) of a continued fraction
+    """
+    ...
+
+def reconstruct(quotients: list[int]) -> tuple[int, int]:
+    """
+    the fraction (h, k) with the given partial quotients
+    """
+    ...
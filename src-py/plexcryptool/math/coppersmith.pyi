@@ -0,0 +1,30 @@
+"""
+coppersmith's method for small roots
+
+Find all x with f(x) = 0 (mod n) and |x| <= bound with the lattice construction of
+Howgrave-Graham. Polynomials are lists of coefficients, starting with the constant term.
+
+___
+@Author:     Christoph J. Scherr <software@cscherr.de>
+@License:    MIT
+@Source:     <https://git.cscherr.de/PlexSheep/plexcryptool/>
+"""
+
+def small_roots(coefficients: list[int], n: int, bound: int, m: int = 0, t: int = 0, verbose: bool = False) -> list[int]:
+    """
+    all roots of f mod n with absolute value at most bound
+
+    raises a ValueError if f is constant, the leading coefficient is not invertible mod n or the
+    bound is larger than n^(1/d)
+
+    :param m the lattice parameter, 0 chooses it from the bound
+    :param t the number of extra polynomials x^j * f^m
+    :param verbose print the lattice polynomials and the reduced polynomials
+    """
+    ...
+
+def parse_polynomial(s: str) -> list[int]:
+    """
+    parse a polynomial in x like "x^3 + 5*x - 7" into its coefficients
+    """
+    ...
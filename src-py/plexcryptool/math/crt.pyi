@@ -0,0 +1,23 @@
+"""
+chinese remainder theorem
+
+Solve systems of congruences x = a_i (mod m_i). Moduli that are not pairwise coprime are merged
+via their lcm, inconsistent systems raise a ValueError.
+
+___
+@Author:     Christoph J. Scherr <software@cscherr.de>
+@License:    MIT
+@Source:     <https://git.cscherr.de/PlexSheep/plexcryptool/>
+"""
+
+def crt(residues: list[int], moduli: list[int], verbose: bool = False) -> tuple[int, int]:
+    """
+    solve x = a_i (mod m_i)
+
+    returns (x, M) with 0 <= x < M, where M is the lcm of all moduli
+
+    :param residues the a_i
+    :param moduli the m_i
+    :param verbose print the M_i and y_i terms
+    """
+    ...
@@ -49,6 +49,24 @@ class EllipticCurve:
         """
         ...
 
+    def pohlig_hellman(self, g: EllipticCurvePoint, h: EllipticCurvePoint, order: int, verbose: bool = False) -> int:
+        """
+        find t with t * g = h using pohlig-hellman, order is the order of g
+        """
+        ...
+
+    def rho_dlog(self, g: EllipticCurvePoint, h: EllipticCurvePoint, order: int, verbose: bool = False) -> int:
+        """
+        find t with t * g = h using pollard's rho, order is the order of g
+        """
+        ...
+
+    def kangaroo(self, g: EllipticCurvePoint, h: EllipticCurvePoint, a: int, b: int, verbose: bool = False) -> int:
+        """
+        find t in [a, b] with t * g = h using pollard's kangaroo
+        """
+        ...
+
     def get_infinity_point(self) -> EllipticCurvePoint:
         """
         get the infinity point of a curve
@@ -0,0 +1,25 @@
+"""
+Lenstra elliptic curve factorization (ECM)
+
+Finds factors of n by calculating on random elliptic curves over Z/nZ. A failed inverse during
+point addition exposes a factor.
+
+___
+@Author:     Christoph J. Scherr <software@cscherr.de>
+@License:    MIT
+@Source:     <https://git.cscherr.de/PlexSheep/plexcryptool/>
+"""
+
+def ecm(n: int, b1: int = 2000, curves: int = 100, verbose: bool = False) -> list[tuple[int, int]]:
+    """
+    find the prime factors of n with ECM
+
+    returns (prime, multiplicity) tuples in ascending order,
+    raises ArithmeticError if a factor could not be found
+
+    :param n number to factorize
+    :param b1 smoothness bound for stage 1
+    :param curves how many random curves to try
+    :param verbose trace each curve
+    """
+    ...
@@ -0,0 +1,30 @@
+"""
+factorize a large integer
+
+Contains naive trial division and Pollard's rho method with Brent's cycle detection.
+
+___
+@Author:     Christoph J. Scherr <software@cscherr.de>
+@License:    MIT
+@Source:     <https://git.cscherr.de/PlexSheep/plexcryptool/>
+"""
+
+def prime_factors(n: int, verbose: bool) -> list[int]:
+    """
+    find the prime factors of n with trial division
+
+    :param n u128 number to factorize
+    :param verbose print steps
+    """
+    ...
+
+def pollard_rho(n: int, verbose: bool = False) -> list[tuple[int, int]]:
+    """
+    find the prime factors of n with Pollard's rho method
+
+    returns (prime, multiplicity) tuples in ascending order
+
+    :param n number to factorize
+    :param verbose print the x/y/gcd sequence
+    """
+    ...
@@ -21,3 +21,38 @@ class GalloisFiled:
         """
         reduce the given number to fit into the field
         """
+        ...
+
+    def mul(self, a: int, b: int) -> int:
+        """
+        multiply two elements, as polynomials for non prime bases
+        """
+        ...
+
+    def dlog(self, g: int, h: int, verbose: bool = False) -> int:
+        """
+        find x with g^x = h using baby-step giant-step, raises ValueError if there is none
+        """
+        ...
+
+    def pohlig_hellman(self, g: int, h: int, verbose: bool = False) -> int:
+        """
+        find x with g^x = h using pohlig-hellman, raises ValueError if there is none
+
+        works well if the order of the field minus one is smooth
+        """
+        ...
+
+    def rho_dlog(self, g: int, h: int, order: int | None = None, verbose: bool = False) -> int:
+        """
+        find x with g^x = h using pollard's rho, raises ValueError if there is none
+
+        order is the order of g, defaults to the order of the multiplicative group
+        """
+        ...
+
+    def kangaroo(self, g: int, h: int, a: int, b: int, verbose: bool = False) -> int:
+        """
+        find x in [a, b] with g^x = h using pollard's kangaroo, raises ValueError if there is none
+        """
+        ...
@@ -0,0 +1,26 @@
+"""
+index calculus
+
+Discrete logarithms in prime fields. Finds relations g^k = prod(l_i^e_i) (mod p) over a factor
+base of small primes, solves them modulo p-1 for the logs of the factor base and uses those to
+find the log of the target.
+
+___
+@Author:     Christoph J. Scherr <software@cscherr.de>
+@License:    MIT
+@Source:     <https://git.cscherr.de/PlexSheep/plexcryptool/>
+"""
+
+def index_calculus(p: int, g: int, h: int, bound: int = 0, verbose: bool = False) -> int:
+    """
+    find x with g^x = h (mod p)
+
+    raises ValueError if p is not prime or no solution was found
+
+    :param p prime modulus
+    :param g primitive root mod p
+    :param h target
+    :param bound largest prime of the factor base, 0 chooses one automatically
+    :param verbose print the relations
+    """
+    ...
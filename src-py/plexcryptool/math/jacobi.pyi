@@ -0,0 +1,34 @@
+"""
+legendre, jacobi and kronecker symbols
+
+Calculated with quadratic reciprocity and its supplements, the verbose output shows every rule
+that was applied.
+
+___
+@Author:     Christoph J. Scherr <software@cscherr.de>
+@License:    MIT
+@Source:     <https://git.cscherr.de/PlexSheep/plexcryptool/>
+"""
+
+def jacobi(a: int, n: int, verbose: bool = False) -> int:
+    """
+    jacobi symbol (a/n)
+
+    raises a ValueError if n is not odd and positive
+    """
+    ...
+
+def legendre(a: int, p: int, verbose: bool = False) -> int:
+    """
+    legendre symbol (a/p)
+
+    1 if a is a quadratic residue mod p, -1 if it is not, 0 if p divides a.
+    raises a ValueError if p is not an odd prime
+    """
+    ...
+
+def kronecker(a: int, n: int, verbose: bool = False) -> int:
+    """
+    kronecker symbol (a/n) for any integer n
+    """
+    ...
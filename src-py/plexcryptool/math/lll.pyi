@@ -0,0 +1,22 @@
+"""
+LLL lattice reduction
+
+Reduce a lattice basis, given as a list of integer row vectors, with exact rational arithmetic.
+
+___
+@Author:     Christoph J. Scherr <software@cscherr.de>
+@License:    MIT
+@Source:     <https://git.cscherr.de/PlexSheep/plexcryptool/>
+"""
+
+def lll(basis: list[list[int]], delta: tuple[int, int] = (3, 4), verbose: bool = False) -> list[list[int]]:
+    """
+    LLL reduce the rows of basis
+
+    raises a ValueError if the rows are linearly dependent, have different lengths or if
+    delta is not in (1/4, 1]
+
+    :param delta the lovász parameter as (numerator, denominator)
+    :param verbose print every size reduction and swap
+    """
+    ...
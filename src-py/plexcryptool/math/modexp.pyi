@@ -23,5 +23,30 @@ def modular_exponentiation(
     :param orig_exp the exponent of the base
     :param field the number that describes the gallois field (should be prime)
     :param verbose print steps
+
+    raises a ValueError if field is not positive or orig_exp is negative and base has no inverse
+    """
+    ...
+
+def modular_exponentiation_with(
+        base: int,
+        exp: int,
+        field: int,
+        method: str = "left-to-right",
+        window: int = 0,
+        reduction: str | None = None,
+        verbose: bool = False
+        ) -> tuple[int, int, int]:
+    """
+    modular exponentiation with a selectable strategy and reduction context
+
+    returns the result, the number of squarings and the number of multiplications
+
+    :param method one of left-to-right, right-to-left, k-ary, sliding-window, ladder
+    :param window window size for k-ary and sliding window, at most 16, 0 chooses one from the
+    exponent
+    :param reduction plain, barrett or montgomery, by default montgomery for odd and plain
+    for even fields
+    :param verbose print steps
     """
     ...
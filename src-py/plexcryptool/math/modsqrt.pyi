@@ -0,0 +1,42 @@
+"""
+modular square roots
+
+Find all x with x² = a (mod n) for a composite n with known factorisation: roots modulo the
+primes with tonelli-shanks, cipolla's algorithm or a^((p+1)/4) for p = 3 mod 4, hensel lifting
+to prime powers and the chinese remainder theorem to combine them. For n = pq there are four
+roots.
+
+___
+@Author:     Christoph J. Scherr <software@cscherr.de>
+@License:    MIT
+@Source:     <https://git.cscherr.de/PlexSheep/plexcryptool/>
+"""
+
+def cipolla(a: int, p: int, verbose: bool = False) -> int:
+    """
+    cipolla's algorithm, the smaller square root of a modulo an odd prime p
+
+    :param a the number to take the root of
+    :param p an odd prime
+    :param verbose print the steps in F_p²
+    """
+    ...
+
+def hensel_lift(a: int, r: int, p: int, k: int, e: int, verbose: bool = False) -> int:
+    """
+    lift a root r of a mod p^k to a root mod p^e
+
+    :param p an odd prime that does not divide a
+    :param verbose print the root for every exponent
+    """
+    ...
+
+def sqrt_mod(a: int, primes: list[int], method: str = "tonelli-shanks", verbose: bool = False) -> list[int]:
+    """
+    all square roots of a modulo the product of the primes, sorted
+
+    :param primes prime factors of n, repeated for prime powers, like [3, 3, 5] for 45
+    :param method tonelli-shanks, cipolla or blum
+    :param verbose print the roots for every prime power and their combinations
+    """
+    ...
@@ -10,13 +10,16 @@ ___
 @Source:     <https://git.cscherr.de/PlexSheep/plexcryptool/>
 """
 
-def p_minus_one(n: int, max_prime: int, verbose: bool) -> list[int]:
+def p_minus_one(n: int, b1: int, verbose: bool = False, b2: int = 0) -> list[int]:
     """
-    p minus 1 prime number test
+    p minus 1 factorization
 
-    :param n u128 number to check
-    :param max_prime the highest prime power to use
+    returns the distinct prime factors of n, raises ArithmeticError if no factor was found
+
+    :param n number to factorize
+    :param b1 stage 1 bound, the highest prime power to use
     :param verbose print steps
+    :param b2 stage 2 bound, stage 2 is skipped if b2 <= b1
     """
     ...
 
@@ -0,0 +1,24 @@
+"""
+P plus 1 method
+
+Determine the prime factors of a number with Williams' p plus 1 method.
+Finds a prime factor p of n if p + 1 is smooth, even if p - 1 is not.
+
+___
+@Author:     Christoph J. Scherr <software@cscherr.de>
+@License:    MIT
+@Source:     <https://git.cscherr.de/PlexSheep/plexcryptool/>
+"""
+
+def p_plus_one(n: int, b1: int, verbose: bool = False, b2: int = 0) -> list[int]:
+    """
+    p plus 1 factorization
+
+    returns the distinct prime factors of n, raises ArithmeticError if no factor was found
+
+    :param n number to factorize
+    :param b1 stage 1 bound, the highest prime power to use
+    :param verbose print the lucas sequence steps
+    :param b2 stage 2 bound, stage 2 is skipped if b2 <= b1
+    """
+    ...
@@ -0,0 +1,40 @@
+"""
+primality tests
+
+Implements trial division, the Miller-Rabin test and the Baillie-PSW test.
+
+___
+@Author:     Christoph J. Scherr <software@cscherr.de>
+@License:    MIT
+@Source:     <https://git.cscherr.de/PlexSheep/plexcryptool/>
+"""
+
+def is_prime(n: int, verbose: bool = False) -> bool:
+    """
+    check if n is prime
+
+    deterministic Miller-Rabin for n < 2**64, Baillie-PSW for larger n
+
+    :param n the number to test
+    :param verbose print witnesses
+    """
+    ...
+
+def miller_rabin(n: int, rounds: int = 20, verbose: bool = False) -> bool:
+    """
+    Miller-Rabin test with random bases
+
+    :param n the number to test
+    :param rounds how many random bases to try
+    :param verbose print witnesses
+    """
+    ...
+
+def bpsw(n: int, verbose: bool = False) -> bool:
+    """
+    Baillie-PSW test
+
+    :param n the number to test
+    :param verbose print steps
+    """
+    ...
@@ -0,0 +1,4 @@
+"""
+simulations of cryptographic protocols
+"""
+from . import dh as dh
@@ -0,0 +1,40 @@
+"""
+# diffie-hellman key exchange
+
+Alice and Bob exchange A = g^a and B = g^b and both calculate K = g^ab, then Alice sends H(K) so
+that Bob can confirm the key. Mallory can substitute the public values with her own, or confine
+the key to a small subgroup if the order of g has a small factor.
+
+___
+@Author:     Christoph J. Scherr <software@cscherr.de>
+@License:    MIT
+@Source:     <https://git.cscherr.de/PlexSheep/plexcryptool/>
+"""
+from ..math.ecc import EllipticCurve, EllipticCurvePoint
+
+def dh(p: int, g: int, order: int | None = None, mallory: bool = False,
+       confine: int | None = None, verbose: bool = False) -> tuple[int, int, list[int]]:
+    """
+    diffie-hellman in F_p, without an order g has to generate all of F_p*
+
+    returns the keys of Alice and Bob and the keys that Mallory learned
+
+    :param mallory Mallory substitutes the public values with her own
+    :param confine Mallory confines the key to the subgroup of this order
+    :param verbose print the transcript message by message
+    """
+    ...
+
+def ecdh(curve: EllipticCurve, g: EllipticCurvePoint, order: int, mallory: bool = False,
+         confine: int | None = None,
+         verbose: bool = False) -> tuple[EllipticCurvePoint, EllipticCurvePoint, list[EllipticCurvePoint]]:
+    """
+    diffie-hellman on an elliptic curve, order is the order of g
+
+    returns the keys of Alice and Bob and the keys that Mallory learned
+
+    :param mallory Mallory substitutes the public values with her own
+    :param confine Mallory confines the key to the subgroup of this order
+    :param verbose print the transcript message by message
+    """
+    ...
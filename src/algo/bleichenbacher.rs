@@ -0,0 +1,510 @@
+#![allow(dead_code)]
+/// # bleichenbacher's attack on PKCS#1 v1.5
+///
+/// A PKCS#1 v1.5 encryption block of a key with k bytes is 00 02 PS 00 M, where PS are at least 8
+/// random nonzero bytes. If an oracle tells us whether a ciphertext decrypts to such a block, we
+/// learn 2B <= m * s mod n < 3B with B = 2^(8(k-2)) for every conforming c * s^e. Each conforming
+/// s narrows down the set of intervals that contains m until only one number is left.
+///
+/// 1. blinding: find s_0 so that c_0 = c * s_0^e is conforming, M_0 = {[2B, 3B - 1]}
+/// 2. search the next conforming s_i: (a) linear from n / 3B, (b) linear from s_(i-1) + 1 if
+///    there are multiple intervals, (c) with the fast r, s search if there is only one interval
+/// 3. narrow the intervals: for every [a, b] and every r with (as - 3B + 1) / n <= r <= (bs - 2B) / n
+///    keep [max(a, (2B + rn) / s), min(b, (3B - 1 + rn) / s)]
+/// 4. if M_i = {[a, a]}, m = a * s_0^-1 mod n
+///
+/// Source: D. Bleichenbacher, "Chosen Ciphertext Attacks Against Protocols Based on the RSA
+/// Encryption Standard PKCS #1", CRYPTO 1998
+///
+/// ___
+/// Author:     Christoph J. Scherr <software@cscherr.de>
+/// License:    MIT
+/// Source:     <https://git.cscherr.de/PlexSheep/plexcryptool/>
+
+use crate::cplex::printing::seperator;
+use crate::algo::rsa::{RsaPrivateKey, RsaError, DEFAULT_E};
+use crate::algo::oaep::{byte_length, to_bytes};
+use crate::math::gcd::mod_inverse;
+
+use core::fmt;
+
+use num::Integer;
+use num_bigint::{BigInt, RandBigInt, Sign};
+use num_traits::{One, Zero};
+
+use rand::Rng;
+
+use pyo3::{prelude::*, exceptions::PyValueError, types::PyBytes};
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// the minimum number of padding bytes
+pub const MIN_PADDING: usize = 8;
+
+/// give up after this many oracle queries
+pub const MAX_QUERIES: u64 = 1 << 24;
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// errors for PKCS#1 v1.5 and bleichenbacher's attack
+pub enum BleichenbacherError {
+    /// the message does not fit into a block of k bytes
+    MessageTooLong(usize),
+    /// the block is not PKCS#1 v1.5 conforming
+    NotConforming,
+    /// the modulus is too small for PKCS#1 v1.5
+    InvalidKey,
+    /// the oracle failed
+    Oracle(String),
+    /// the oracle was queried more than MAX_QUERIES times
+    TooManyQueries,
+    /// an RSA operation failed
+    Rsa(RsaError),
+}
+
+impl fmt::Display for BleichenbacherError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BleichenbacherError::MessageTooLong(k) => write!(f, "the message does not fit into {k} bytes"),
+            BleichenbacherError::NotConforming => write!(f, "the block is not PKCS#1 v1.5 conforming"),
+            BleichenbacherError::InvalidKey => write!(f, "the modulus must be at least {} bytes long", MIN_PADDING + 3),
+            BleichenbacherError::Oracle(e) => write!(f, "the oracle failed: {e}"),
+            BleichenbacherError::TooManyQueries => write!(f, "gave up after {MAX_QUERIES} oracle queries"),
+            BleichenbacherError::Rsa(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl From<RsaError> for BleichenbacherError {
+    fn from(e: RsaError) -> Self {
+        BleichenbacherError::Rsa(e)
+    }
+}
+
+/// pad a message to a PKCS#1 v1.5 encryption block 00 02 PS 00 M of k bytes
+pub fn pad(message: &[u8], k: usize) -> Result<BigInt, BleichenbacherError> {
+    if message.len() + MIN_PADDING + 3 > k {
+        return Err(BleichenbacherError::MessageTooLong(k));
+    }
+    let mut rng = rand::thread_rng();
+    let mut block: Vec<u8> = vec![0x00, 0x02];
+    for _ in 0..(k - message.len() - 3) {
+        block.push(rng.gen_range(1..=255));
+    }
+    block.push(0x00);
+    block.extend_from_slice(message);
+    Ok(BigInt::from_bytes_be(Sign::Plus, &block))
+}
+
+/// remove the PKCS#1 v1.5 padding of a block with k bytes
+pub fn unpad(m: &BigInt, k: usize) -> Result<Vec<u8>, BleichenbacherError> {
+    let block = to_bytes(m, k);
+    if block.len() != k || block[0] != 0x00 || block[1] != 0x02 {
+        return Err(BleichenbacherError::NotConforming);
+    }
+    match block.iter().skip(2).position(|b| *b == 0) {
+        Some(i) if i >= MIN_PADDING => Ok(block[(i + 3)..].to_vec()),
+        _ => Err(BleichenbacherError::NotConforming)
+    }
+}
+
+/// an oracle that tells if a ciphertext is PKCS#1 v1.5 conforming
+pub trait PaddingOracle {
+    /// true if c decrypts to a block starting with 00 02
+    fn query(&mut self, c: &BigInt) -> Result<bool, BleichenbacherError>;
+}
+
+/// a padding oracle that decrypts with a private key
+///
+/// the lax oracle only checks the leading 00 02, the strict one also checks the padding bytes and
+/// the 00 separator. The attack needs more queries against a strict oracle.
+pub struct LocalOracle {
+    /// the key to decrypt with
+    key: RsaPrivateKey,
+    /// length of n in bytes
+    k: usize,
+    /// check the whole padding, not only the leading 00 02
+    strict: bool,
+}
+
+impl LocalOracle {
+    /// make an oracle from a private key
+    pub fn new(key: RsaPrivateKey, strict: bool) -> Self {
+        let k = byte_length(&key.n);
+        LocalOracle { key, k, strict }
+    }
+}
+
+impl PaddingOracle for LocalOracle {
+    fn query(&mut self, c: &BigInt) -> Result<bool, BleichenbacherError> {
+        // the oracle is queried thousands of times, so this uses the fast modpow of num
+        let m = c.modpow(&self.key.d, &self.key.n);
+        if self.strict {
+            return Ok(unpad(&m, self.k).is_ok());
+        }
+        let block = to_bytes(&m, self.k);
+        Ok(block[0] == 0x00 && block[1] == 0x02)
+    }
+}
+
+/// a padding oracle that calls a python function with the ciphertext
+pub struct PyOracle {
+    /// takes the ciphertext as int and returns a bool
+    callback: PyObject,
+}
+
+impl PyOracle {
+    /// wrap a python callable that takes an int and returns a bool
+    pub fn new(callback: PyObject) -> Self {
+        PyOracle { callback }
+    }
+}
+
+impl PaddingOracle for PyOracle {
+    fn query(&mut self, c: &BigInt) -> Result<bool, BleichenbacherError> {
+        Python::with_gil(|py| {
+            match self.callback.call1(py, (c.clone(),)).and_then(|r| r.extract::<bool>(py)) {
+                Ok(b) => Ok(b),
+                Err(e) => Err(BleichenbacherError::Oracle(e.to_string()))
+            }
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// the state after one iteration of the attack
+pub struct Round {
+    /// the conforming multiplier s_i
+    pub s: BigInt,
+    /// the number of oracle queries so far
+    pub queries: u64,
+    /// the intervals M_i that contain m * s_0
+    pub intervals: Vec<(BigInt, BigInt)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// the result of bleichenbacher's attack
+pub struct Recovered {
+    /// the decrypted block
+    pub m: BigInt,
+    /// the blinding factor
+    pub s0: BigInt,
+    /// the total number of oracle queries
+    pub queries: u64,
+    /// the interval progression, one entry per iteration
+    pub rounds: Vec<Round>,
+}
+
+impl fmt::Display for Recovered {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "m = {}\nm = {:x}\ns0 = {}\niterations = {}\nqueries = {}",
+            self.m, self.m, self.s0, self.rounds.len(), self.queries)
+    }
+}
+
+/// counts the queries to an oracle
+struct Counter<'a, O: PaddingOracle + ?Sized> {
+    /// the oracle that is asked
+    oracle: &'a mut O,
+    /// the public modulus
+    n: BigInt,
+    /// the public exponent
+    e: BigInt,
+    /// the blinded ciphertext c_0 = c * s_0^e, just c while s_0 is searched
+    c0: BigInt,
+    /// the number of queries so far
+    queries: u64,
+}
+
+impl<'a, O: PaddingOracle + ?Sized> Counter<'a, O> {
+    /// ask the oracle if c_0 * s^e is conforming
+    fn conforming(&mut self, s: &BigInt) -> Result<bool, BleichenbacherError> {
+        if self.queries >= MAX_QUERIES {
+            return Err(BleichenbacherError::TooManyQueries);
+        }
+        self.queries += 1;
+        let c = (&self.c0 * s.modpow(&self.e, &self.n)).mod_floor(&self.n);
+        self.oracle.query(&c)
+    }
+
+    /// the smallest conforming s >= start
+    fn search_from(&mut self, start: BigInt) -> Result<BigInt, BleichenbacherError> {
+        let mut s = start;
+        while !self.conforming(&s)? {
+            s += 1u8;
+        }
+        Ok(s)
+    }
+}
+
+/// ceil(a / b)
+fn ceil_div(a: &BigInt, b: &BigInt) -> BigInt {
+    a.div_ceil(b)
+}
+
+/// sort and merge overlapping intervals
+fn union(mut intervals: Vec<(BigInt, BigInt)>) -> Vec<(BigInt, BigInt)> {
+    intervals.sort();
+    let mut merged: Vec<(BigInt, BigInt)> = Vec::with_capacity(intervals.len());
+    for (a, b) in intervals {
+        match merged.last_mut() {
+            Some(last) if a <= last.1 => {
+                if b > last.1 {
+                    last.1 = b;
+                }
+            }
+            _ => merged.push((a, b))
+        }
+    }
+    merged
+}
+
+/// print the intervals as their width in bits
+fn print_round(i: usize, round: &Round) {
+    let widths: Vec<String> = round.intervals.iter()
+        .map(|(a, b)| {
+            let width: BigInt = b - a + 1u8;
+            format!("2^{}", width.bits().saturating_sub(1))
+        })
+        .collect();
+    println!("{:>5}  {:>9}  {:>3}  {:<32}  s = {}", i, round.queries, round.intervals.len(),
+        widths.join(", "), round.s);
+}
+
+/// bleichenbacher's attack
+///
+/// decrypts c under the public key (n, e) with a PKCS#1 v1.5 padding oracle. If c itself is not
+/// conforming, it is blinded with random s_0 first. The verbose output shows every iteration
+/// with the number of queries, the number of intervals and their widths.
+pub fn attack<O: PaddingOracle + ?Sized>(n: &BigInt, e: &BigInt, c: &BigInt, oracle: &mut O, verbose: bool)
+    -> Result<Recovered, BleichenbacherError> {
+    let k = byte_length(n);
+    if k < MIN_PADDING + 3 {
+        return Err(BleichenbacherError::InvalidKey);
+    }
+    let b: BigInt = BigInt::one() << (8 * (k - 2));
+    let b2: BigInt = &b * 2u8;
+    let b3: BigInt = &b * 3u8;
+    let mut counter = Counter { oracle, n: n.clone(), e: e.clone(), c0: c.mod_floor(n), queries: 0 };
+
+    // step 1: blinding
+    let mut s0 = BigInt::one();
+    if !counter.conforming(&s0)? {
+        let mut rng = rand::thread_rng();
+        loop {
+            s0 = rng.gen_bigint_range(&BigInt::from(2), n);
+            if counter.conforming(&s0)? {
+                break;
+            }
+        }
+        counter.c0 = (c * s0.modpow(e, n)).mod_floor(n);
+    }
+    if verbose {
+        println!("k = {k} bytes, B = 2^{}", 8 * (k - 2));
+        println!("step 1: s0 = {s0} after {} queries", counter.queries);
+        seperator();
+        println!("{:>5}  {:>9}  {:>3}  {:<32}  s_i", "i", "queries", "|M|", "interval widths");
+    }
+    let mut intervals: Vec<(BigInt, BigInt)> = vec![(b2.clone(), &b3 - 1u8)];
+    let mut s = BigInt::zero();
+    let mut rounds: Vec<Round> = Vec::new();
+    let mut i = 1;
+    loop {
+        // step 2: search the next s
+        s = if i == 1 {
+            // 2a
+            counter.search_from(ceil_div(n, &b3))?
+        }
+        else if intervals.len() > 1 {
+            // 2b
+            counter.search_from(&s + 1u8)?
+        }
+        else {
+            // 2c
+            let (a, bb) = intervals[0].clone();
+            let mut r = ceil_div(&((&bb * &s - &b2) * 2u8), n);
+            'search: loop {
+                let low = ceil_div(&(&b2 + &r * n), &bb);
+                let high = ceil_div(&(&b3 + &r * n), &a);
+                let mut candidate = low;
+                while candidate < high {
+                    if counter.conforming(&candidate)? {
+                        break 'search candidate;
+                    }
+                    candidate += 1u8;
+                }
+                r += 1u8;
+            }
+        };
+
+        // step 3: narrow the intervals
+        let mut next: Vec<(BigInt, BigInt)> = Vec::new();
+        for (a, bb) in &intervals {
+            let mut r = ceil_div(&(a * &s - &b3 + 1u8), n);
+            let r_max = (bb * &s - &b2).div_floor(n);
+            while r <= r_max {
+                let low = ceil_div(&(&b2 + &r * n), &s).max(a.clone());
+                let high = ((&b3 - 1u8 + &r * n).div_floor(&s)).min(bb.clone());
+                if low <= high {
+                    next.push((low, high));
+                }
+                r += 1u8;
+            }
+        }
+        intervals = union(next);
+        let round = Round { s: s.clone(), queries: counter.queries, intervals: intervals.clone() };
+        if verbose {
+            print_round(i, &round);
+        }
+        rounds.push(round);
+
+        // step 4: done if there is only one candidate left
+        if intervals.len() == 1 && intervals[0].0 == intervals[0].1 {
+            break;
+        }
+        i += 1;
+    }
+    let s0_inv = mod_inverse(&s0, n).expect("s0 is invertible, otherwise c_0 would be 0");
+    let m = (&intervals[0].0 * s0_inv).mod_floor(n);
+    if verbose {
+        seperator();
+        println!("m = a * s0^-1 mod n = {m}");
+        println!("m = {:x}", m);
+        println!("found after {} iterations and {} queries", rounds.len(), counter.queries);
+    }
+    Ok(Recovered { m, s0, queries: counter.queries, rounds })
+}
+
+/// bleichenbacher's attack against a local oracle that knows the private key
+pub fn attack_local(key: &RsaPrivateKey, c: &BigInt, strict: bool, verbose: bool) -> Result<Recovered, BleichenbacherError> {
+    let mut oracle = LocalOracle::new(key.clone(), strict);
+    attack(&key.n, &key.e, c, &mut oracle, verbose)
+}
+
+/// generate a key, encrypt a padded message and decrypt it again with the attack
+pub fn demo(message: &[u8], bits: u64, strict: bool, verbose: bool) -> Result<Recovered, BleichenbacherError> {
+    let key = RsaPrivateKey::generate(bits, &BigInt::from(DEFAULT_E), false)?;
+    let k = byte_length(&key.n);
+    let m = pad(message, k)?;
+    let c = key.public_key().encrypt(&m, false)?;
+    if verbose {
+        println!("{key}");
+        println!("padded m = {:0width$x}", m, width = 2 * k);
+        println!("c = {c}");
+        seperator();
+    }
+    attack_local(&key, &c, strict, verbose)
+}
+
+/// the python representation of the result: (m, queries, [(s_i, queries, intervals)])
+type PyRecovered = (BigInt, u64, Vec<(BigInt, u64, Vec<(BigInt, BigInt)>)>);
+
+/// convert the result for python
+fn to_python(r: Recovered) -> PyRecovered {
+    let rounds = r.rounds.into_iter().map(|round| (round.s, round.queries, round.intervals)).collect();
+    (r.m, r.queries, rounds)
+}
+
+#[pyfunction]
+#[pyo3(name = "attack")]
+#[pyo3(signature=(n, e, c, oracle, verbose = false))]
+/// python wrapper for attack, the oracle is a function that takes a ciphertext and returns a bool
+pub fn py_attack(n: BigInt, e: BigInt, c: BigInt, oracle: PyObject, verbose: bool) -> PyResult<PyRecovered> {
+    let mut oracle = PyOracle::new(oracle);
+    match attack(&n, &e, &c, &mut oracle, verbose) {
+        Ok(r) => Ok(to_python(r)),
+        Err(e) => Err(PyValueError::new_err(e.to_string()))
+    }
+}
+
+#[pyfunction]
+#[pyo3(name = "attack_local")]
+#[pyo3(signature=(key, c, strict = false, verbose = false))]
+/// python wrapper for attack_local
+pub fn py_attack_local(key: RsaPrivateKey, c: BigInt, strict: bool, verbose: bool) -> PyResult<PyRecovered> {
+    match attack_local(&key, &c, strict, verbose) {
+        Ok(r) => Ok(to_python(r)),
+        Err(e) => Err(PyValueError::new_err(e.to_string()))
+    }
+}
+
+#[pyfunction]
+#[pyo3(name = "pad")]
+/// python wrapper for pad
+pub fn py_pad(message: Vec<u8>, k: usize) -> PyResult<BigInt> {
+    match pad(&message, k) {
+        Ok(m) => Ok(m),
+        Err(e) => Err(PyValueError::new_err(e.to_string()))
+    }
+}
+
+#[pyfunction]
+#[pyo3(name = "unpad")]
+/// python wrapper for unpad
+pub fn py_unpad(py: Python, m: BigInt, k: usize) -> PyResult<PyObject> {
+    match unpad(&m, k) {
+        Ok(message) => Ok(PyBytes::new(py, &message).into()),
+        Err(e) => Err(PyValueError::new_err(e.to_string()))
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+pub mod test {
+
+    use super::*;
+
+    use std::str::FromStr;
+
+    fn key() -> RsaPrivateKey {
+        let p = BigInt::from_str("17984814915101041691").unwrap();
+        let q = BigInt::from_str("16741329457535991541").unwrap();
+        RsaPrivateKey::from_primes(&p, &q, &BigInt::from(65537), false).unwrap()
+    }
+
+    #[test]
+    fn test_padding() {
+        let m = pad(b"hello", 32).unwrap();
+        assert_eq!(to_bytes(&m, 32)[..2], [0x00, 0x02]);
+        assert_eq!(unpad(&m, 32), Ok(b"hello".to_vec()));
+        assert_eq!(pad(&[0x41; 22], 32), Err(BleichenbacherError::MessageTooLong(32)));
+        assert!(pad(&[0x41; 21], 32).is_ok());
+        // padding string too short
+        let mut block = vec![0x00, 0x02, 1, 2, 3, 0x00];
+        block.extend_from_slice(&[0x41; 26]);
+        assert_eq!(unpad(&BigInt::from_bytes_be(Sign::Plus, &block), 32), Err(BleichenbacherError::NotConforming));
+    }
+
+    #[test]
+    fn test_attack() {
+        // a ciphertext that is not conforming needs blinding first
+        let key = key();
+        let m = BigInt::from(1234567890);
+        let c = key.public_key().encrypt(&m, false).unwrap();
+        let result = attack_local(&key, &c, false, true).unwrap();
+        assert_eq!(result.m, m);
+        assert_ne!(result.s0, BigInt::one());
+        let blinded = (&m * &result.s0) % &key.n;
+        assert_eq!(result.rounds.last().unwrap().intervals, vec![(blinded.clone(), blinded)]);
+        assert!(result.queries >= result.rounds.len() as u64);
+    }
+
+    #[test]
+    fn test_attack_strict() {
+        // n is just above 2^120, so that one in about 256 numbers starts with 00 02. The strict
+        // oracle also needs the 00 separator in the last 6 bytes, which takes far more queries.
+        let p = BigInt::from_str("1152921504606847009").unwrap();
+        let q = BigInt::from_str("1152921504606847067").unwrap();
+        let key = RsaPrivateKey::from_primes(&p, &q, &BigInt::from(65537), false).unwrap();
+        // a conforming message needs no blinding
+        let m = pad(b"hi", 16).unwrap();
+        let c = key.public_key().encrypt(&m, false).unwrap();
+        let lax = attack_local(&key, &c, false, false).unwrap();
+        let strict = attack_local(&key, &c, true, false).unwrap();
+        assert_eq!(strict.m, m);
+        assert_eq!(strict.s0, BigInt::one());
+        assert_eq!(unpad(&strict.m, 16), Ok(b"hi".to_vec()));
+        assert!(strict.queries > lax.queries);
+    }
+}
@@ -0,0 +1,842 @@
+#![allow(dead_code)]
+/// # elgamal encryption and signatures
+///
+/// Works in any cyclic group with a generator g of order q, like the multiplicative group of a
+/// [GalloisField] or [BigGalloisField] or the points of an [EllipticCurve]. The private key is a
+/// random x in [1, q), the public key is h = g^x.
+///
+/// Encryption of a group element m with a random k is (c1, c2) = (g^k, m * h^k), decryption is
+/// m = c2 * (c1^x)^-1. The ciphertext is malleable: (c1, c2 * t) decrypts to m * t.
+///
+/// A signature for an integer m is (R, s) with R = g^k and s = k^-1 * (m - x * f(R)) mod q, where
+/// f maps group elements to integers: the number itself in a field, the x coordinate on a curve.
+/// It is valid if g^m = h^f(R) * R^s. Two signatures with the same k give away k and then x:
+///
+/// k * (s1 - s2) = m1 - m2 (mod q) and x * f(R) = m1 - k * s1 (mod q)
+///
+/// Curves are written additively, so g^k is the point multiplication k * G there.
+///
+/// ___
+/// Author:     Christoph J. Scherr <software@cscherr.de>
+/// License:    MIT
+/// Source:     <https://git.cscherr.de/PlexSheep/plexcryptool/>
+
+use crate::cplex::printing::seperator;
+use crate::math::biggallois::BigGalloisField;
+use crate::math::ecc::{EllipticCurve, EllipticCurvePoint};
+use crate::math::gallois::GalloisField;
+use crate::math::gcd::mod_inverse;
+use crate::math::group::Group;
+use crate::math::primality::is_prime;
+
+use core::fmt;
+
+use num::Integer;
+use num_bigint::{BigInt, BigUint, RandBigInt};
+use num_traits::{One, ToPrimitive, Zero};
+
+use pyo3::{prelude::*, exceptions::PyValueError};
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// give up recovering a key when a congruence has more solutions than this
+pub const MAX_CANDIDATES: u64 = 1 << 16;
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// errors for elgamal
+pub enum ElGamalError {
+    /// the field needs a prime base
+    NotPrime(BigUint),
+    /// the curve could not be created
+    InvalidCurve(String),
+    /// the order is smaller than 2 or g^order is not the identity
+    InvalidOrder(BigUint),
+    /// the value is not an element of the group
+    InvalidElement(String),
+    /// the private key is not in [1, q)
+    InvalidSecret(BigUint),
+    /// k is not invertible mod q
+    NotInvertible(BigUint),
+    /// the signatures do not share the same k
+    DifferentK,
+    /// no candidate fits the public values
+    NoSolution,
+    /// a congruence has too many solutions to test them all
+    TooManyCandidates(BigUint),
+}
+
+impl fmt::Display for ElGamalError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ElGamalError::NotPrime(p) => write!(f, "{p} is not prime"),
+            ElGamalError::InvalidCurve(e) => write!(f, "invalid curve: {e}"),
+            ElGamalError::InvalidOrder(q) => write!(f, "{q} is not the order of g"),
+            ElGamalError::InvalidElement(e) => write!(f, "{e} is not an element of the group"),
+            ElGamalError::InvalidSecret(x) => write!(f, "{x} is not in [1, q)"),
+            ElGamalError::NotInvertible(k) => write!(f, "{k} is not invertible mod q"),
+            ElGamalError::DifferentK => write!(f, "the signatures were made with different k"),
+            ElGamalError::NoSolution => write!(f, "no candidate fits the public values"),
+            ElGamalError::TooManyCandidates(d) => write!(f, "{d} candidates are too many, at most {MAX_CANDIDATES}"),
+        }
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// a group that elgamal can use
+pub trait ElGamalGroup: Group {
+    /// true if the group is written additively, only used for the verbose output
+    const ADDITIVE: bool = false;
+
+    /// map an element to an integer, for the signature
+    fn to_int(&self, e: &Self::Element) -> BigUint;
+
+    /// check that e is an element of the group
+    fn contains(&self, e: &Self::Element) -> bool;
+}
+
+/// the multiplicative group of the field
+impl ElGamalGroup for GalloisField {
+    fn to_int(&self, e: &u128) -> BigUint {
+        BigUint::from(*e)
+    }
+
+    fn contains(&self, e: &u128) -> bool {
+        *e > 0 && *e < self.base
+    }
+}
+
+/// the multiplicative group of the field
+impl ElGamalGroup for BigGalloisField {
+    fn to_int(&self, e: &BigUint) -> BigUint {
+        e.clone()
+    }
+
+    fn contains(&self, e: &BigUint) -> bool {
+        !e.is_zero() && e < &self.base
+    }
+}
+
+/// the group of points on the curve, f(R) is the x coordinate
+impl ElGamalGroup for EllipticCurve {
+    const ADDITIVE: bool = true;
+
+    fn to_int(&self, e: &EllipticCurvePoint) -> BigUint {
+        match e.coordinates() {
+            Some((r, _)) => BigUint::from(r),
+            None => BigUint::zero()
+        }
+    }
+
+    fn contains(&self, e: &EllipticCurvePoint) -> bool {
+        self.check_point(*e, false)
+    }
+}
+
+/// g^k, printed if verbose
+fn power<G: ElGamalGroup>(group: &G, name: &str, g: &G::Element, k: &BigUint, verbose: bool) -> G::Element {
+    let result = group.exp(g, k);
+    if verbose {
+        if G::ADDITIVE {
+            println!("{name} = {k} * {g} = {result}");
+        }
+        else {
+            println!("{name} = {g}^{k} = {result}");
+        }
+    }
+    result
+}
+
+/// a * b, printed if verbose
+fn product<G: ElGamalGroup>(group: &G, name: &str, a: &G::Element, b: &G::Element, verbose: bool) -> G::Element {
+    let result = group.op(a, b);
+    if verbose {
+        if G::ADDITIVE {
+            println!("{name} = {a} + {b} = {result}");
+        }
+        else {
+            println!("{name} = {a} * {b} = {result}");
+        }
+    }
+    result
+}
+
+/// all x in [0, n) with a * x = b (mod n)
+fn solve_congruence(a: &BigInt, b: &BigInt, n: &BigInt) -> Result<Vec<BigInt>, ElGamalError> {
+    let a = a.mod_floor(n);
+    let b = b.mod_floor(n);
+    let d = a.gcd(n);
+    if d.is_zero() || !b.is_multiple_of(&d) {
+        return Ok(Vec::new());
+    }
+    if d > BigInt::from(MAX_CANDIDATES) {
+        return Err(ElGamalError::TooManyCandidates(d.to_biguint().expect("gcd is positive")));
+    }
+    let nd: BigInt = n / &d;
+    let inv = mod_inverse(&(&a / &d), &nd).expect("a / d and n / d are coprime");
+    let x = ((&b / &d) * inv).mod_floor(&nd);
+    let count = d.to_u64().expect("checked against MAX_CANDIDATES");
+    Ok((0..count).map(|i| &x + &nd * i).collect())
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// a key pair
+pub struct KeyPair<G: ElGamalGroup> {
+    /// the private key x
+    pub x: BigUint,
+    /// the public key h = g^x
+    pub h: G::Element,
+}
+
+impl<G: ElGamalGroup> fmt::Display for KeyPair<G> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "x = {}\nh = {}", self.x, self.h)
+    }
+}
+
+/// an encrypted group element
+pub struct Ciphertext<G: ElGamalGroup> {
+    /// g^k
+    pub c1: G::Element,
+    /// m * h^k
+    pub c2: G::Element,
+}
+
+impl<G: ElGamalGroup> fmt::Display for Ciphertext<G> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "c1 = {}\nc2 = {}", self.c1, self.c2)
+    }
+}
+
+/// a signature (R, s)
+pub struct Signature<G: ElGamalGroup> {
+    /// R = g^k
+    pub r: G::Element,
+    /// s = k^-1 * (m - x * f(R)) mod q
+    pub s: BigUint,
+}
+
+impl<G: ElGamalGroup> fmt::Display for Signature<G> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "R = {}\ns = {}", self.r, self.s)
+    }
+}
+
+/// the domain parameters: a group and a generator g of order q
+pub struct ElGamal<G: ElGamalGroup> {
+    /// the group
+    pub group: G,
+    /// the generator
+    pub g: G::Element,
+    /// the order of g
+    pub order: BigUint,
+}
+
+impl<G: ElGamalGroup> ElGamal<G> {
+    /// make new domain parameters, order must be the order of g or a multiple of it
+    pub fn new(group: G, g: G::Element, order: BigUint) -> Result<Self, ElGamalError> {
+        if !group.contains(&g) {
+            return Err(ElGamalError::InvalidElement(g.to_string()));
+        }
+        if order < BigUint::from(2u8) || group.exp(&g, &order) != group.identity() {
+            return Err(ElGamalError::InvalidOrder(order));
+        }
+        Ok(ElGamal { group, g, order })
+    }
+
+    /// a random integer in [1, q)
+    fn random_exponent(&self) -> BigUint {
+        rand::thread_rng().gen_biguint_range(&BigUint::one(), &self.order)
+    }
+
+    /// check that e is an element of the group
+    fn check_element(&self, e: &G::Element) -> Result<(), ElGamalError> {
+        if !self.group.contains(e) {
+            return Err(ElGamalError::InvalidElement(e.to_string()));
+        }
+        Ok(())
+    }
+
+    /// the key pair for the private key x
+    pub fn key_from_secret(&self, x: &BigUint, verbose: bool) -> Result<KeyPair<G>, ElGamalError> {
+        if x.is_zero() || x >= &self.order {
+            return Err(ElGamalError::InvalidSecret(x.clone()));
+        }
+        let h = power(&self.group, "h", &self.g, x, verbose);
+        Ok(KeyPair { x: x.clone(), h })
+    }
+
+    /// generate a new key pair
+    pub fn keygen(&self, verbose: bool) -> KeyPair<G> {
+        let x = self.random_exponent();
+        if verbose {
+            println!("x = {x}");
+        }
+        self.key_from_secret(&x, verbose).expect("x is in [1, q)")
+    }
+
+    /// encrypt m with a given k
+    pub fn encrypt_with(&self, h: &G::Element, m: &G::Element, k: &BigUint, verbose: bool) -> Result<Ciphertext<G>, ElGamalError> {
+        self.check_element(h)?;
+        self.check_element(m)?;
+        let c1 = power(&self.group, "c1", &self.g, k, verbose);
+        let shared = power(&self.group, "shared", h, k, verbose);
+        let c2 = product(&self.group, "c2", m, &shared, verbose);
+        Ok(Ciphertext { c1, c2 })
+    }
+
+    /// encrypt m with a random k
+    pub fn encrypt(&self, h: &G::Element, m: &G::Element, verbose: bool) -> Result<Ciphertext<G>, ElGamalError> {
+        let k = self.random_exponent();
+        if verbose {
+            println!("k = {k}");
+        }
+        self.encrypt_with(h, m, &k, verbose)
+    }
+
+    /// decrypt with the private key x: m = c2 * (c1^x)^-1
+    pub fn decrypt(&self, x: &BigUint, c: &Ciphertext<G>, verbose: bool) -> Result<G::Element, ElGamalError> {
+        self.check_element(&c.c1)?;
+        self.check_element(&c.c2)?;
+        let shared = power(&self.group, "shared", &c.c1, x, verbose);
+        let inv = self.group.inv(&shared);
+        if verbose {
+            println!("{} = {inv}", if G::ADDITIVE { "-shared" } else { "shared^-1" });
+        }
+        Ok(product(&self.group, "m", &c.c2, &inv, verbose))
+    }
+
+    /// change the plaintext of c from m to m * t without knowing m or the key
+    pub fn maul(&self, c: &Ciphertext<G>, t: &G::Element, verbose: bool) -> Result<Ciphertext<G>, ElGamalError> {
+        self.check_element(t)?;
+        let c2 = product(&self.group, "c2'", &c.c2, t, verbose);
+        Ok(Ciphertext { c1: c.c1.clone(), c2 })
+    }
+
+    /// sign m with a given k, k must be invertible mod q
+    pub fn sign_with(&self, x: &BigUint, m: &BigUint, k: &BigUint, verbose: bool) -> Result<Signature<G>, ElGamalError> {
+        let q = BigInt::from(self.order.clone());
+        let k_inv = match mod_inverse(&BigInt::from(k.clone()), &q) {
+            Ok(inv) => inv,
+            Err(_) => return Err(ElGamalError::NotInvertible(k.clone()))
+        };
+        let r = power(&self.group, "R", &self.g, k, verbose);
+        let fr = BigInt::from(self.group.to_int(&r));
+        let s = (&k_inv * (BigInt::from(m.clone()) - BigInt::from(x.clone()) * &fr)).mod_floor(&q);
+        if verbose {
+            println!("s = k^-1 * (m - x * f(R)) mod q = {k_inv} * ({m} - {x} * {fr}) mod {q} = {s}");
+        }
+        Ok(Signature { r, s: s.to_biguint().expect("s is reduced") })
+    }
+
+    /// a random k in [1, q) that is invertible mod q
+    fn random_invertible(&self) -> BigUint {
+        loop {
+            let k = self.random_exponent();
+            if k.gcd(&self.order).is_one() {
+                return k;
+            }
+        }
+    }
+
+    /// sign m with a random k
+    pub fn sign(&self, x: &BigUint, m: &BigUint, verbose: bool) -> Signature<G> {
+        let k = self.random_invertible();
+        if verbose {
+            println!("k = {k}");
+        }
+        self.sign_with(x, m, &k, verbose).expect("k is invertible")
+    }
+
+    /// check g^m = h^f(R) * R^s
+    pub fn verify(&self, h: &G::Element, m: &BigUint, sig: &Signature<G>, verbose: bool) -> bool {
+        if !self.group.contains(h) || !self.group.contains(&sig.r) || sig.s >= self.order {
+            return false;
+        }
+        let fr = self.group.to_int(&sig.r) % &self.order;
+        let left = power(&self.group, "left", &self.g, m, verbose);
+        let hr = power(&self.group, "a", h, &fr, verbose);
+        let rs = power(&self.group, "b", &sig.r, &sig.s, verbose);
+        let right = product(&self.group, "right", &hr, &rs, verbose);
+        if verbose {
+            println!("{left} {} {right}", if left == right { "==" } else { "!=" });
+        }
+        left == right
+    }
+
+    /// recover k and the private key from two signatures that share the same k
+    pub fn recover_key(&self, h: &G::Element, m1: &BigUint, sig1: &Signature<G>, m2: &BigUint,
+        sig2: &Signature<G>, verbose: bool) -> Result<(BigUint, BigUint), ElGamalError> {
+        if sig1.r != sig2.r {
+            return Err(ElGamalError::DifferentK);
+        }
+        let q = BigInt::from(self.order.clone());
+        let (m1, m2) = (BigInt::from(m1.clone()), BigInt::from(m2.clone()));
+        let (s1, s2) = (BigInt::from(sig1.s.clone()), BigInt::from(sig2.s.clone()));
+        let fr = BigInt::from(self.group.to_int(&sig1.r));
+        let ks = solve_congruence(&(&s1 - &s2), &(&m1 - &m2), &q)?;
+        if verbose {
+            println!("k * (s1 - s2) = m1 - m2 (mod q): {} candidates", ks.len());
+        }
+        for k in ks {
+            let k = k.to_biguint().expect("k is reduced");
+            if power(&self.group, "R'", &self.g, &k, verbose) != sig1.r {
+                continue;
+            }
+            let xs = solve_congruence(&fr, &(&m1 - BigInt::from(k.clone()) * &s1), &q)?;
+            if verbose {
+                println!("x * f(R) = m1 - k * s1 (mod q): {} candidates", xs.len());
+            }
+            for x in xs {
+                let x = x.to_biguint().expect("x is reduced");
+                if &power(&self.group, "h'", &self.g, &x, verbose) == h {
+                    if verbose {
+                        seperator();
+                        println!("k = {k}\nx = {x}");
+                    }
+                    return Ok((k, x));
+                }
+            }
+        }
+        Err(ElGamalError::NoSolution)
+    }
+}
+
+/// elgamal in the multiplicative group of the prime field F_p
+///
+/// without an order, g is assumed to be a generator of order p - 1
+pub fn field_scheme(p: &BigUint, g: &BigUint, order: Option<BigUint>) -> Result<ElGamal<BigGalloisField>, ElGamalError> {
+    if !is_prime(&BigInt::from(p.clone()), false) {
+        return Err(ElGamalError::NotPrime(p.clone()));
+    }
+    let field = BigGalloisField::new(p.clone(), false, None);
+    let order = order.unwrap_or_else(|| p - 1u8);
+    ElGamal::new(field, g.clone(), order)
+}
+
+/// elgamal on the curve y² = x³ + ax + b over F_p with a point g of the given order
+pub fn curve_scheme(p: u128, a: i128, b: i128, g: (u128, u128), order: BigUint) -> Result<ElGamal<EllipticCurve>, ElGamalError> {
+    let field = GalloisField::new(p, false, None);
+    if !field.prime_base {
+        return Err(ElGamalError::NotPrime(BigUint::from(p)));
+    }
+    let curve = match EllipticCurve::new(field, a, b, false) {
+        Ok(c) => c,
+        Err(e) => return Err(ElGamalError::InvalidCurve(e))
+    };
+    let g = match curve.new_point(g.0, g.1) {
+        Ok(g) => g,
+        Err(_) => return Err(ElGamalError::InvalidElement(format!("({}, {})", g.0, g.1)))
+    };
+    ElGamal::new(curve, g, order)
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// the result of the demo
+pub struct Demo<G: ElGamalGroup> {
+    /// the generated keys
+    pub key: KeyPair<G>,
+    /// the encrypted message
+    pub ciphertext: Ciphertext<G>,
+    /// the decrypted message
+    pub plaintext: G::Element,
+    /// the signature for f(m)
+    pub signature: Signature<G>,
+    /// true if the signature is valid
+    pub valid: bool,
+}
+
+impl<G: ElGamalGroup> fmt::Display for Demo<G> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}\n{}\nm = {}\n{}\nvalid = {}", self.key, self.ciphertext, self.plaintext,
+            self.signature, self.valid)
+    }
+}
+
+/// generate a key, encrypt and decrypt m, then sign f(m) and verify the signature
+pub fn demo<G: ElGamalGroup>(scheme: &ElGamal<G>, m: &G::Element, verbose: bool) -> Result<Demo<G>, ElGamalError> {
+    let key = scheme.keygen(verbose);
+    if verbose {
+        seperator();
+    }
+    let ciphertext = scheme.encrypt(&key.h, m, verbose)?;
+    if verbose {
+        seperator();
+    }
+    let plaintext = scheme.decrypt(&key.x, &ciphertext, verbose)?;
+    if verbose {
+        seperator();
+    }
+    let message = scheme.group.to_int(m);
+    let signature = scheme.sign(&key.x, &message, verbose);
+    if verbose {
+        seperator();
+    }
+    let valid = scheme.verify(&key.h, &message, &signature, verbose);
+    Ok(Demo { key, ciphertext, plaintext, signature, valid })
+}
+
+/// the result of the malleability demo
+pub struct Malleability<G: ElGamalGroup> {
+    /// the encryption of m
+    pub original: Ciphertext<G>,
+    /// the mauled ciphertext
+    pub mauled: Ciphertext<G>,
+    /// the decryption of the mauled ciphertext, m * t
+    pub plaintext: G::Element,
+}
+
+impl<G: ElGamalGroup> fmt::Display for Malleability<G> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}\nmauled:\n{}\ndecrypted = {}", self.original, self.mauled, self.plaintext)
+    }
+}
+
+/// encrypt m, multiply the ciphertext with t and decrypt it to m * t
+pub fn malleability<G: ElGamalGroup>(scheme: &ElGamal<G>, m: &G::Element, t: &G::Element, verbose: bool) -> Result<Malleability<G>, ElGamalError> {
+    let key = scheme.keygen(false);
+    let original = scheme.encrypt(&key.h, m, verbose)?;
+    if verbose {
+        seperator();
+        println!("without the key, an attacker changes c2:");
+    }
+    let mauled = scheme.maul(&original, t, verbose)?;
+    if verbose {
+        seperator();
+    }
+    let plaintext = scheme.decrypt(&key.x, &mauled, verbose)?;
+    Ok(Malleability { original, mauled, plaintext })
+}
+
+/// the result of the k reuse demo
+pub struct Forgery<G: ElGamalGroup> {
+    /// the recovered k
+    pub k: BigUint,
+    /// the recovered private key
+    pub x: BigUint,
+    /// the forged signature
+    pub signature: Signature<G>,
+    /// true if the forged signature is valid
+    pub valid: bool,
+}
+
+impl<G: ElGamalGroup> fmt::Display for Forgery<G> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "k = {}\nx = {}\nforged:\n{}\nvalid = {}", self.k, self.x, self.signature, self.valid)
+    }
+}
+
+/// sign m1 and m2 with the same k, recover the key from the signatures and forge one for m3
+pub fn reuse<G: ElGamalGroup>(scheme: &ElGamal<G>, m1: &BigUint, m2: &BigUint, m3: &BigUint, verbose: bool) -> Result<Forgery<G>, ElGamalError> {
+    let key = scheme.keygen(false);
+    let k = scheme.random_invertible();
+    if verbose {
+        println!("h = {}\nthe signer uses k = {k} twice", key.h);
+    }
+    let sig1 = scheme.sign_with(&key.x, m1, &k, verbose)?;
+    let sig2 = scheme.sign_with(&key.x, m2, &k, verbose)?;
+    if verbose {
+        seperator();
+    }
+    let (k, x) = scheme.recover_key(&key.h, m1, &sig1, m2, &sig2, verbose)?;
+    if verbose {
+        seperator();
+    }
+    let signature = scheme.sign(&x, m3, verbose);
+    let valid = scheme.verify(&key.h, m3, &signature, verbose);
+    Ok(Forgery { k, x, signature, valid })
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[pyclass]
+/// elgamal in the multiplicative group of a prime field
+pub struct ElGamalField {
+    /// the domain parameters
+    scheme: ElGamal<BigGalloisField>,
+}
+
+#[pymethods]
+/// python wrappers for elgamal in a prime field
+impl ElGamalField {
+    #[new]
+    #[pyo3(signature=(p, g, order = None))]
+    /// g has order p - 1 if no order is given
+    pub fn py_new(p: BigUint, g: BigUint, order: Option<BigUint>) -> PyResult<Self> {
+        match field_scheme(&p, &g, order) {
+            Ok(scheme) => Ok(ElGamalField { scheme }),
+            Err(e) => Err(PyValueError::new_err(e.to_string()))
+        }
+    }
+
+    #[getter]
+    /// the prime
+    pub fn p(&self) -> BigUint {
+        self.scheme.group.base.clone()
+    }
+
+    #[getter]
+    /// the generator
+    pub fn g(&self) -> BigUint {
+        self.scheme.g.clone()
+    }
+
+    #[getter]
+    /// the order of g
+    pub fn order(&self) -> BigUint {
+        self.scheme.order.clone()
+    }
+
+    #[pyo3(name="keygen")]
+    #[pyo3(signature=(verbose = false))]
+    /// generate a key pair (x, h)
+    pub fn py_keygen(&self, verbose: bool) -> (BigUint, BigUint) {
+        let key = self.scheme.keygen(verbose);
+        (key.x, key.h)
+    }
+
+    #[pyo3(name="encrypt")]
+    #[pyo3(signature=(h, m, k = None, verbose = false))]
+    /// encrypt m to (c1, c2), with a random k if none is given
+    pub fn py_encrypt(&self, h: BigUint, m: BigUint, k: Option<BigUint>, verbose: bool) -> PyResult<(BigUint, BigUint)> {
+        let result = match k {
+            Some(k) => self.scheme.encrypt_with(&h, &m, &k, verbose),
+            None => self.scheme.encrypt(&h, &m, verbose)
+        };
+        match result {
+            Ok(c) => Ok((c.c1, c.c2)),
+            Err(e) => Err(PyValueError::new_err(e.to_string()))
+        }
+    }
+
+    #[pyo3(name="decrypt")]
+    #[pyo3(signature=(x, c1, c2, verbose = false))]
+    /// decrypt (c1, c2)
+    pub fn py_decrypt(&self, x: BigUint, c1: BigUint, c2: BigUint, verbose: bool) -> PyResult<BigUint> {
+        match self.scheme.decrypt(&x, &Ciphertext { c1, c2 }, verbose) {
+            Ok(m) => Ok(m),
+            Err(e) => Err(PyValueError::new_err(e.to_string()))
+        }
+    }
+
+    #[pyo3(name="maul")]
+    #[pyo3(signature=(c1, c2, t, verbose = false))]
+    /// change (c1, c2) so that it decrypts to m * t
+    pub fn py_maul(&self, c1: BigUint, c2: BigUint, t: BigUint, verbose: bool) -> PyResult<(BigUint, BigUint)> {
+        match self.scheme.maul(&Ciphertext { c1, c2 }, &t, verbose) {
+            Ok(c) => Ok((c.c1, c.c2)),
+            Err(e) => Err(PyValueError::new_err(e.to_string()))
+        }
+    }
+
+    #[pyo3(name="sign")]
+    #[pyo3(signature=(x, m, k = None, verbose = false))]
+    /// sign m to (r, s), with a random k if none is given
+    pub fn py_sign(&self, x: BigUint, m: BigUint, k: Option<BigUint>, verbose: bool) -> PyResult<(BigUint, BigUint)> {
+        let sig = match k {
+            Some(k) => match self.scheme.sign_with(&x, &m, &k, verbose) {
+                Ok(sig) => sig,
+                Err(e) => return Err(PyValueError::new_err(e.to_string()))
+            },
+            None => self.scheme.sign(&x, &m, verbose)
+        };
+        Ok((sig.r, sig.s))
+    }
+
+    #[pyo3(name="verify")]
+    #[pyo3(signature=(h, m, r, s, verbose = false))]
+    /// verify the signature (r, s) for m
+    pub fn py_verify(&self, h: BigUint, m: BigUint, r: BigUint, s: BigUint, verbose: bool) -> bool {
+        self.scheme.verify(&h, &m, &Signature { r, s }, verbose)
+    }
+
+    #[pyo3(name="recover_key")]
+    #[pyo3(signature=(h, m1, sig1, m2, sig2, verbose = false))]
+    /// recover (k, x) from two signatures (r, s1) and (r, s2) with the same k
+    pub fn py_recover_key(&self, h: BigUint, m1: BigUint, sig1: (BigUint, BigUint), m2: BigUint,
+        sig2: (BigUint, BigUint), verbose: bool) -> PyResult<(BigUint, BigUint)> {
+        let sig1 = Signature { r: sig1.0, s: sig1.1 };
+        let sig2 = Signature { r: sig2.0, s: sig2.1 };
+        match self.scheme.recover_key(&h, &m1, &sig1, &m2, &sig2, verbose) {
+            Ok(r) => Ok(r),
+            Err(e) => Err(PyValueError::new_err(e.to_string()))
+        }
+    }
+}
+
+#[pyclass]
+/// elgamal on an elliptic curve
+pub struct ElGamalCurve {
+    /// the domain parameters
+    scheme: ElGamal<EllipticCurve>,
+}
+
+#[pymethods]
+/// python wrappers for elgamal on a curve
+impl ElGamalCurve {
+    #[new]
+    /// g is a point of the curve with the given order
+    pub fn py_new(curve: EllipticCurve, g: EllipticCurvePoint, order: BigUint) -> PyResult<Self> {
+        match ElGamal::new(curve, g, order) {
+            Ok(scheme) => Ok(ElGamalCurve { scheme }),
+            Err(e) => Err(PyValueError::new_err(e.to_string()))
+        }
+    }
+
+    #[getter]
+    /// the generator
+    pub fn g(&self) -> EllipticCurvePoint {
+        self.scheme.g
+    }
+
+    #[getter]
+    /// the order of g
+    pub fn order(&self) -> BigUint {
+        self.scheme.order.clone()
+    }
+
+    #[pyo3(name="keygen")]
+    #[pyo3(signature=(verbose = false))]
+    /// generate a key pair (x, H)
+    pub fn py_keygen(&self, verbose: bool) -> (BigUint, EllipticCurvePoint) {
+        let key = self.scheme.keygen(verbose);
+        (key.x, key.h)
+    }
+
+    #[pyo3(name="encrypt")]
+    #[pyo3(signature=(h, m, k = None, verbose = false))]
+    /// encrypt the point m to (C1, C2), with a random k if none is given
+    pub fn py_encrypt(&self, h: EllipticCurvePoint, m: EllipticCurvePoint, k: Option<BigUint>, verbose: bool)
+        -> PyResult<(EllipticCurvePoint, EllipticCurvePoint)> {
+        let result = match k {
+            Some(k) => self.scheme.encrypt_with(&h, &m, &k, verbose),
+            None => self.scheme.encrypt(&h, &m, verbose)
+        };
+        match result {
+            Ok(c) => Ok((c.c1, c.c2)),
+            Err(e) => Err(PyValueError::new_err(e.to_string()))
+        }
+    }
+
+    #[pyo3(name="decrypt")]
+    #[pyo3(signature=(x, c1, c2, verbose = false))]
+    /// decrypt (C1, C2)
+    pub fn py_decrypt(&self, x: BigUint, c1: EllipticCurvePoint, c2: EllipticCurvePoint, verbose: bool)
+        -> PyResult<EllipticCurvePoint> {
+        match self.scheme.decrypt(&x, &Ciphertext { c1, c2 }, verbose) {
+            Ok(m) => Ok(m),
+            Err(e) => Err(PyValueError::new_err(e.to_string()))
+        }
+    }
+
+    #[pyo3(name="maul")]
+    #[pyo3(signature=(c1, c2, t, verbose = false))]
+    /// change (C1, C2) so that it decrypts to M + T
+    pub fn py_maul(&self, c1: EllipticCurvePoint, c2: EllipticCurvePoint, t: EllipticCurvePoint, verbose: bool)
+        -> PyResult<(EllipticCurvePoint, EllipticCurvePoint)> {
+        match self.scheme.maul(&Ciphertext { c1, c2 }, &t, verbose) {
+            Ok(c) => Ok((c.c1, c.c2)),
+            Err(e) => Err(PyValueError::new_err(e.to_string()))
+        }
+    }
+
+    #[pyo3(name="sign")]
+    #[pyo3(signature=(x, m, k = None, verbose = false))]
+    /// sign m to (R, s), with a random k if none is given
+    pub fn py_sign(&self, x: BigUint, m: BigUint, k: Option<BigUint>, verbose: bool) -> PyResult<(EllipticCurvePoint, BigUint)> {
+        let sig = match k {
+            Some(k) => match self.scheme.sign_with(&x, &m, &k, verbose) {
+                Ok(sig) => sig,
+                Err(e) => return Err(PyValueError::new_err(e.to_string()))
+            },
+            None => self.scheme.sign(&x, &m, verbose)
+        };
+        Ok((sig.r, sig.s))
+    }
+
+    #[pyo3(name="verify")]
+    #[pyo3(signature=(h, m, r, s, verbose = false))]
+    /// verify the signature (R, s) for m
+    pub fn py_verify(&self, h: EllipticCurvePoint, m: BigUint, r: EllipticCurvePoint, s: BigUint, verbose: bool) -> bool {
+        self.scheme.verify(&h, &m, &Signature { r, s }, verbose)
+    }
+
+    #[pyo3(name="recover_key")]
+    #[pyo3(signature=(h, m1, sig1, m2, sig2, verbose = false))]
+    /// recover (k, x) from two signatures (R, s1) and (R, s2) with the same k
+    pub fn py_recover_key(&self, h: EllipticCurvePoint, m1: BigUint, sig1: (EllipticCurvePoint, BigUint),
+        m2: BigUint, sig2: (EllipticCurvePoint, BigUint), verbose: bool) -> PyResult<(BigUint, BigUint)> {
+        let sig1 = Signature { r: sig1.0, s: sig1.1 };
+        let sig2 = Signature { r: sig2.0, s: sig2.1 };
+        match self.scheme.recover_key(&h, &m1, &sig1, &m2, &sig2, verbose) {
+            Ok(r) => Ok(r),
+            Err(e) => Err(PyValueError::new_err(e.to_string()))
+        }
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+pub mod test {
+
+    use super::*;
+
+    fn big(n: u64) -> BigUint {
+        BigUint::from(n)
+    }
+
+    #[test]
+    fn test_elgamal_field() {
+        // p = 467 with the generator 2
+        let scheme = field_scheme(&big(467), &big(2), None).unwrap();
+        let key = scheme.key_from_secret(&big(127), true).unwrap();
+        assert_eq!(key.h, big(132));
+        let c = scheme.encrypt_with(&key.h, &big(100), &big(213), true).unwrap();
+        assert_eq!(scheme.decrypt(&key.x, &c, true), Ok(big(100)));
+        let mauled = scheme.maul(&c, &big(3), true).unwrap();
+        assert_eq!(scheme.decrypt(&key.x, &mauled, false), Ok(big(300)));
+        let sig = scheme.sign_with(&key.x, &big(100), &big(213), true).unwrap();
+        assert_eq!((sig.r.clone(), sig.s.clone()), (big(29), big(51)));
+        assert!(scheme.verify(&key.h, &big(100), &sig, true));
+        assert!(!scheme.verify(&key.h, &big(101), &sig, false));
+        assert!(scheme.sign_with(&key.x, &big(100), &big(2), false).is_err());
+        assert_eq!(field_scheme(&big(467), &big(2), Some(big(233))).err(), Some(ElGamalError::InvalidOrder(big(233))));
+        assert_eq!(field_scheme(&big(468), &big(2), None).err(), Some(ElGamalError::NotPrime(big(468))));
+        assert!(scheme.key_from_secret(&big(466), false).is_err());
+    }
+
+    #[test]
+    fn test_elgamal_curve() {
+        // y² = x³ + 2x + 2 over F_17, (5, 1) generates all 19 points
+        let scheme = curve_scheme(17, 2, 2, (5, 1), big(19)).unwrap();
+        let key = scheme.keygen(true);
+        let m = scheme.group.new_point(6, 3).unwrap();
+        let c = scheme.encrypt(&key.h, &m, true).unwrap();
+        assert_eq!(scheme.decrypt(&key.x, &c, true).unwrap(), m);
+        let t = scheme.group.new_point(10, 6).unwrap();
+        let mauled = scheme.maul(&c, &t, false).unwrap();
+        assert_eq!(scheme.decrypt(&key.x, &mauled, false).unwrap(), scheme.group.op(&m, &t));
+        let sig = scheme.sign(&key.x, &big(7), true);
+        assert!(scheme.verify(&key.h, &big(7), &sig, true));
+        assert!(curve_scheme(17, 2, 2, (5, 1), big(18)).is_err());
+        assert!(curve_scheme(17, 2, 2, (5, 2), big(19)).is_err());
+    }
+
+    #[test]
+    fn test_reuse() {
+        let scheme = field_scheme(&big(467), &big(2), None).unwrap();
+        let forgery = reuse(&scheme, &big(100), &big(200), &big(300), true).unwrap();
+        assert!(forgery.valid);
+        let scheme = curve_scheme(17, 2, 2, (5, 1), big(19)).unwrap();
+        let forgery = reuse(&scheme, &big(3), &big(5), &big(11), true).unwrap();
+        assert!(forgery.valid);
+        let key = scheme.keygen(false);
+        let sig1 = scheme.sign_with(&key.x, &big(3), &big(2), false).unwrap();
+        let sig2 = scheme.sign_with(&key.x, &big(5), &big(4), false).unwrap();
+        assert_eq!(scheme.recover_key(&key.h, &big(3), &sig1, &big(5), &sig2, false).err(), Some(ElGamalError::DifferentK));
+    }
+}
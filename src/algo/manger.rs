@@ -0,0 +1,320 @@
+#![allow(dead_code)]
+/// # manger's attack on RSA-OAEP
+///
+/// An OAEP encoded message of a key with k bytes starts with a 00 byte, so m < B = 2^(8(k-1)).
+/// If the decryption leaks whether that first byte is 00, for example through a different error
+/// or a timing difference, we can ask if f * m mod n < B for any f by sending f^e * c. Requires
+/// 2B < n, which only fails if the bit length of n is 1 mod 8.
+///
+/// 0. check that c itself decrypts to a number below B
+/// 1. double f_1 = 2, 4, 8, ... until f_1 * m >= B, so f_1 * m is in [B, 2B)
+/// 2. f_2 = (floor((n + B) / B) + j) * f_1 / 2 for j = 0, 1, ... until f_2 * m < n + B, so
+///    f_2 * m is in [n, n + B)
+/// 3. binary search: with m in [m_min, m_max], choose f_3 so that f_3 * m_min is just above
+///    i * n + B for the boundary i * n + B, every query halves the interval
+///
+/// This takes about log2(n) queries for step 3 and a handful more for the first two steps.
+///
+/// Source: J. Manger, "A Chosen Ciphertext Attack on RSA Optimal Asymmetric Encryption Padding
+/// (OAEP) as Standardized in PKCS #1 v2.0", CRYPTO 2001
+///
+/// ___
+/// Author:     Christoph J. Scherr <software@cscherr.de>
+/// License:    MIT
+/// Source:     <https://git.cscherr.de/PlexSheep/plexcryptool/>
+
+use crate::cplex::printing::seperator;
+use crate::algo::oaep::{self, HashFunction, OaepError};
+use crate::algo::rsa::{RsaPrivateKey, DEFAULT_E};
+
+use core::fmt;
+
+use num::Integer;
+use num_bigint::BigInt;
+use num_traits::One;
+
+use pyo3::{prelude::*, exceptions::PyValueError};
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// give up after this many oracle queries
+pub const MAX_QUERIES: u64 = 1 << 20;
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// errors for manger's attack
+pub enum MangerError {
+    /// the attack needs 2B < n
+    InvalidKey,
+    /// c does not decrypt to a number below B
+    NotBelowB,
+    /// the oracle was queried more than MAX_QUERIES times, it is probably not consistent
+    TooManyQueries,
+    /// encrypting the demo message failed
+    Oaep(OaepError),
+}
+
+impl fmt::Display for MangerError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MangerError::InvalidKey => write!(f, "the attack needs 2B < n, the bit length of n must not be 1 mod 8"),
+            MangerError::NotBelowB => write!(f, "c does not decrypt to a number below B"),
+            MangerError::TooManyQueries => write!(f, "gave up after {MAX_QUERIES} oracle queries"),
+            MangerError::Oaep(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl From<OaepError> for MangerError {
+    fn from(e: OaepError) -> Self {
+        MangerError::Oaep(e)
+    }
+}
+
+/// an oracle that tells if a ciphertext decrypts to a number below B
+pub trait ThresholdOracle {
+    /// true if c^d mod n < B, i.e. the first byte of the decryption is 00
+    fn query(&mut self, c: &BigInt) -> bool;
+}
+
+/// an oracle that decrypts with a private key and checks the first byte
+pub struct LocalOracle {
+    /// the key to decrypt with
+    key: RsaPrivateKey,
+    /// the threshold B = 2^(8 * (k - 1))
+    b: BigInt,
+}
+
+impl LocalOracle {
+    /// make an oracle from a private key
+    pub fn new(key: RsaPrivateKey) -> Self {
+        let k = oaep::byte_length(&key.n);
+        let b = BigInt::one() << (8 * (k - 1));
+        LocalOracle { key, b }
+    }
+}
+
+impl ThresholdOracle for LocalOracle {
+    fn query(&mut self, c: &BigInt) -> bool {
+        c.modpow(&self.key.d, &self.key.n) < self.b
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// the result of manger's attack
+pub struct Recovered {
+    /// the decrypted block
+    pub m: BigInt,
+    /// the number of oracle queries in steps 1, 2 and 3
+    pub queries: [u64; 3],
+}
+
+impl Recovered {
+    /// the total number of oracle queries
+    pub fn total_queries(&self) -> u64 {
+        self.queries.iter().sum()
+    }
+}
+
+impl fmt::Display for Recovered {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "m = {}\nm = {:x}\nqueries = {} ({} + {} + {})", self.m, self.m, self.total_queries(),
+            self.queries[0], self.queries[1], self.queries[2])
+    }
+}
+
+/// counts the queries to an oracle
+struct Counter<'a, O: ThresholdOracle + ?Sized> {
+    /// the oracle that is asked
+    oracle: &'a mut O,
+    /// the public modulus
+    n: BigInt,
+    /// the public exponent
+    e: BigInt,
+    /// the attacked ciphertext
+    c: BigInt,
+    /// the number of queries so far
+    queries: u64,
+    /// give up after this many queries
+    max_queries: u64,
+}
+
+impl<'a, O: ThresholdOracle + ?Sized> Counter<'a, O> {
+    /// ask the oracle if f * m mod n < B
+    fn below(&mut self, f: &BigInt) -> Result<bool, MangerError> {
+        if self.queries >= self.max_queries {
+            return Err(MangerError::TooManyQueries);
+        }
+        self.queries += 1;
+        let c = (&self.c * f.modpow(&self.e, &self.n)).mod_floor(&self.n);
+        Ok(self.oracle.query(&c))
+    }
+}
+
+/// manger's attack
+///
+/// decrypts c under the public key (n, e) with an oracle that tells if a ciphertext decrypts to
+/// a number below B. The verbose output shows f_1, f_2 and every step of the binary search with
+/// the width of the remaining interval.
+pub fn attack<O: ThresholdOracle + ?Sized>(n: &BigInt, e: &BigInt, c: &BigInt, oracle: &mut O, verbose: bool)
+    -> Result<Recovered, MangerError> {
+    attack_limited(n, e, c, oracle, MAX_QUERIES, verbose)
+}
+
+/// manger's attack with a custom limit for the oracle queries
+fn attack_limited<O: ThresholdOracle + ?Sized>(n: &BigInt, e: &BigInt, c: &BigInt, oracle: &mut O,
+    max_queries: u64, verbose: bool) -> Result<Recovered, MangerError> {
+    let k = oaep::byte_length(n);
+    let b: BigInt = BigInt::one() << (8 * (k.max(1) - 1));
+    if &b * 2u8 >= *n {
+        return Err(MangerError::InvalidKey);
+    }
+    let mut counter = Counter { oracle, n: n.clone(), e: e.clone(), c: c.mod_floor(n), queries: 0, max_queries };
+    if !counter.below(&BigInt::one())? {
+        return Err(MangerError::NotBelowB);
+    }
+    if verbose {
+        println!("k = {k} bytes, B = 2^{}", 8 * (k - 1));
+    }
+
+    // step 1: f_1 * m in [B, 2B)
+    let mut f1 = BigInt::from(2);
+    while counter.below(&f1)? {
+        f1 *= 2u8;
+    }
+    let step1 = counter.queries;
+    if verbose {
+        println!("step 1: f1 = {f1} after {step1} queries, f1 * m in [B, 2B)");
+    }
+
+    // step 2: f_2 * m in [n, n + B)
+    let half: BigInt = &f1 / 2u8;
+    let mut f2: BigInt = (n + &b).div_floor(&b) * &half;
+    while !counter.below(&f2)? {
+        f2 += &half;
+    }
+    let step2 = counter.queries - step1;
+    if verbose {
+        println!("step 2: f2 = {f2} after {step2} queries, f2 * m in [n, n + B)");
+        seperator();
+    }
+
+    // step 3: binary search
+    let mut m_min = n.div_ceil(&f2);
+    let mut m_max = (n + &b).div_floor(&f2);
+    while m_min < m_max {
+        let f_tmp = (&b * 2u8).div_floor(&(&m_max - &m_min));
+        let i = (&f_tmp * &m_min).div_floor(n);
+        let boundary: BigInt = &i * n + &b;
+        let f3 = (&i * n).div_ceil(&m_min);
+        if counter.below(&f3)? {
+            m_max = boundary.div_floor(&f3);
+        }
+        else {
+            m_min = boundary.div_ceil(&f3);
+        }
+        if verbose {
+            let width: BigInt = &m_max - &m_min + 1u8;
+            println!("{:>5}  f3 = {:<48}  width 2^{}", counter.queries - step1 - step2, f3.to_string(),
+                width.bits().saturating_sub(1));
+        }
+    }
+    let step3 = counter.queries - step1 - step2;
+    let result = Recovered { m: m_min, queries: [step1, step2, step3] };
+    if verbose {
+        seperator();
+        println!("{result}");
+    }
+    Ok(result)
+}
+
+/// manger's attack against a local oracle that knows the private key
+pub fn attack_local(key: &RsaPrivateKey, c: &BigInt, verbose: bool) -> Result<Recovered, MangerError> {
+    let mut oracle = LocalOracle::new(key.clone());
+    attack(&key.n, &key.e, c, &mut oracle, verbose)
+}
+
+/// generate a key, encrypt a message with OAEP and decrypt it again with the attack
+///
+/// returns the result of the attack and the message that was decoded from it
+pub fn demo(message: &[u8], bits: u64, hash: HashFunction, verbose: bool) -> Result<(Recovered, Vec<u8>), MangerError> {
+    let key = RsaPrivateKey::generate(bits, &BigInt::from(DEFAULT_E), false).map_err(OaepError::from)?;
+    let c = key.public_key().encrypt_oaep(message, b"", hash, false)?;
+    if verbose {
+        println!("{key}");
+        println!("c = {c}");
+        seperator();
+    }
+    let result = attack_local(&key, &c, verbose)?;
+    let k = oaep::byte_length(&key.n);
+    let decoded = oaep::decode(&oaep::to_bytes(&result.m, k), b"", k, hash, false)?;
+    Ok((result, decoded))
+}
+
+#[pyfunction]
+#[pyo3(name = "attack_local")]
+#[pyo3(signature=(key, c, verbose = false))]
+/// python wrapper for attack_local, returns m and the total number of queries
+pub fn py_attack_local(key: RsaPrivateKey, c: BigInt, verbose: bool) -> PyResult<(BigInt, u64)> {
+    match attack_local(&key, &c, verbose) {
+        Ok(r) => {
+            let queries = r.total_queries();
+            Ok((r.m, queries))
+        }
+        Err(e) => Err(PyValueError::new_err(e.to_string()))
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+pub mod test {
+
+    use super::*;
+
+    use std::str::FromStr;
+
+    fn key() -> RsaPrivateKey {
+        let p = BigInt::from_str("1364261455040934983979847334928781619607152338509874157350571").unwrap();
+        let q = BigInt::from_str("1563492944513045043352607399343903131718626902938967747180247").unwrap();
+        RsaPrivateKey::from_primes(&p, &q, &BigInt::from(65537), false).unwrap()
+    }
+
+    #[test]
+    fn test_attack() {
+        let key = key();
+        let c = key.public_key().encrypt_oaep(b"manger", b"", HashFunction::Sha1, false).unwrap();
+        let result = attack_local(&key, &c, true).unwrap();
+        assert_eq!(result.m, c.modpow(&key.d, &key.n));
+        assert!(result.total_queries() < 2 * key.n.bits());
+        assert_eq!(key.decrypt_oaep(&c, b"", HashFunction::Sha1, false), Ok(b"manger".to_vec()));
+        // 2^396 is not below B = 2^392
+        let c = key.public_key().encrypt(&(BigInt::one() << 396), false).unwrap();
+        assert_eq!(attack_local(&key, &c, false), Err(MangerError::NotBelowB));
+    }
+
+    /// says yes to the first query and no to every other one
+    struct Liar {
+        /// the number of queries so far
+        queries: u64,
+    }
+
+    impl ThresholdOracle for Liar {
+        fn query(&mut self, _c: &BigInt) -> bool {
+            self.queries += 1;
+            self.queries == 1
+        }
+    }
+
+    #[test]
+    fn test_custom_oracle() {
+        let (n, e, c) = (BigInt::from(1003), BigInt::from(3), BigInt::from(42));
+        let mut liar = Liar { queries: 0 };
+        assert_eq!(attack_limited(&n, &e, &c, &mut liar, 1000, false), Err(MangerError::TooManyQueries));
+        assert_eq!(liar.queries, 1000);
+        // n = 257 has 9 bits, so 2B = 512 > n
+        let mut liar = Liar { queries: 0 };
+        assert_eq!(attack(&BigInt::from(257), &e, &c, &mut liar, false), Err(MangerError::InvalidKey));
+        assert_eq!(liar.queries, 0);
+    }
+}
@@ -9,3 +9,12 @@
 /// License:    MIT
 /// Source:     <https://git.cscherr.de/PlexSheep/plexcryptool/>
 pub mod feistel0;
+pub mod wiener;
+pub mod rsa;
+pub mod rsa_attacks;
+pub mod bleichenbacher;
+pub mod oaep;
+pub mod manger;
+pub mod rabin;
+pub mod paillier;
+pub mod elgamal;
@@ -0,0 +1,377 @@
+#![allow(dead_code)]
+/// # RSA-OAEP
+///
+/// Optimal asymmetric encryption padding as in RFC 8017. With the hash length h and a key of k
+/// bytes, a message M is encoded as
+///
+/// DB = Hash(L) || 00 ... 00 || 01 || M            (k - h - 1 bytes)
+/// maskedDB = DB ^ MGF1(seed, k - h - 1)
+/// maskedSeed = seed ^ MGF1(maskedDB, h)
+/// EM = 00 || maskedSeed || maskedDB
+///
+/// where the seed are h random bytes and L is an optional label. MGF1 is the mask generation
+/// function Hash(Z || 00000000) || Hash(Z || 00000001) || ... of RFC 8017.
+///
+/// ___
+/// Author:     Christoph J. Scherr <software@cscherr.de>
+/// License:    MIT
+/// Source:     <https://git.cscherr.de/PlexSheep/plexcryptool/>
+
+use crate::cplex::printing::seperator;
+use crate::algo::rsa::{self, RsaError};
+
+use core::fmt;
+
+use num_bigint::{BigInt, Sign};
+
+use rand::RngCore;
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+
+use pyo3::{prelude::*, exceptions::PyValueError, types::PyBytes};
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// hash functions for OAEP and MGF1
+pub enum HashFunction {
+    /// SHA-1, 20 bytes
+    Sha1,
+    /// SHA-256, 32 bytes
+    Sha256,
+}
+
+impl HashFunction {
+    /// hash some data
+    pub fn digest(&self, data: &[u8]) -> Vec<u8> {
+        match self {
+            HashFunction::Sha1 => Sha1::digest(data).to_vec(),
+            HashFunction::Sha256 => Sha256::digest(data).to_vec(),
+        }
+    }
+
+    /// length of the digest in bytes
+    pub fn len(&self) -> usize {
+        match self {
+            HashFunction::Sha1 => 20,
+            HashFunction::Sha256 => 32,
+        }
+    }
+
+    /// parse "sha1" or "sha256"
+    pub fn from_name(name: &str) -> Result<Self, OaepError> {
+        match name.to_lowercase().replace('-', "").as_str() {
+            "sha1" => Ok(HashFunction::Sha1),
+            "sha256" => Ok(HashFunction::Sha256),
+            _ => Err(OaepError::UnknownHash(name.to_string()))
+        }
+    }
+}
+
+impl fmt::Display for HashFunction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            HashFunction::Sha1 => write!(f, "SHA-1"),
+            HashFunction::Sha256 => write!(f, "SHA-256"),
+        }
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// errors for OAEP
+pub enum OaepError {
+    /// the message is too long for the key, contains the maximum length
+    MessageTooLong(usize),
+    /// the requested mask is longer than 2^32 hash lengths
+    MaskTooLong,
+    /// the encoded message is invalid, deliberately without any details
+    Decoding,
+    /// the hash function is not supported
+    UnknownHash(String),
+    /// an RSA operation failed
+    Rsa(RsaError),
+}
+
+impl fmt::Display for OaepError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OaepError::MessageTooLong(max) => write!(f, "the message is too long, the key allows {max} bytes"),
+            OaepError::MaskTooLong => write!(f, "mask too long"),
+            OaepError::Decoding => write!(f, "decryption error"),
+            OaepError::UnknownHash(name) => write!(f, "unknown hash function {name}, use sha1 or sha256"),
+            OaepError::Rsa(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl From<RsaError> for OaepError {
+    fn from(e: RsaError) -> Self {
+        OaepError::Rsa(e)
+    }
+}
+
+/// length of n in bytes
+pub fn byte_length(n: &BigInt) -> usize {
+    n.bits().div_ceil(8) as usize
+}
+
+/// the big endian bytes of x, left padded to k bytes
+pub fn to_bytes(x: &BigInt, k: usize) -> Vec<u8> {
+    let (_, bytes) = x.to_bytes_be();
+    let mut result = vec![0u8; k.saturating_sub(bytes.len())];
+    result.extend(bytes);
+    result
+}
+
+/// xor two byte strings of the same length
+fn xor(a: &[u8], b: &[u8]) -> Vec<u8> {
+    a.iter().zip(b).map(|(x, y)| x ^ y).collect()
+}
+
+/// mask generation function MGF1
+pub fn mgf1(seed: &[u8], length: usize, hash: HashFunction) -> Result<Vec<u8>, OaepError> {
+    if length as u64 > (hash.len() as u64) << 32 {
+        return Err(OaepError::MaskTooLong);
+    }
+    let mut mask: Vec<u8> = Vec::with_capacity(length + hash.len());
+    let mut counter: u32 = 0;
+    while mask.len() < length {
+        let mut block = seed.to_vec();
+        block.extend_from_slice(&counter.to_be_bytes());
+        mask.extend(hash.digest(&block));
+        counter += 1;
+    }
+    mask.truncate(length);
+    Ok(mask)
+}
+
+/// the largest message for a key of k bytes
+pub fn max_message_length(k: usize, hash: HashFunction) -> usize {
+    k.saturating_sub(2 * hash.len() + 2)
+}
+
+/// encode a block with an explicit label hash and seed
+///
+/// DB = l_hash || PS || 01 || M fills k - |seed| - 1 bytes. With l_hash = Hash(L) and a random
+/// seed of the hash length, this is the encoding of RFC 8017, but it also allows the variants
+/// that are used in exercises, like an empty l_hash and a short seed.
+pub fn encode_block(message: &[u8], l_hash: &[u8], seed: &[u8], k: usize, hash: HashFunction, verbose: bool)
+    -> Result<Vec<u8>, OaepError> {
+    let db_len = k.saturating_sub(seed.len() + 1);
+    if l_hash.len() + message.len() + 1 > db_len {
+        return Err(OaepError::MessageTooLong(db_len.saturating_sub(l_hash.len() + 1)));
+    }
+    let mut db: Vec<u8> = l_hash.to_vec();
+    db.resize(db_len - message.len() - 1, 0x00);
+    db.push(0x01);
+    db.extend_from_slice(message);
+    let db_mask = mgf1(seed, db_len, hash)?;
+    let masked_db = xor(&db, &db_mask);
+    let seed_mask = mgf1(&masked_db, seed.len(), hash)?;
+    let masked_seed = xor(seed, &seed_mask);
+    if verbose {
+        println!("DB = {}", hex(&db));
+        println!("seed = {}", hex(seed));
+        println!("MGF1(seed) = {}", hex(&db_mask));
+        println!("maskedDB = DB ^ MGF1(seed) = {}", hex(&masked_db));
+        println!("MGF1(maskedDB) = {}", hex(&seed_mask));
+        println!("maskedSeed = seed ^ MGF1(maskedDB) = {}", hex(&masked_seed));
+    }
+    let mut em: Vec<u8> = vec![0x00];
+    em.extend(masked_seed);
+    em.extend(masked_db);
+    if verbose {
+        println!("EM = 00 || maskedSeed || maskedDB = {}", hex(&em));
+    }
+    Ok(em)
+}
+
+/// OAEP encoding of a message for a key of k bytes, with a random seed
+pub fn encode(message: &[u8], label: &[u8], k: usize, hash: HashFunction, verbose: bool) -> Result<Vec<u8>, OaepError> {
+    if message.len() > max_message_length(k, hash) {
+        return Err(OaepError::MessageTooLong(max_message_length(k, hash)));
+    }
+    let mut seed = vec![0u8; hash.len()];
+    rand::thread_rng().fill_bytes(&mut seed);
+    encode_block(message, &hash.digest(label), &seed, k, hash, verbose)
+}
+
+/// OAEP decoding of an encoded message of k bytes
+///
+/// all checks are done before an error is returned, and every error is the same. Otherwise the
+/// decoding would be an oracle for manger's attack.
+pub fn decode(em: &[u8], label: &[u8], k: usize, hash: HashFunction, verbose: bool) -> Result<Vec<u8>, OaepError> {
+    let h = hash.len();
+    if em.len() != k || k < 2 * h + 2 {
+        return Err(OaepError::Decoding);
+    }
+    let masked_seed = &em[1..(h + 1)];
+    let masked_db = &em[(h + 1)..];
+    let seed = xor(masked_seed, &mgf1(masked_db, h, hash)?);
+    let db = xor(masked_db, &mgf1(&seed, k - h - 1, hash)?);
+    if verbose {
+        println!("seed = maskedSeed ^ MGF1(maskedDB) = {}", hex(&seed));
+        println!("DB = maskedDB ^ MGF1(seed) = {}", hex(&db));
+    }
+    let l_hash = hash.digest(label);
+    let mut valid = em[0] == 0x00;
+    valid &= db[..h] == l_hash[..];
+    let separator = db[h..].iter().position(|b| *b != 0x00);
+    let start = match separator {
+        Some(i) if db[h + i] == 0x01 => h + i + 1,
+        _ => {
+            valid = false;
+            db.len()
+        }
+    };
+    if !valid {
+        return Err(OaepError::Decoding);
+    }
+    Ok(db[start..].to_vec())
+}
+
+/// encode and encrypt a message with RSA-OAEP
+pub fn encrypt(message: &[u8], label: &[u8], n: &BigInt, e: &BigInt, hash: HashFunction, verbose: bool)
+    -> Result<BigInt, OaepError> {
+    let k = byte_length(n);
+    let em = encode(message, label, k, hash, verbose)?;
+    let m = BigInt::from_bytes_be(Sign::Plus, &em);
+    if verbose {
+        seperator();
+    }
+    Ok(rsa::encrypt(&m, n, e, verbose)?)
+}
+
+/// decrypt and decode an RSA-OAEP ciphertext
+pub fn decrypt(c: &BigInt, label: &[u8], n: &BigInt, d: &BigInt, hash: HashFunction, verbose: bool)
+    -> Result<Vec<u8>, OaepError> {
+    let m = rsa::decrypt(c, n, d, verbose)?;
+    if verbose {
+        seperator();
+    }
+    let k = byte_length(n);
+    decode(&to_bytes(&m, k), label, k, hash, verbose)
+}
+
+/// format bytes as hex
+pub fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+#[pyfunction]
+#[pyo3(name = "mgf1")]
+#[pyo3(signature=(seed, length, hash = "sha256"))]
+/// python wrapper for mgf1
+pub fn py_mgf1(py: Python, seed: Vec<u8>, length: usize, hash: &str) -> PyResult<PyObject> {
+    let result = HashFunction::from_name(hash).and_then(|h| mgf1(&seed, length, h));
+    match result {
+        Ok(mask) => Ok(PyBytes::new(py, &mask).into()),
+        Err(e) => Err(PyValueError::new_err(e.to_string()))
+    }
+}
+
+#[pyfunction]
+#[pyo3(name = "encode")]
+#[pyo3(signature=(message, k, label = Vec::new(), hash = "sha256", verbose = false))]
+/// python wrapper for encode
+pub fn py_encode(py: Python, message: Vec<u8>, k: usize, label: Vec<u8>, hash: &str, verbose: bool) -> PyResult<PyObject> {
+    let result = HashFunction::from_name(hash).and_then(|h| encode(&message, &label, k, h, verbose));
+    match result {
+        Ok(em) => Ok(PyBytes::new(py, &em).into()),
+        Err(e) => Err(PyValueError::new_err(e.to_string()))
+    }
+}
+
+#[pyfunction]
+#[pyo3(name = "encode_block")]
+#[pyo3(signature=(message, l_hash, seed, k, hash = "sha256", verbose = false))]
+/// python wrapper for encode_block
+pub fn py_encode_block(py: Python, message: Vec<u8>, l_hash: Vec<u8>, seed: Vec<u8>, k: usize, hash: &str, verbose: bool)
+    -> PyResult<PyObject> {
+    let result = HashFunction::from_name(hash).and_then(|h| encode_block(&message, &l_hash, &seed, k, h, verbose));
+    match result {
+        Ok(em) => Ok(PyBytes::new(py, &em).into()),
+        Err(e) => Err(PyValueError::new_err(e.to_string()))
+    }
+}
+
+#[pyfunction]
+#[pyo3(name = "decode")]
+#[pyo3(signature=(em, k, label = Vec::new(), hash = "sha256", verbose = false))]
+/// python wrapper for decode
+pub fn py_decode(py: Python, em: Vec<u8>, k: usize, label: Vec<u8>, hash: &str, verbose: bool) -> PyResult<PyObject> {
+    let result = HashFunction::from_name(hash).and_then(|h| decode(&em, &label, k, h, verbose));
+    match result {
+        Ok(message) => Ok(PyBytes::new(py, &message).into()),
+        Err(e) => Err(PyValueError::new_err(e.to_string()))
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+pub mod test {
+
+    use super::*;
+    use crate::algo::rsa::RsaPrivateKey;
+
+    use std::str::FromStr;
+
+    fn unhex(s: &str) -> Vec<u8> {
+        (0..s.len()).step_by(2).map(|i| u8::from_str_radix(&s[i..(i + 2)], 16).unwrap()).collect()
+    }
+
+    #[test]
+    fn test_encode_block() {
+        // the exercise from the oaep_rsa script: empty l_hash and an 8 byte seed
+        let message = unhex("466f6f62617220313233343536373839");
+        let seed = unhex("aa1122fe0815beef");
+        let em = encode_block(&message, &[], &seed, 128, HashFunction::Sha256, true).unwrap();
+        assert_eq!(hex(&em), concat!(
+            "00db2040f6425bb082ea600669f6f16b3a2ad05d4b6d9b23911c8cc4",
+            "32fddd8d34a68d88af3d787b7eebf6cd1b720812086758ce56e24ab8",
+            "19ccd8fb5eedb1cae9f6f895667d7f89d0454b828777ecabc040a649",
+            "c8956e78ec1c721370663065cbc343deabad9eb6f2aceab6bfed5bea",
+            "6543aa3672cddf915c5b564848f4e6ec"));
+        let n = BigInt::parse_bytes(concat!(
+            "AF5466C26A6B662AC98C06023501C9DF6036B065BD1F6804B1FC86307718DA4048211FD68A06917DE6F81DC018DCAF84",
+            "B38AB77A6538BA2FE6664D3FB81E4A0886BBCDAB071AD6823FE20DF1CD67D33FB6CC5DA519F69B11F3D48534074A83F0",
+            "3A5A9545427720A30A27432E94970155A026572E358072023061AF65A2A18E85").as_bytes(), 16).unwrap();
+        let c = rsa::encrypt(&BigInt::from_bytes_be(Sign::Plus, &em), &n, &BigInt::from(65537), false).unwrap();
+        assert_eq!(hex(&to_bytes(&c, 128)), concat!(
+            "1b57819fa11340ac8b1843c87db7adb126daa8b6dde1feefd7af721c",
+            "ee8f46b6e2c361fc04ac055406a342187388b019dba0bc3f6503f267",
+            "b848f7cc86b29a3d0b32730ccf04c5a8a3e1255708cbc6a6a648015e",
+            "30f38b1c1c7aa9d2b0e67a775c7ad1cb72ff76c000af46e7cada3c3b",
+            "45b5f4d1ec8e0596928cc9b46ee2b53d"));
+    }
+
+    #[test]
+    fn test_oaep() {
+        // ciphertexts from the python cryptography package
+        let p = BigInt::from_str("12580423244086085366836721065397962957811528637098650886929747815697446250441488627940560144509277158425483991888694659896102646164416768652879908958815221").unwrap();
+        let q = BigInt::from_str("10718146349249886994657749725251848181234194716807819564815086948025883373771313833606758136918293652498611574539895344434885728325866138073261333608434429").unwrap();
+        let key = RsaPrivateKey::from_primes(&p, &q, &BigInt::from(65537), false).unwrap();
+        let c1 = BigInt::from_str("2880380898297966097964918887633242902082015484090109454482216642837106640290452630946078500518308017731210683592850382588247778717467906123078121789388834018869857334935478941851872850679170981707799077568933898194523371008129718523745132989964408281007312969017594695998197926610297621961946386300707201008").unwrap();
+        let c2 = BigInt::from_str("45635696089105472097098012312372589601060224789617570433277596379073220294177306320290936393669835171612438485941783728756324223665895167828394657552458899746134867890921974759495247020798826015352910518147454675661673950647565456832352255077506801776051900474649094939970572409569702022071916587145611441944").unwrap();
+        assert_eq!(key.decrypt_oaep(&c1, b"", HashFunction::Sha1, false), Ok(b"interop".to_vec()));
+        assert_eq!(key.decrypt_oaep(&c2, b"label", HashFunction::Sha256, true), Ok(b"interop".to_vec()));
+        assert_eq!(key.decrypt_oaep(&c2, b"", HashFunction::Sha256, false), Err(OaepError::Decoding));
+        assert_eq!(key.decrypt_oaep(&c1, b"", HashFunction::Sha256, false), Err(OaepError::Decoding));
+        // round trip
+        let c = key.public_key().encrypt_oaep(b"hello", b"", HashFunction::Sha256, false).unwrap();
+        assert_eq!(key.decrypt_oaep(&c, b"", HashFunction::Sha256, false), Ok(b"hello".to_vec()));
+        assert_eq!(max_message_length(128, HashFunction::Sha256), 62);
+        assert_eq!(encode(&[0u8; 63], b"", 128, HashFunction::Sha256, false), Err(OaepError::MessageTooLong(62)));
+        assert!(encode(&[0u8; 62], b"", 128, HashFunction::Sha256, false).is_ok());
+    }
+
+    #[test]
+    fn test_mgf1() {
+        // from the oaep_rsa script
+        let mask = mgf1(&unhex("aa1122fe0815beef"), 8, HashFunction::Sha256).unwrap();
+        assert_eq!(hex(&mask), "ea600669f6f16b3a");
+        assert_eq!(HashFunction::from_name("SHA-1"), Ok(HashFunction::Sha1));
+        assert!(HashFunction::from_name("md5").is_err());
+    }
+}
@@ -0,0 +1,619 @@
+#![allow(dead_code)]
+/// # paillier cryptosystem
+///
+/// The public key is n = pq with g = n + 1, ciphertexts live in Z*_{n²}. Encryption of m with a
+/// random r in Z*_n is c = g^m * r^n mod n². With λ = lcm(p-1, q-1), L(u) = (u - 1) / n and
+/// μ = L(g^λ mod n²)^-1 mod n, decryption is m = L(c^λ mod n²) * μ mod n.
+///
+/// The scheme is additively homomorphic:
+///
+/// E(m1) * E(m2) = E(m1 + m2), E(m)^k = E(k * m), E(m) * r^n = E(m) with fresh randomness
+///
+/// This is enough for e-voting: every voter encrypts a one in the digit of their candidate, the
+/// product of all ballots decrypts to the tally without opening a single ballot.
+///
+/// ___
+/// Author:     Christoph J. Scherr <software@cscherr.de>
+/// License:    MIT
+/// Source:     <https://git.cscherr.de/PlexSheep/plexcryptool/>
+
+use crate::cplex::printing::seperator;
+use crate::algo::rsa::{random_prime, RsaError, MIN_BITS};
+use crate::math::gcd::{egcd_big, mod_inverse};
+use crate::math::modexp::modular_exponentiation;
+use crate::math::primality::is_prime;
+
+use core::fmt;
+
+use num::Integer;
+use num_bigint::{BigInt, RandBigInt};
+use num_traits::{One, Signed};
+
+use pyo3::{prelude::*, exceptions::PyValueError};
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// errors for the paillier cryptosystem
+pub enum PaillierError {
+    /// the requested size in bits is too small
+    TooSmall(u64),
+    /// a factor of the key is not prime
+    NotPrime(BigInt),
+    /// p and q are the same prime
+    EqualPrimes,
+    /// gcd(pq, (p-1)(q-1)) is not 1
+    NotCoprime,
+    /// the message is not in [0, n)
+    OutOfRange(BigInt),
+    /// the ciphertext is not in [1, n²) or not coprime to n
+    InvalidCiphertext(BigInt),
+    /// the randomness is not in Z*_n
+    InvalidRandom(BigInt),
+    /// a vote is not for one of the candidates
+    InvalidVote(usize),
+    /// the tally does not fit into the message space
+    TooManyVotes,
+    /// generating a prime failed
+    Rsa(RsaError),
+}
+
+impl fmt::Display for PaillierError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PaillierError::TooSmall(bits) => write!(f, "{bits} bits are too small, use at least {MIN_BITS}"),
+            PaillierError::NotPrime(p) => write!(f, "{p} is not prime"),
+            PaillierError::EqualPrimes => write!(f, "p and q must be different"),
+            PaillierError::NotCoprime => write!(f, "pq and (p-1)(q-1) are not coprime"),
+            PaillierError::OutOfRange(m) => write!(f, "{m} is not in [0, n)"),
+            PaillierError::InvalidCiphertext(c) => write!(f, "{c} is not in Z*_n²"),
+            PaillierError::InvalidRandom(r) => write!(f, "{r} is not in Z*_n"),
+            PaillierError::InvalidVote(v) => write!(f, "there is no candidate {v}"),
+            PaillierError::TooManyVotes => write!(f, "the tally does not fit into n, use a larger key"),
+            PaillierError::Rsa(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl From<RsaError> for PaillierError {
+    fn from(e: RsaError) -> Self {
+        PaillierError::Rsa(e)
+    }
+}
+
+/// check that m is in [0, n)
+fn check_range(m: &BigInt, n: &BigInt) -> Result<(), PaillierError> {
+    if m.is_negative() || m >= n {
+        return Err(PaillierError::OutOfRange(m.clone()));
+    }
+    Ok(())
+}
+
+/// check that c is in Z*_n²
+fn check_ciphertext(c: &BigInt, n: &BigInt) -> Result<(), PaillierError> {
+    if !c.is_positive() || c >= &(n * n) || !c.gcd(n).is_one() {
+        return Err(PaillierError::InvalidCiphertext(c.clone()));
+    }
+    Ok(())
+}
+
+/// a random r in Z*_n
+fn random_unit(n: &BigInt) -> BigInt {
+    let mut rng = rand::thread_rng();
+    loop {
+        let r = rng.gen_bigint_range(&BigInt::one(), n);
+        if r.gcd(n).is_one() {
+            return r;
+        }
+    }
+}
+
+/// L(u) = (u - 1) / n
+pub fn l_function(u: &BigInt, n: &BigInt) -> BigInt {
+    (u - 1u8) / n
+}
+
+/// encryption c = g^m * r^n mod n² with g = n + 1 and a given r
+pub fn encrypt_with(m: &BigInt, r: &BigInt, n: &BigInt, verbose: bool) -> Result<BigInt, PaillierError> {
+    check_range(m, n)?;
+    if !r.is_positive() || r >= n || !r.gcd(n).is_one() {
+        return Err(PaillierError::InvalidRandom(r.clone()));
+    }
+    let n2: BigInt = n * n;
+    // g^m = (1 + n)^m = 1 + m * n mod n²
+    let gm = (BigInt::one() + m * n).mod_floor(&n2);
+    let rn = modular_exponentiation(r.clone(), n.clone(), n2.clone(), false);
+    let c = (&gm * &rn).mod_floor(&n2);
+    if verbose {
+        println!("g^m mod n² = 1 + m * n = {gm}");
+        println!("r^n mod n² = {r}^{n} mod {n2} = {rn}");
+        println!("c = g^m * r^n mod n² = {c}");
+    }
+    Ok(c)
+}
+
+/// encryption c = g^m * r^n mod n² with g = n + 1 and a random r
+pub fn encrypt(m: &BigInt, n: &BigInt, verbose: bool) -> Result<BigInt, PaillierError> {
+    let r = random_unit(n);
+    if verbose {
+        println!("r = {r}");
+    }
+    encrypt_with(m, &r, n, verbose)
+}
+
+/// decryption m = L(c^λ mod n²) * μ mod n
+pub fn decrypt(c: &BigInt, n: &BigInt, lambda: &BigInt, mu: &BigInt, verbose: bool) -> Result<BigInt, PaillierError> {
+    check_ciphertext(c, n)?;
+    let n2: BigInt = n * n;
+    let u = modular_exponentiation(c.clone(), lambda.clone(), n2, false);
+    let l = l_function(&u, n);
+    let m = (&l * mu).mod_floor(n);
+    if verbose {
+        println!("u = c^λ mod n² = {c}^{lambda} mod n² = {u}");
+        println!("L(u) = (u - 1) / n = {l}");
+        println!("m = L(u) * μ mod n = {l} * {mu} mod {n} = {m}");
+    }
+    Ok(m)
+}
+
+/// homomorphic addition, E(m1) * E(m2) mod n² = E(m1 + m2)
+pub fn add(c1: &BigInt, c2: &BigInt, n: &BigInt, verbose: bool) -> Result<BigInt, PaillierError> {
+    check_ciphertext(c1, n)?;
+    check_ciphertext(c2, n)?;
+    let c = (c1 * c2).mod_floor(&(n * n));
+    if verbose {
+        println!("c = c1 * c2 mod n² = {c1} * {c2} mod n² = {c}");
+    }
+    Ok(c)
+}
+
+/// homomorphic multiplication with a plaintext scalar, E(m)^k mod n² = E(k * m)
+///
+/// k is taken mod n, so a negative k subtracts.
+pub fn scalar_mul(c: &BigInt, k: &BigInt, n: &BigInt, verbose: bool) -> Result<BigInt, PaillierError> {
+    check_ciphertext(c, n)?;
+    let k = k.mod_floor(n);
+    let result = modular_exponentiation(c.clone(), k.clone(), n * n, false);
+    if verbose {
+        println!("c' = c^k mod n² = {c}^{k} mod n² = {result}");
+    }
+    Ok(result)
+}
+
+/// multiply with a fresh r^n, the result decrypts the same but is unlinkable to c
+pub fn rerandomize(c: &BigInt, n: &BigInt, verbose: bool) -> Result<BigInt, PaillierError> {
+    check_ciphertext(c, n)?;
+    let n2: BigInt = n * n;
+    let r = random_unit(n);
+    let rn = modular_exponentiation(r.clone(), n.clone(), n2.clone(), false);
+    let result = (c * &rn).mod_floor(&n2);
+    if verbose {
+        println!("r = {r}");
+        println!("c' = c * r^n mod n² = {c} * {rn} mod n² = {result}");
+    }
+    Ok(result)
+}
+
+/// homomorphic sum of all ciphertexts, an empty sum is E(0) = 1
+pub fn sum(cs: &[BigInt], n: &BigInt, verbose: bool) -> Result<BigInt, PaillierError> {
+    let mut acc = BigInt::one();
+    for c in cs {
+        acc = add(&acc, c, n, verbose)?;
+    }
+    Ok(acc)
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[pyclass]
+/// a public paillier key, g is always n + 1
+pub struct PaillierPublicKey {
+    #[pyo3(get)]
+    /// the modulus
+    pub n: BigInt,
+    #[pyo3(get)]
+    /// the generator n + 1
+    pub g: BigInt,
+}
+
+impl PaillierPublicKey {
+    /// make a new public key
+    pub fn new(n: BigInt) -> Self {
+        let g = &n + 1u8;
+        PaillierPublicKey { n, g }
+    }
+
+    /// encrypt m with a random r
+    pub fn encrypt(&self, m: &BigInt, verbose: bool) -> Result<BigInt, PaillierError> {
+        encrypt(m, &self.n, verbose)
+    }
+
+    /// encrypt m with a given r
+    pub fn encrypt_with(&self, m: &BigInt, r: &BigInt, verbose: bool) -> Result<BigInt, PaillierError> {
+        encrypt_with(m, r, &self.n, verbose)
+    }
+
+    /// E(m1 + m2) from E(m1) and E(m2)
+    pub fn add(&self, c1: &BigInt, c2: &BigInt, verbose: bool) -> Result<BigInt, PaillierError> {
+        add(c1, c2, &self.n, verbose)
+    }
+
+    /// E(k * m) from E(m)
+    pub fn scalar_mul(&self, c: &BigInt, k: &BigInt, verbose: bool) -> Result<BigInt, PaillierError> {
+        scalar_mul(c, k, &self.n, verbose)
+    }
+
+    /// E(m) with fresh randomness
+    pub fn rerandomize(&self, c: &BigInt, verbose: bool) -> Result<BigInt, PaillierError> {
+        rerandomize(c, &self.n, verbose)
+    }
+}
+
+impl fmt::Display for PaillierPublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "n = {}\ng = {}", self.n, self.g)
+    }
+}
+
+#[pymethods]
+/// python wrappers for the public key
+impl PaillierPublicKey {
+    #[new]
+    /// make a new public key
+    pub fn py_new(n: BigInt) -> Self {
+        PaillierPublicKey::new(n)
+    }
+
+    #[pyo3(name="encrypt")]
+    #[pyo3(signature=(m, r = None, verbose = false))]
+    /// encrypt m, with a random r if none is given
+    pub fn py_encrypt(&self, m: BigInt, r: Option<BigInt>, verbose: bool) -> PyResult<BigInt> {
+        let result = match r {
+            Some(r) => self.encrypt_with(&m, &r, verbose),
+            None => self.encrypt(&m, verbose)
+        };
+        match result {
+            Ok(c) => Ok(c),
+            Err(e) => Err(PyValueError::new_err(e.to_string()))
+        }
+    }
+
+    #[pyo3(name="add")]
+    #[pyo3(signature=(c1, c2, verbose = false))]
+    /// E(m1 + m2) from E(m1) and E(m2)
+    pub fn py_add(&self, c1: BigInt, c2: BigInt, verbose: bool) -> PyResult<BigInt> {
+        match self.add(&c1, &c2, verbose) {
+            Ok(c) => Ok(c),
+            Err(e) => Err(PyValueError::new_err(e.to_string()))
+        }
+    }
+
+    #[pyo3(name="scalar_mul")]
+    #[pyo3(signature=(c, k, verbose = false))]
+    /// E(k * m) from E(m)
+    pub fn py_scalar_mul(&self, c: BigInt, k: BigInt, verbose: bool) -> PyResult<BigInt> {
+        match self.scalar_mul(&c, &k, verbose) {
+            Ok(c) => Ok(c),
+            Err(e) => Err(PyValueError::new_err(e.to_string()))
+        }
+    }
+
+    #[pyo3(name="rerandomize")]
+    #[pyo3(signature=(c, verbose = false))]
+    /// E(m) with fresh randomness
+    pub fn py_rerandomize(&self, c: BigInt, verbose: bool) -> PyResult<BigInt> {
+        match self.rerandomize(&c, verbose) {
+            Ok(c) => Ok(c),
+            Err(e) => Err(PyValueError::new_err(e.to_string()))
+        }
+    }
+
+    #[pyo3(name="sum")]
+    #[pyo3(signature=(cs, verbose = false))]
+    /// homomorphic sum of all ciphertexts
+    pub fn py_sum(&self, cs: Vec<BigInt>, verbose: bool) -> PyResult<BigInt> {
+        match sum(&cs, &self.n, verbose) {
+            Ok(c) => Ok(c),
+            Err(e) => Err(PyValueError::new_err(e.to_string()))
+        }
+    }
+
+    /// string representation
+    fn __str__(&self) -> String {
+        format!("{}", self)
+    }
+
+    /// debug representation
+    fn __repr__(&self) -> String {
+        format!("{:?}", self)
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[pyclass]
+/// a private paillier key
+pub struct PaillierPrivateKey {
+    #[pyo3(get)]
+    /// the modulus
+    pub n: BigInt,
+    #[pyo3(get, name = "lambda_")]
+    /// λ = lcm(p-1, q-1), lambda_ in python as lambda is a keyword
+    pub lambda: BigInt,
+    #[pyo3(get)]
+    /// μ = L(g^λ mod n²)^-1 mod n
+    pub mu: BigInt,
+    #[pyo3(get)]
+    /// the first prime factor of n
+    pub p: BigInt,
+    #[pyo3(get)]
+    /// the second prime factor of n
+    pub q: BigInt,
+}
+
+impl PaillierPrivateKey {
+    /// make a private key from two primes
+    ///
+    /// λ = (p-1)(q-1) / gcd(p-1, q-1) and μ = L(g^λ mod n²)^-1 mod n
+    pub fn from_primes(p: &BigInt, q: &BigInt, verbose: bool) -> Result<Self, PaillierError> {
+        for f in [p, q] {
+            if !is_prime(f, false) {
+                return Err(PaillierError::NotPrime(f.clone()));
+            }
+        }
+        if p == q {
+            return Err(PaillierError::EqualPrimes);
+        }
+        let n: BigInt = p * q;
+        let phi: BigInt = (p - 1u8) * (q - 1u8);
+        let (g, _, _) = egcd_big(&n, &phi);
+        if !g.is_one() {
+            return Err(PaillierError::NotCoprime);
+        }
+        let (g, _, _) = egcd_big(&(p - 1u8), &(q - 1u8));
+        let lambda: BigInt = &phi / &g;
+        let n2: BigInt = &n * &n;
+        let u = modular_exponentiation(&n + 1u8, lambda.clone(), n2, false);
+        let l = l_function(&u, &n);
+        let mu = match mod_inverse(&l, &n) {
+            Ok(mu) => mu,
+            Err(_) => return Err(PaillierError::NotCoprime)
+        };
+        if verbose {
+            println!("n = p * q = {p} * {q} = {n}");
+            println!("λ = (p-1) * (q-1) / gcd(p-1, q-1) = {phi} / {g} = {lambda}");
+            println!("L(g^λ mod n²) = {l}");
+            println!("μ = {l}^-1 mod n = {mu}");
+        }
+        Ok(PaillierPrivateKey { n, lambda, mu, p: p.clone(), q: q.clone() })
+    }
+
+    /// generate a new key with a modulus of bits bits
+    pub fn generate(bits: u64, verbose: bool) -> Result<Self, PaillierError> {
+        if bits < MIN_BITS {
+            return Err(PaillierError::TooSmall(bits));
+        }
+        loop {
+            let p = random_prime(bits - bits / 2, verbose)?;
+            let q = random_prime(bits / 2, verbose)?;
+            if verbose {
+                seperator();
+            }
+            match PaillierPrivateKey::from_primes(&p, &q, verbose) {
+                Err(PaillierError::EqualPrimes) | Err(PaillierError::NotCoprime) => continue,
+                other => return other
+            }
+        }
+    }
+
+    /// the public part of the key
+    pub fn public_key(&self) -> PaillierPublicKey {
+        PaillierPublicKey::new(self.n.clone())
+    }
+
+    /// decrypt c
+    pub fn decrypt(&self, c: &BigInt, verbose: bool) -> Result<BigInt, PaillierError> {
+        decrypt(c, &self.n, &self.lambda, &self.mu, verbose)
+    }
+}
+
+impl fmt::Display for PaillierPrivateKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "n = {}\nλ = {}\nμ = {}\np = {}\nq = {}", self.n, self.lambda, self.mu, self.p, self.q)
+    }
+}
+
+#[pymethods]
+/// python wrappers for the private key
+impl PaillierPrivateKey {
+    #[new]
+    #[pyo3(signature=(p, q, verbose = false))]
+    /// make a private key from two primes
+    pub fn py_new(p: BigInt, q: BigInt, verbose: bool) -> PyResult<Self> {
+        match PaillierPrivateKey::from_primes(&p, &q, verbose) {
+            Ok(k) => Ok(k),
+            Err(e) => Err(PyValueError::new_err(e.to_string()))
+        }
+    }
+
+    #[staticmethod]
+    #[pyo3(name="generate")]
+    #[pyo3(signature=(bits, verbose = false))]
+    /// generate a new key with a modulus of bits bits
+    pub fn py_generate(bits: u64, verbose: bool) -> PyResult<Self> {
+        match PaillierPrivateKey::generate(bits, verbose) {
+            Ok(k) => Ok(k),
+            Err(e) => Err(PyValueError::new_err(e.to_string()))
+        }
+    }
+
+    #[pyo3(name="public_key")]
+    /// the public part of the key
+    pub fn py_public_key(&self) -> PaillierPublicKey {
+        self.public_key()
+    }
+
+    #[pyo3(name="decrypt")]
+    #[pyo3(signature=(c, verbose = false))]
+    /// decrypt c
+    pub fn py_decrypt(&self, c: BigInt, verbose: bool) -> PyResult<BigInt> {
+        match self.decrypt(&c, verbose) {
+            Ok(m) => Ok(m),
+            Err(e) => Err(PyValueError::new_err(e.to_string()))
+        }
+    }
+
+    /// string representation
+    fn __str__(&self) -> String {
+        format!("{}", self)
+    }
+
+    /// debug representation
+    fn __repr__(&self) -> String {
+        format!("{:?}", self)
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// encode a vote for candidate as a one in digit candidate of base voters + 1
+pub fn encode_vote(candidate: usize, candidates: usize, voters: usize) -> Result<BigInt, PaillierError> {
+    if candidate >= candidates {
+        return Err(PaillierError::InvalidVote(candidate));
+    }
+    Ok(BigInt::from(voters + 1).pow(candidate as u32))
+}
+
+/// decode a tally into the number of votes for each candidate
+pub fn decode_tally(tally: &BigInt, candidates: usize, voters: usize) -> Vec<BigInt> {
+    let base = BigInt::from(voters + 1);
+    let mut rest = tally.clone();
+    let mut counts = Vec::with_capacity(candidates);
+    for _ in 0..candidates {
+        let (q, r) = rest.div_mod_floor(&base);
+        counts.push(r);
+        rest = q;
+    }
+    counts
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// the result of an encrypted election
+pub struct Tally {
+    /// the product of all ballots
+    pub ciphertext: BigInt,
+    /// the decrypted tally
+    pub plaintext: BigInt,
+    /// the votes for each candidate
+    pub counts: Vec<BigInt>,
+}
+
+impl fmt::Display for Tally {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "E(tally) = {}", self.ciphertext)?;
+        write!(f, "tally = {}", self.plaintext)?;
+        for (candidate, count) in self.counts.iter().enumerate() {
+            write!(f, "\ncandidate {candidate}: {count}")?;
+        }
+        Ok(())
+    }
+}
+
+/// tally an election: every vote is encrypted on its own, only the product is decrypted
+pub fn tally(key: &PaillierPrivateKey, votes: &[usize], candidates: usize, verbose: bool) -> Result<Tally, PaillierError> {
+    let voters = votes.len();
+    if BigInt::from(voters + 1).pow(candidates as u32) > key.n {
+        return Err(PaillierError::TooManyVotes);
+    }
+    let public = key.public_key();
+    let mut ballots = Vec::with_capacity(voters);
+    for (voter, vote) in votes.iter().enumerate() {
+        let m = encode_vote(*vote, candidates, voters)?;
+        let c = public.encrypt(&m, false)?;
+        if verbose {
+            println!("voter {voter}: E({m}) = {c}");
+        }
+        ballots.push(c);
+    }
+    let ciphertext = sum(&ballots, &key.n, false)?;
+    if verbose {
+        seperator();
+        println!("product of all ballots = {ciphertext}");
+    }
+    let plaintext = key.decrypt(&ciphertext, verbose)?;
+    let counts = decode_tally(&plaintext, candidates, voters);
+    Ok(Tally { ciphertext, plaintext, counts })
+}
+
+/// generate a key and tally an election with it
+pub fn demo(votes: &[usize], candidates: usize, bits: u64, verbose: bool) -> Result<Tally, PaillierError> {
+    let key = PaillierPrivateKey::generate(bits, false)?;
+    if verbose {
+        println!("{key}");
+        seperator();
+    }
+    tally(&key, votes, candidates, verbose)
+}
+
+#[pyfunction]
+#[pyo3(name = "tally")]
+#[pyo3(signature=(key, votes, candidates, verbose = false))]
+/// python wrapper for tally, returns the votes for each candidate
+pub fn py_tally(key: PaillierPrivateKey, votes: Vec<usize>, candidates: usize, verbose: bool) -> PyResult<Vec<BigInt>> {
+    match tally(&key, &votes, candidates, verbose) {
+        Ok(t) => Ok(t.counts),
+        Err(e) => Err(PyValueError::new_err(e.to_string()))
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+pub mod test {
+
+    use super::*;
+
+    fn big(n: i64) -> BigInt {
+        BigInt::from(n)
+    }
+
+    #[test]
+    fn test_paillier_textbook() {
+        let key = PaillierPrivateKey::from_primes(&big(7), &big(11), true).unwrap();
+        assert_eq!(key.lambda, big(30));
+        assert_eq!(key.mu, big(18));
+        let c = key.public_key().encrypt_with(&big(42), &big(23), true).unwrap();
+        assert_eq!(c, big(3840));
+        assert_eq!(key.decrypt(&c, true), Ok(big(42)));
+        assert_eq!(key.public_key().encrypt(&big(77), false), Err(PaillierError::OutOfRange(big(77))));
+        assert_eq!(key.public_key().encrypt_with(&big(1), &big(14), false), Err(PaillierError::InvalidRandom(big(14))));
+        assert_eq!(key.decrypt(&big(0), false), Err(PaillierError::InvalidCiphertext(big(0))));
+        assert_eq!(PaillierPrivateKey::from_primes(&big(3), &big(7), false), Err(PaillierError::NotCoprime));
+        assert_eq!(PaillierPrivateKey::generate(8, false), Err(PaillierError::TooSmall(8)));
+    }
+
+    #[test]
+    fn test_homomorphic() {
+        let key = PaillierPrivateKey::generate(128, false).unwrap();
+        assert_eq!(key.n.bits(), 128);
+        let public = key.public_key();
+        let c1 = public.encrypt(&big(1000), false).unwrap();
+        let c2 = public.encrypt(&big(337), false).unwrap();
+        assert_eq!(key.decrypt(&public.add(&c1, &c2, true).unwrap(), false), Ok(big(1337)));
+        assert_eq!(key.decrypt(&public.scalar_mul(&c2, &big(3), true).unwrap(), false), Ok(big(1011)));
+        let minus = public.scalar_mul(&c2, &big(-1), false).unwrap();
+        assert_eq!(key.decrypt(&public.add(&c1, &minus, false).unwrap(), false), Ok(big(663)));
+        let fresh = public.rerandomize(&c1, true).unwrap();
+        assert_ne!(fresh, c1);
+        assert_eq!(key.decrypt(&fresh, false), Ok(big(1000)));
+        assert_eq!(sum(&[], &key.n, false), Ok(BigInt::one()));
+    }
+
+    #[test]
+    fn test_tally() {
+        let key = PaillierPrivateKey::generate(64, false).unwrap();
+        let result = tally(&key, &[0, 2, 1, 2, 2], 3, true).unwrap();
+        assert_eq!(result.counts, vec![big(1), big(1), big(3)]);
+        assert_eq!(tally(&key, &[3], 3, false), Err(PaillierError::InvalidVote(3)));
+        assert_eq!(tally(&key, &[0; 100], 20, false), Err(PaillierError::TooManyVotes));
+    }
+}
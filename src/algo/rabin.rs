@@ -0,0 +1,571 @@
+#![allow(dead_code)]
+/// # rabin cryptosystem
+///
+/// Encryption is squaring, c = m² mod n with n = pq. For p = q = 3 (mod 4) the roots modulo the
+/// primes are m_p = c^((p+1)/4) mod p and m_q = c^((q+1)/4) mod q, the chinese remainder theorem
+/// combines them to four roots mod n. Decrypting is as hard as factoring n, because two roots x
+/// and y with x != ±y give the factor gcd(x - y, n).
+///
+/// To know which root is the plaintext, a redundancy of r bits can be added: the lowest r bits of
+/// m are repeated, m' = m * 2^r + (m mod 2^r). A wrong root has the same pattern only with a
+/// probability of about 2^-r.
+///
+/// The same reduction breaks the scheme with a chosen ciphertext: send c = x² mod n for a random
+/// x to a decryption oracle, with a probability of 1/2 it answers with a root y != ±x. The
+/// redundancy stops this, the oracle only answers for roots with a valid pattern, which is x
+/// itself if the attacker chose one.
+///
+/// ___
+/// Author:     Christoph J. Scherr <software@cscherr.de>
+/// License:    MIT
+/// Source:     <https://git.cscherr.de/PlexSheep/plexcryptool/>
+
+use crate::cplex::printing::seperator;
+use crate::algo::rsa::{random_prime, RsaError, MIN_BITS};
+use crate::math::modsqrt::{sqrt_mod, SqrtError, SqrtMethod};
+use crate::math::primality::is_prime;
+
+use core::fmt;
+
+use num::Integer;
+use num_bigint::{BigInt, RandBigInt};
+use num_traits::{One, Signed};
+
+use pyo3::{prelude::*, exceptions::PyValueError};
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// give up the chosen ciphertext attack after this many queries
+pub const MAX_QUERIES: u64 = 128;
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// errors for the rabin cryptosystem
+pub enum RabinError {
+    /// the requested size in bits is too small
+    TooSmall(u64),
+    /// a factor of the key is not prime
+    NotPrime(BigInt),
+    /// a factor of the key is not 3 mod 4
+    NotBlum(BigInt),
+    /// p and q are the same prime
+    EqualPrimes,
+    /// the message or ciphertext is not in [0, n)
+    OutOfRange(BigInt),
+    /// the ciphertext is not a square mod n
+    NotASquare(BigInt),
+    /// no root has a valid redundancy
+    NoRedundantRoot,
+    /// more than one root has a valid redundancy
+    Ambiguous(usize),
+    /// the oracle never gave away a factor
+    TooManyQueries,
+    /// taking the square roots failed
+    Sqrt(SqrtError),
+    /// generating a prime failed
+    Rsa(RsaError),
+}
+
+impl fmt::Display for RabinError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RabinError::TooSmall(bits) => write!(f, "{bits} bits are too small, use at least {MIN_BITS}"),
+            RabinError::NotPrime(p) => write!(f, "{p} is not prime"),
+            RabinError::NotBlum(p) => write!(f, "{p} is not 3 mod 4"),
+            RabinError::EqualPrimes => write!(f, "p and q must be different"),
+            RabinError::OutOfRange(m) => write!(f, "{m} is not in [0, n)"),
+            RabinError::NotASquare(c) => write!(f, "{c} is not a square mod n"),
+            RabinError::NoRedundantRoot => write!(f, "no root has a valid redundancy"),
+            RabinError::Ambiguous(count) => write!(f, "{count} roots have a valid redundancy"),
+            RabinError::TooManyQueries => write!(f, "no factor after {MAX_QUERIES} oracle queries"),
+            RabinError::Sqrt(e) => write!(f, "{e}"),
+            RabinError::Rsa(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl From<SqrtError> for RabinError {
+    fn from(e: SqrtError) -> Self {
+        RabinError::Sqrt(e)
+    }
+}
+
+impl From<RsaError> for RabinError {
+    fn from(e: RsaError) -> Self {
+        RabinError::Rsa(e)
+    }
+}
+
+/// check that c is in [0, n)
+fn check_range(c: &BigInt, n: &BigInt) -> Result<(), RabinError> {
+    if c.is_negative() || c >= n {
+        return Err(RabinError::OutOfRange(c.clone()));
+    }
+    Ok(())
+}
+
+/// repeat the lowest r bits of m: m * 2^r + (m mod 2^r)
+pub fn add_redundancy(m: &BigInt, r: u32) -> BigInt {
+    let mask: BigInt = (BigInt::one() << r) - 1u8;
+    (m << r) | (m & mask)
+}
+
+/// remove the redundancy of x, if the lowest r bits are repeated
+pub fn strip_redundancy(x: &BigInt, r: u32) -> Option<BigInt> {
+    let mask: BigInt = (BigInt::one() << r) - 1u8;
+    let m: BigInt = x >> r;
+    if (x & &mask) == (&m & &mask) {
+        Some(m)
+    }
+    else {
+        None
+    }
+}
+
+/// encryption c = m² mod n, with r bits of redundancy if r > 0
+pub fn encrypt(m: &BigInt, n: &BigInt, r: u32, verbose: bool) -> Result<BigInt, RabinError> {
+    let padded = add_redundancy(m, r);
+    check_range(&padded, n)?;
+    let c = (&padded * &padded).mod_floor(n);
+    if verbose {
+        if r > 0 {
+            println!("m' = m * 2^{r} + (m mod 2^{r}) = {padded} = {padded:#x}");
+        }
+        println!("c = m² mod n = {padded}² mod {n} = {c}");
+    }
+    Ok(c)
+}
+
+/// all four roots of c mod pq, sorted
+///
+/// p and q must be different primes that are 3 mod 4, so that the roots mod p and q are
+/// ±c^((p+1)/4) and ±c^((q+1)/4), see [SqrtMethod::Blum]. The chinese remainder theorem
+/// combines them.
+pub fn decrypt(c: &BigInt, p: &BigInt, q: &BigInt, verbose: bool) -> Result<Vec<BigInt>, RabinError> {
+    for prime in [p, q] {
+        if prime.mod_floor(&BigInt::from(4)) != BigInt::from(3) {
+            return Err(RabinError::NotBlum(prime.clone()));
+        }
+    }
+    if p == q {
+        return Err(RabinError::EqualPrimes);
+    }
+    let n: BigInt = p * q;
+    check_range(c, &n)?;
+    match sqrt_mod(c, &[p.clone(), q.clone()], SqrtMethod::Blum, verbose) {
+        Ok(roots) => Ok(roots),
+        Err(SqrtError::NoRoot(_)) => Err(RabinError::NotASquare(c.clone())),
+        Err(e) => Err(e.into())
+    }
+}
+
+/// decrypt c and choose the root with a valid redundancy of r bits
+pub fn decrypt_redundant(c: &BigInt, p: &BigInt, q: &BigInt, r: u32, verbose: bool) -> Result<BigInt, RabinError> {
+    let roots = decrypt(c, p, q, verbose)?;
+    let valid: Vec<BigInt> = roots.iter().filter_map(|x| strip_redundancy(x, r)).collect();
+    if verbose {
+        seperator();
+        for x in &roots {
+            let ok = strip_redundancy(x, r).is_some();
+            println!("{x:#x}: {}", if ok { "valid redundancy" } else { "invalid" });
+        }
+    }
+    match valid.len() {
+        0 => Err(RabinError::NoRedundantRoot),
+        1 => Ok(valid[0].clone()),
+        count => Err(RabinError::Ambiguous(count))
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[pyclass]
+/// a public rabin key
+pub struct RabinPublicKey {
+    #[pyo3(get)]
+    /// the modulus
+    pub n: BigInt,
+}
+
+impl RabinPublicKey {
+    /// make a new public key
+    pub fn new(n: BigInt) -> Self {
+        RabinPublicKey { n }
+    }
+
+    /// encrypt m with r bits of redundancy
+    pub fn encrypt(&self, m: &BigInt, r: u32, verbose: bool) -> Result<BigInt, RabinError> {
+        encrypt(m, &self.n, r, verbose)
+    }
+}
+
+impl fmt::Display for RabinPublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "n = {}", self.n)
+    }
+}
+
+#[pymethods]
+/// python wrappers for the public key
+impl RabinPublicKey {
+    #[new]
+    /// make a new public key
+    pub fn py_new(n: BigInt) -> Self {
+        RabinPublicKey::new(n)
+    }
+
+    #[pyo3(name="encrypt")]
+    #[pyo3(signature=(m, redundancy = 0, verbose = false))]
+    /// encrypt m with redundancy bits of redundancy
+    pub fn py_encrypt(&self, m: BigInt, redundancy: u32, verbose: bool) -> PyResult<BigInt> {
+        match self.encrypt(&m, redundancy, verbose) {
+            Ok(c) => Ok(c),
+            Err(e) => Err(PyValueError::new_err(e.to_string()))
+        }
+    }
+
+    /// string representation
+    fn __str__(&self) -> String {
+        format!("{}", self)
+    }
+
+    /// debug representation
+    fn __repr__(&self) -> String {
+        format!("{:?}", self)
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[pyclass]
+/// a private rabin key
+pub struct RabinPrivateKey {
+    #[pyo3(get)]
+    /// the modulus
+    pub n: BigInt,
+    #[pyo3(get)]
+    /// the first prime factor of n
+    pub p: BigInt,
+    #[pyo3(get)]
+    /// the second prime factor of n
+    pub q: BigInt,
+}
+
+impl RabinPrivateKey {
+    /// make a private key from two primes that are 3 mod 4
+    pub fn from_primes(p: &BigInt, q: &BigInt, verbose: bool) -> Result<Self, RabinError> {
+        for f in [p, q] {
+            if !is_prime(f, false) {
+                return Err(RabinError::NotPrime(f.clone()));
+            }
+            if f.mod_floor(&BigInt::from(4)) != BigInt::from(3) {
+                return Err(RabinError::NotBlum(f.clone()));
+            }
+        }
+        if p == q {
+            return Err(RabinError::EqualPrimes);
+        }
+        let n: BigInt = p * q;
+        if verbose {
+            println!("n = p * q = {p} * {q} = {n}");
+        }
+        Ok(RabinPrivateKey { n, p: p.clone(), q: q.clone() })
+    }
+
+    /// generate a new key with a modulus of bits bits
+    pub fn generate(bits: u64, verbose: bool) -> Result<Self, RabinError> {
+        if bits < MIN_BITS {
+            return Err(RabinError::TooSmall(bits));
+        }
+        let blum = |size: u64| -> Result<BigInt, RabinError> {
+            loop {
+                let p = random_prime(size, verbose)?;
+                if p.mod_floor(&BigInt::from(4)) == BigInt::from(3) {
+                    return Ok(p);
+                }
+            }
+        };
+        let p = blum(bits - bits / 2)?;
+        let q = loop {
+            let q = blum(bits / 2)?;
+            if q != p {
+                break q;
+            }
+        };
+        if verbose {
+            seperator();
+        }
+        RabinPrivateKey::from_primes(&p, &q, verbose)
+    }
+
+    /// the public part of the key
+    pub fn public_key(&self) -> RabinPublicKey {
+        RabinPublicKey::new(self.n.clone())
+    }
+
+    /// all four roots of c
+    pub fn decrypt(&self, c: &BigInt, verbose: bool) -> Result<Vec<BigInt>, RabinError> {
+        decrypt(c, &self.p, &self.q, verbose)
+    }
+
+    /// the root of c with a valid redundancy of r bits, without the redundancy
+    pub fn decrypt_redundant(&self, c: &BigInt, r: u32, verbose: bool) -> Result<BigInt, RabinError> {
+        decrypt_redundant(c, &self.p, &self.q, r, verbose)
+    }
+}
+
+impl fmt::Display for RabinPrivateKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "n = {}\np = {}\nq = {}", self.n, self.p, self.q)
+    }
+}
+
+#[pymethods]
+/// python wrappers for the private key
+impl RabinPrivateKey {
+    #[new]
+    #[pyo3(signature=(p, q, verbose = false))]
+    /// make a private key from two primes that are 3 mod 4
+    pub fn py_new(p: BigInt, q: BigInt, verbose: bool) -> PyResult<Self> {
+        match RabinPrivateKey::from_primes(&p, &q, verbose) {
+            Ok(k) => Ok(k),
+            Err(e) => Err(PyValueError::new_err(e.to_string()))
+        }
+    }
+
+    #[staticmethod]
+    #[pyo3(name="generate")]
+    #[pyo3(signature=(bits, verbose = false))]
+    /// generate a new key with a modulus of bits bits
+    pub fn py_generate(bits: u64, verbose: bool) -> PyResult<Self> {
+        match RabinPrivateKey::generate(bits, verbose) {
+            Ok(k) => Ok(k),
+            Err(e) => Err(PyValueError::new_err(e.to_string()))
+        }
+    }
+
+    #[pyo3(name="public_key")]
+    /// the public part of the key
+    pub fn py_public_key(&self) -> RabinPublicKey {
+        self.public_key()
+    }
+
+    #[pyo3(name="decrypt")]
+    #[pyo3(signature=(c, verbose = false))]
+    /// all four roots of c
+    pub fn py_decrypt(&self, c: BigInt, verbose: bool) -> PyResult<Vec<BigInt>> {
+        match self.decrypt(&c, verbose) {
+            Ok(roots) => Ok(roots),
+            Err(e) => Err(PyValueError::new_err(e.to_string()))
+        }
+    }
+
+    #[pyo3(name="decrypt_redundant")]
+    #[pyo3(signature=(c, redundancy, verbose = false))]
+    /// the root of c with a valid redundancy, without the redundancy
+    pub fn py_decrypt_redundant(&self, c: BigInt, redundancy: u32, verbose: bool) -> PyResult<BigInt> {
+        match self.decrypt_redundant(&c, redundancy, verbose) {
+            Ok(m) => Ok(m),
+            Err(e) => Err(PyValueError::new_err(e.to_string()))
+        }
+    }
+
+    /// string representation
+    fn __str__(&self) -> String {
+        format!("{}", self)
+    }
+
+    /// debug representation
+    fn __repr__(&self) -> String {
+        format!("{:?}", self)
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// a decryption oracle that answers with one square root of c
+pub trait SquareRootOracle {
+    /// a root of c mod n, None if the oracle refuses to answer
+    fn query(&mut self, c: &BigInt) -> Option<BigInt>;
+}
+
+/// an oracle that decrypts with a private key
+///
+/// without redundancy it answers with the smallest root, with redundancy only with the root that
+/// has a valid pattern.
+pub struct LocalOracle {
+    /// the key to decrypt with
+    key: RabinPrivateKey,
+    /// bits of redundancy, 0 for none
+    redundancy: u32,
+}
+
+impl LocalOracle {
+    /// make an oracle from a private key, redundancy 0 means no redundancy
+    pub fn new(key: RabinPrivateKey, redundancy: u32) -> Self {
+        LocalOracle { key, redundancy }
+    }
+}
+
+impl SquareRootOracle for LocalOracle {
+    fn query(&mut self, c: &BigInt) -> Option<BigInt> {
+        let roots = self.key.decrypt(c, false).ok()?;
+        if self.redundancy == 0 {
+            return roots.into_iter().next();
+        }
+        let mut valid = roots.into_iter().filter(|x| strip_redundancy(x, self.redundancy).is_some());
+        match (valid.next(), valid.next()) {
+            (Some(x), None) => Some(x),
+            _ => None
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// the result of the chosen ciphertext attack
+pub struct Factored {
+    /// the smaller factor of n
+    pub p: BigInt,
+    /// the larger factor of n
+    pub q: BigInt,
+    /// the number of oracle queries
+    pub queries: u64,
+}
+
+impl fmt::Display for Factored {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "p = {}\nq = {}\nqueries = {}", self.p, self.q, self.queries)
+    }
+}
+
+/// chosen ciphertext attack, factor n with a decryption oracle
+///
+/// sends x² mod n for random x until the answer y is not ±x, then gcd(x - y, n) is a factor.
+pub fn attack<O: SquareRootOracle + ?Sized>(n: &BigInt, oracle: &mut O, verbose: bool) -> Result<Factored, RabinError> {
+    let mut rng = rand::thread_rng();
+    for queries in 1..=MAX_QUERIES {
+        let x = rng.gen_bigint_range(&BigInt::from(2), n);
+        let c = (&x * &x).mod_floor(n);
+        let y = match oracle.query(&c) {
+            Some(y) => y,
+            None => {
+                if verbose {
+                    println!("{queries}. x = {x}: the oracle refused");
+                }
+                continue;
+            }
+        };
+        let g = (&x - &y).gcd(n);
+        if verbose {
+            println!("{queries}. x = {x}: y = {y}, gcd(x - y, n) = {g}");
+        }
+        if !g.is_one() && &g != n {
+            let other: BigInt = n / &g;
+            let (p, q) = if g < other { (g, other) } else { (other, g) };
+            let result = Factored { p, q, queries };
+            if verbose {
+                seperator();
+                println!("{result}");
+            }
+            return Ok(result);
+        }
+    }
+    Err(RabinError::TooManyQueries)
+}
+
+/// the chosen ciphertext attack against a local oracle
+pub fn attack_local(key: &RabinPrivateKey, redundancy: u32, verbose: bool) -> Result<Factored, RabinError> {
+    let mut oracle = LocalOracle::new(key.clone(), redundancy);
+    attack(&key.n, &mut oracle, verbose)
+}
+
+/// generate a key and factor it with the chosen ciphertext attack
+pub fn demo(bits: u64, redundancy: u32, verbose: bool) -> Result<Factored, RabinError> {
+    let key = RabinPrivateKey::generate(bits, false)?;
+    if verbose {
+        println!("{key}");
+        if redundancy > 0 {
+            println!("the oracle only answers for roots with {redundancy} bits of redundancy");
+        }
+        seperator();
+    }
+    attack_local(&key, redundancy, verbose)
+}
+
+#[pyfunction]
+#[pyo3(name = "add_redundancy")]
+/// python wrapper for add_redundancy
+pub fn py_add_redundancy(m: BigInt, redundancy: u32) -> BigInt {
+    add_redundancy(&m, redundancy)
+}
+
+#[pyfunction]
+#[pyo3(name = "strip_redundancy")]
+/// python wrapper for strip_redundancy
+pub fn py_strip_redundancy(x: BigInt, redundancy: u32) -> Option<BigInt> {
+    strip_redundancy(&x, redundancy)
+}
+
+#[pyfunction]
+#[pyo3(name = "attack_local")]
+#[pyo3(signature=(key, redundancy = 0, verbose = false))]
+/// python wrapper for attack_local, returns p, q and the number of queries
+pub fn py_attack_local(key: RabinPrivateKey, redundancy: u32, verbose: bool) -> PyResult<(BigInt, BigInt, u64)> {
+    match attack_local(&key, redundancy, verbose) {
+        Ok(r) => Ok((r.p, r.q, r.queries)),
+        Err(e) => Err(PyValueError::new_err(e.to_string()))
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+pub mod test {
+
+    use super::*;
+
+    fn big(n: i64) -> BigInt {
+        BigInt::from(n)
+    }
+
+    #[test]
+    fn test_rabin_textbook() {
+        let key = RabinPrivateKey::from_primes(&big(7), &big(11), true).unwrap();
+        let c = key.public_key().encrypt(&big(20), 0, true).unwrap();
+        assert_eq!(c, big(15));
+        assert_eq!(key.decrypt(&c, true), Ok(vec![big(13), big(20), big(57), big(64)]));
+        assert_eq!(key.decrypt(&big(3), false), Err(RabinError::NotASquare(big(3))));
+        // 14 = 0 mod 7 has only two roots
+        assert_eq!(key.decrypt(&big(14), false), Ok(vec![big(28), big(49)]));
+        assert_eq!(decrypt(&c, &big(13), &big(11), false), Err(RabinError::NotBlum(big(13))));
+        assert_eq!(key.public_key().encrypt(&big(77), 0, false), Err(RabinError::OutOfRange(big(77))));
+        assert_eq!(RabinPrivateKey::from_primes(&big(13), &big(11), false), Err(RabinError::NotBlum(big(13))));
+        assert_eq!(RabinPrivateKey::from_primes(&big(15), &big(11), false), Err(RabinError::NotPrime(big(15))));
+        assert_eq!(RabinPrivateKey::generate(8, false), Err(RabinError::TooSmall(8)));
+    }
+
+    #[test]
+    fn test_redundancy() {
+        assert_eq!(add_redundancy(&big(0b1011), 3), big(0b1011011));
+        assert_eq!(strip_redundancy(&big(0b1011011), 3), Some(big(0b1011)));
+        assert_eq!(strip_redundancy(&big(0b1011010), 3), None);
+        let key = RabinPrivateKey::generate(128, false).unwrap();
+        assert_eq!(key.n.bits(), 128);
+        let m = big(1234567890123);
+        let c = key.public_key().encrypt(&m, 32, false).unwrap();
+        assert_eq!(key.decrypt_redundant(&c, 32, true), Ok(m.clone()));
+        assert!(key.decrypt(&c, false).unwrap().contains(&add_redundancy(&m, 32)));
+        let c = key.public_key().encrypt(&m, 0, false).unwrap();
+        assert!(key.decrypt_redundant(&c, 32, false).is_err());
+    }
+
+    #[test]
+    fn test_attack() {
+        let key = RabinPrivateKey::generate(128, false).unwrap();
+        let result = attack_local(&key, 0, true).unwrap();
+        assert_eq!(&result.p * &result.q, key.n);
+        assert!(result.p == key.p || result.p == key.q);
+        // the oracle never answers for random ciphertexts
+        assert_eq!(attack_local(&key, 48, false), Err(RabinError::TooManyQueries));
+    }
+}
@@ -0,0 +1,522 @@
+#![allow(dead_code)]
+/// # textbook RSA
+///
+/// Key generation, encryption, decryption, signatures and decryption with the chinese remainder
+/// theorem. There is no padding here, so this is malleable and deterministic. Do not use it for
+/// anything but exercises.
+///
+/// CRT decryption uses dp = d mod (p-1), dq = d mod (q-1) and qInv = q^-1 mod p:
+///
+/// m1 = c^dp mod p, m2 = c^dq mod q, h = qInv * (m1 - m2) mod p, m = m2 + h * q
+///
+/// ___
+/// Author:     Christoph J. Scherr <software@cscherr.de>
+/// License:    MIT
+/// Source:     <https://git.cscherr.de/PlexSheep/plexcryptool/>
+
+use crate::cplex::printing::seperator;
+use crate::algo::oaep::{self, HashFunction, OaepError};
+use crate::math::gcd::egcd_big;
+use crate::math::modexp::modular_exponentiation;
+use crate::math::primality::is_prime;
+
+use core::fmt;
+
+use num::Integer;
+use num_bigint::{BigInt, RandBigInt};
+use num_traits::{One, Signed};
+
+use pyo3::{prelude::*, exceptions::PyValueError, types::PyBytes};
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// the usual public exponent 2^16 + 1
+pub const DEFAULT_E: u64 = 65537;
+/// the smallest modulus size that keygen accepts
+pub const MIN_BITS: u64 = 16;
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// errors for RSA
+pub enum RsaError {
+    /// the requested size in bits is too small
+    TooSmall(u64),
+    /// e is not odd, smaller than 3 or not coprime to φ(n)
+    InvalidExponent(BigInt),
+    /// a factor of the key is not prime
+    NotPrime(BigInt),
+    /// p and q are the same prime
+    EqualPrimes,
+    /// the message or ciphertext is not in [0, n)
+    OutOfRange(BigInt),
+    /// p * q, which does not match the given n
+    WrongFactors(BigInt),
+}
+
+impl fmt::Display for RsaError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RsaError::TooSmall(bits) => write!(f, "{bits} bits are too small, use at least {MIN_BITS}"),
+            RsaError::InvalidExponent(e) => write!(f, "{e} can not be used as exponent"),
+            RsaError::NotPrime(p) => write!(f, "{p} is not prime"),
+            RsaError::EqualPrimes => write!(f, "p and q must be different"),
+            RsaError::OutOfRange(m) => write!(f, "{m} is not in [0, n)"),
+            RsaError::WrongFactors(pq) => write!(f, "p * q = {pq} is not n"),
+        }
+    }
+}
+
+/// generate a random prime with exactly bits bits
+///
+/// the two highest bits are set, so the product of two such primes has exactly 2 * bits bits.
+pub fn random_prime(bits: u64, verbose: bool) -> Result<BigInt, RsaError> {
+    if bits < 2 {
+        return Err(RsaError::TooSmall(bits));
+    }
+    let mut rng = rand::thread_rng();
+    let top: BigInt = BigInt::from(3u8) << (bits - 2);
+    let mut candidates: u64 = 0;
+    loop {
+        candidates += 1;
+        let candidate: BigInt = BigInt::from(rng.gen_biguint(bits)) | &top | BigInt::one();
+        if is_prime(&candidate, false) {
+            if verbose {
+                println!("found a {bits} bit prime after {candidates} candidates: {candidate}");
+            }
+            return Ok(candidate);
+        }
+    }
+}
+
+/// check that c is in [0, n)
+fn check_range(c: &BigInt, n: &BigInt) -> Result<(), RsaError> {
+    if c.is_negative() || c >= n {
+        return Err(RsaError::OutOfRange(c.clone()));
+    }
+    Ok(())
+}
+
+/// textbook encryption c = m^e mod n
+pub fn encrypt(m: &BigInt, n: &BigInt, e: &BigInt, verbose: bool) -> Result<BigInt, RsaError> {
+    check_range(m, n)?;
+    let c = modular_exponentiation(m.clone(), e.clone(), n.clone(), false);
+    if verbose {
+        println!("c = m^e mod n = {m}^{e} mod {n} = {c}");
+    }
+    Ok(c)
+}
+
+/// textbook decryption m = c^d mod n
+pub fn decrypt(c: &BigInt, n: &BigInt, d: &BigInt, verbose: bool) -> Result<BigInt, RsaError> {
+    check_range(c, n)?;
+    let m = modular_exponentiation(c.clone(), d.clone(), n.clone(), false);
+    if verbose {
+        println!("m = c^d mod n = {c}^{d} mod {n} = {m}");
+    }
+    Ok(m)
+}
+
+/// decryption with the chinese remainder theorem
+///
+/// calculates dp, dq and qInv from p, q and d and then does two half size exponentiations
+pub fn decrypt_crt(c: &BigInt, p: &BigInt, q: &BigInt, d: &BigInt, verbose: bool) -> Result<BigInt, RsaError> {
+    let n: BigInt = p * q;
+    check_range(c, &n)?;
+    let dp = d.mod_floor(&(p - 1u8));
+    let dq = d.mod_floor(&(q - 1u8));
+    let (g, x, y) = egcd_big(q, p);
+    if !g.is_one() {
+        return Err(RsaError::EqualPrimes);
+    }
+    let qinv = x.mod_floor(p);
+    if verbose {
+        println!("dp = d mod (p-1) = {d} mod {} = {dp}", p - 1u8);
+        println!("dq = d mod (q-1) = {d} mod {} = {dq}", q - 1u8);
+        println!("egcd(q, p): {q} * {x} + {p} * {y} = {g}");
+        println!("qInv = {x} mod p = {qinv}");
+        seperator();
+    }
+    let m1 = modular_exponentiation(c.mod_floor(p), dp.clone(), p.clone(), false);
+    let m2 = modular_exponentiation(c.mod_floor(q), dq.clone(), q.clone(), false);
+    let h = (&qinv * (&m1 - &m2)).mod_floor(p);
+    let m: BigInt = &m2 + &h * q;
+    if verbose {
+        println!("m1 = c^dp mod p = {c}^{dp} mod {p} = {m1}");
+        println!("m2 = c^dq mod q = {c}^{dq} mod {q} = {m2}");
+        println!("h = qInv * (m1 - m2) mod p = {qinv} * ({m1} - {m2}) mod {p} = {h}");
+        println!("m = m2 + h * q = {m2} + {h} * {q} = {m}");
+    }
+    Ok(m)
+}
+
+/// textbook signature s = m^d mod n
+pub fn sign(m: &BigInt, n: &BigInt, d: &BigInt, verbose: bool) -> Result<BigInt, RsaError> {
+    check_range(m, n)?;
+    let s = modular_exponentiation(m.clone(), d.clone(), n.clone(), false);
+    if verbose {
+        println!("s = m^d mod n = {m}^{d} mod {n} = {s}");
+    }
+    Ok(s)
+}
+
+/// verify a textbook signature: s^e = m (mod n)
+pub fn verify(m: &BigInt, s: &BigInt, n: &BigInt, e: &BigInt, verbose: bool) -> bool {
+    if check_range(s, n).is_err() {
+        if verbose {
+            println!("s is not in [0, n)");
+        }
+        return false;
+    }
+    let v = modular_exponentiation(s.clone(), e.clone(), n.clone(), false);
+    let valid = v == m.mod_floor(n);
+    if verbose {
+        println!("s^e mod n = {s}^{e} mod {n} = {v}");
+        println!("{v} {} {m} => signature is {}", if valid { "==" } else { "!=" }, if valid { "valid" } else { "invalid" });
+    }
+    valid
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[pyclass]
+/// a public RSA key (n, e)
+pub struct RsaPublicKey {
+    #[pyo3(get)]
+    /// the modulus
+    pub n: BigInt,
+    #[pyo3(get)]
+    /// the public exponent
+    pub e: BigInt,
+}
+
+impl RsaPublicKey {
+    /// make a new public key
+    pub fn new(n: BigInt, e: BigInt) -> Self {
+        RsaPublicKey { n, e }
+    }
+
+    /// encrypt m
+    pub fn encrypt(&self, m: &BigInt, verbose: bool) -> Result<BigInt, RsaError> {
+        encrypt(m, &self.n, &self.e, verbose)
+    }
+
+    /// verify the signature s of m
+    pub fn verify(&self, m: &BigInt, s: &BigInt, verbose: bool) -> bool {
+        verify(m, s, &self.n, &self.e, verbose)
+    }
+
+    /// encrypt a message with OAEP padding
+    pub fn encrypt_oaep(&self, message: &[u8], label: &[u8], hash: HashFunction, verbose: bool) -> Result<BigInt, OaepError> {
+        oaep::encrypt(message, label, &self.n, &self.e, hash, verbose)
+    }
+}
+
+impl fmt::Display for RsaPublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "n = {}\ne = {}", self.n, self.e)
+    }
+}
+
+#[pymethods]
+/// python wrappers for the public key
+impl RsaPublicKey {
+    #[new]
+    /// make a new public key
+    pub fn py_new(n: BigInt, e: BigInt) -> Self {
+        RsaPublicKey::new(n, e)
+    }
+
+    #[pyo3(name="encrypt")]
+    #[pyo3(signature=(m, verbose = false))]
+    /// encrypt m
+    pub fn py_encrypt(&self, m: BigInt, verbose: bool) -> PyResult<BigInt> {
+        match self.encrypt(&m, verbose) {
+            Ok(c) => Ok(c),
+            Err(e) => Err(PyValueError::new_err(e.to_string()))
+        }
+    }
+
+    #[pyo3(name="verify")]
+    #[pyo3(signature=(m, s, verbose = false))]
+    /// verify the signature s of m
+    pub fn py_verify(&self, m: BigInt, s: BigInt, verbose: bool) -> bool {
+        self.verify(&m, &s, verbose)
+    }
+
+    #[pyo3(name="encrypt_oaep")]
+    #[pyo3(signature=(message, label = Vec::new(), hash = "sha256", verbose = false))]
+    /// encrypt a message with OAEP padding
+    pub fn py_encrypt_oaep(&self, message: Vec<u8>, label: Vec<u8>, hash: &str, verbose: bool) -> PyResult<BigInt> {
+        let result = HashFunction::from_name(hash).and_then(|h| self.encrypt_oaep(&message, &label, h, verbose));
+        match result {
+            Ok(c) => Ok(c),
+            Err(e) => Err(PyValueError::new_err(e.to_string()))
+        }
+    }
+
+    /// string representation
+    fn __str__(&self) -> String {
+        format!("{}", self)
+    }
+
+    /// debug representation
+    fn __repr__(&self) -> String {
+        format!("{:?}", self)
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[pyclass]
+/// a private RSA key
+pub struct RsaPrivateKey {
+    #[pyo3(get)]
+    /// the modulus
+    pub n: BigInt,
+    #[pyo3(get)]
+    /// the public exponent
+    pub e: BigInt,
+    #[pyo3(get)]
+    /// the private exponent
+    pub d: BigInt,
+    #[pyo3(get)]
+    /// the first prime factor of n
+    pub p: BigInt,
+    #[pyo3(get)]
+    /// the second prime factor of n
+    pub q: BigInt,
+}
+
+impl RsaPrivateKey {
+    /// make a private key from its primes and the public exponent
+    ///
+    /// d is the inverse of e modulo φ(n) = (p-1)(q-1)
+    pub fn from_primes(p: &BigInt, q: &BigInt, e: &BigInt, verbose: bool) -> Result<Self, RsaError> {
+        for f in [p, q] {
+            if !is_prime(f, false) {
+                return Err(RsaError::NotPrime(f.clone()));
+            }
+        }
+        if p == q {
+            return Err(RsaError::EqualPrimes);
+        }
+        if *e < BigInt::from(3) || e.is_even() {
+            return Err(RsaError::InvalidExponent(e.clone()));
+        }
+        let n: BigInt = p * q;
+        let phi: BigInt = (p - 1u8) * (q - 1u8);
+        let (g, x, y) = egcd_big(e, &phi);
+        if verbose {
+            println!("n = p * q = {p} * {q} = {n}");
+            println!("φ(n) = (p-1) * (q-1) = {phi}");
+            println!("egcd(e, φ(n)): {e} * {x} + {phi} * {y} = {g}");
+        }
+        if !g.is_one() {
+            if verbose {
+                println!("gcd(e, φ(n)) != 1 => e can not be used");
+            }
+            return Err(RsaError::InvalidExponent(e.clone()));
+        }
+        let d = x.mod_floor(&phi);
+        if verbose {
+            println!("d = {x} mod φ(n) = {d}");
+        }
+        Ok(RsaPrivateKey { n, e: e.clone(), d, p: p.clone(), q: q.clone() })
+    }
+
+    /// generate a new key with a modulus of bits bits
+    pub fn generate(bits: u64, e: &BigInt, verbose: bool) -> Result<Self, RsaError> {
+        if bits < MIN_BITS {
+            return Err(RsaError::TooSmall(bits));
+        }
+        if *e < BigInt::from(3) || e.is_even() {
+            return Err(RsaError::InvalidExponent(e.clone()));
+        }
+        // the primes must not be 1 mod e
+        let usable = |f: &BigInt| (f - 1u8).gcd(e).is_one();
+        let p = loop {
+            let p = random_prime(bits - bits / 2, verbose)?;
+            if usable(&p) {
+                break p;
+            }
+        };
+        let q = loop {
+            let q = random_prime(bits / 2, verbose)?;
+            if q != p && usable(&q) {
+                break q;
+            }
+        };
+        if verbose {
+            seperator();
+        }
+        RsaPrivateKey::from_primes(&p, &q, e, verbose)
+    }
+
+    /// the public part of the key
+    pub fn public_key(&self) -> RsaPublicKey {
+        RsaPublicKey::new(self.n.clone(), self.e.clone())
+    }
+
+    /// decrypt c with the chinese remainder theorem
+    pub fn decrypt(&self, c: &BigInt, verbose: bool) -> Result<BigInt, RsaError> {
+        decrypt_crt(c, &self.p, &self.q, &self.d, verbose)
+    }
+
+    /// sign m, using the chinese remainder theorem
+    pub fn sign(&self, m: &BigInt, verbose: bool) -> Result<BigInt, RsaError> {
+        decrypt_crt(m, &self.p, &self.q, &self.d, verbose)
+    }
+
+    /// decrypt an OAEP padded ciphertext
+    pub fn decrypt_oaep(&self, c: &BigInt, label: &[u8], hash: HashFunction, verbose: bool) -> Result<Vec<u8>, OaepError> {
+        let m = self.decrypt(c, verbose)?;
+        if verbose {
+            seperator();
+        }
+        let k = oaep::byte_length(&self.n);
+        oaep::decode(&oaep::to_bytes(&m, k), label, k, hash, verbose)
+    }
+}
+
+impl fmt::Display for RsaPrivateKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "n = {}\ne = {}\nd = {}\np = {}\nq = {}", self.n, self.e, self.d, self.p, self.q)
+    }
+}
+
+#[pymethods]
+/// python wrappers for the private key
+impl RsaPrivateKey {
+    #[new]
+    #[pyo3(signature=(p, q, e = BigInt::from(DEFAULT_E), verbose = false))]
+    /// make a private key from its primes and the public exponent
+    pub fn py_new(p: BigInt, q: BigInt, e: BigInt, verbose: bool) -> PyResult<Self> {
+        match RsaPrivateKey::from_primes(&p, &q, &e, verbose) {
+            Ok(k) => Ok(k),
+            Err(e) => Err(PyValueError::new_err(e.to_string()))
+        }
+    }
+
+    #[staticmethod]
+    #[pyo3(name="generate")]
+    #[pyo3(signature=(bits, e = BigInt::from(DEFAULT_E), verbose = false))]
+    /// generate a new key with a modulus of bits bits
+    pub fn py_generate(bits: u64, e: BigInt, verbose: bool) -> PyResult<Self> {
+        match RsaPrivateKey::generate(bits, &e, verbose) {
+            Ok(k) => Ok(k),
+            Err(e) => Err(PyValueError::new_err(e.to_string()))
+        }
+    }
+
+    #[pyo3(name="public_key")]
+    /// the public part of the key
+    pub fn py_public_key(&self) -> RsaPublicKey {
+        self.public_key()
+    }
+
+    #[pyo3(name="decrypt")]
+    #[pyo3(signature=(c, verbose = false))]
+    /// decrypt c with the chinese remainder theorem
+    pub fn py_decrypt(&self, c: BigInt, verbose: bool) -> PyResult<BigInt> {
+        match self.decrypt(&c, verbose) {
+            Ok(m) => Ok(m),
+            Err(e) => Err(PyValueError::new_err(e.to_string()))
+        }
+    }
+
+    #[pyo3(name="sign")]
+    #[pyo3(signature=(m, verbose = false))]
+    /// sign m
+    pub fn py_sign(&self, m: BigInt, verbose: bool) -> PyResult<BigInt> {
+        match self.sign(&m, verbose) {
+            Ok(s) => Ok(s),
+            Err(e) => Err(PyValueError::new_err(e.to_string()))
+        }
+    }
+
+    #[pyo3(name="decrypt_oaep")]
+    #[pyo3(signature=(c, label = Vec::new(), hash = "sha256", verbose = false))]
+    /// decrypt an OAEP padded ciphertext
+    pub fn py_decrypt_oaep(&self, py: Python, c: BigInt, label: Vec<u8>, hash: &str, verbose: bool) -> PyResult<PyObject> {
+        let result = HashFunction::from_name(hash).and_then(|h| self.decrypt_oaep(&c, &label, h, verbose));
+        match result {
+            Ok(message) => Ok(PyBytes::new(py, &message).into()),
+            Err(e) => Err(PyValueError::new_err(e.to_string()))
+        }
+    }
+
+    /// string representation
+    fn __str__(&self) -> String {
+        format!("{}", self)
+    }
+
+    /// debug representation
+    fn __repr__(&self) -> String {
+        format!("{:?}", self)
+    }
+}
+
+#[pyfunction]
+#[pyo3(name = "random_prime")]
+#[pyo3(signature=(bits, verbose = false))]
+/// python wrapper for random_prime
+pub fn py_random_prime(bits: u64, verbose: bool) -> PyResult<BigInt> {
+    match random_prime(bits, verbose) {
+        Ok(p) => Ok(p),
+        Err(e) => Err(PyValueError::new_err(e.to_string()))
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+pub mod test {
+
+    use super::*;
+
+    fn big(n: i64) -> BigInt {
+        BigInt::from(n)
+    }
+
+    #[test]
+    fn test_rsa_textbook() {
+        // the usual lecture example
+        let key = RsaPrivateKey::from_primes(&big(61), &big(53), &big(17), true).unwrap();
+        assert_eq!(key.n, big(3233));
+        assert_eq!(key.d, big(2753));
+        let c = key.public_key().encrypt(&big(65), true).unwrap();
+        assert_eq!(c, big(2790));
+        assert_eq!(decrypt(&c, &key.n, &key.d, true), Ok(big(65)));
+        assert_eq!(key.decrypt(&c, true), Ok(big(65)));
+        let s = key.sign(&big(123), false).unwrap();
+        assert!(key.public_key().verify(&big(123), &s, true));
+        assert!(!key.public_key().verify(&big(124), &s, true));
+        assert_eq!(key.public_key().encrypt(&big(3233), false), Err(RsaError::OutOfRange(big(3233))));
+    }
+
+    #[test]
+    fn test_rsa_invalid() {
+        assert_eq!(RsaPrivateKey::from_primes(&big(61), &big(53), &big(13), false), Err(RsaError::InvalidExponent(big(13))));
+        assert_eq!(RsaPrivateKey::from_primes(&big(61), &big(51), &big(17), false), Err(RsaError::NotPrime(big(51))));
+        assert_eq!(RsaPrivateKey::from_primes(&big(61), &big(61), &big(17), false), Err(RsaError::EqualPrimes));
+        assert_eq!(RsaPrivateKey::generate(8, &big(3), false), Err(RsaError::TooSmall(8)));
+        assert_eq!(RsaPrivateKey::generate(64, &big(4), false), Err(RsaError::InvalidExponent(big(4))));
+    }
+
+    #[test]
+    fn test_rsa_generate() {
+        let p = random_prime(100, false).unwrap();
+        assert_eq!(p.bits(), 100);
+        assert!(is_prime(&p, false));
+        for bits in [16, 17, 512] {
+            let key = RsaPrivateKey::generate(bits, &BigInt::from(DEFAULT_E), false).unwrap();
+            assert_eq!(key.n.bits(), bits);
+            let m = &key.n - 2u8;
+            let c = key.public_key().encrypt(&m, false).unwrap();
+            assert_eq!(key.decrypt(&c, false), Ok(m.clone()));
+            assert_eq!(decrypt(&c, &key.n, &key.d, false), Ok(m));
+        }
+        let key = RsaPrivateKey::generate(64, &big(3), false).unwrap();
+        assert_eq!((&key.e * &key.d).mod_floor(&((&key.p - 1u8) * (&key.q - 1u8))), BigInt::one());
+    }
+}
@@ -0,0 +1,342 @@
+#![allow(dead_code)]
+/// # classic attacks on textbook RSA
+///
+/// - common modulus: the same m is encrypted under (n, e1) and (n, e2) with gcd(e1, e2) = 1.
+///   With a * e1 + b * e2 = 1 from the extended euclidean algorithm, m = c1^a * c2^b mod n.
+/// - håstad's broadcast attack: the same m is encrypted under (n_i, e) for e different moduli.
+///   The chinese remainder theorem gives m^e mod prod(n_i), which is m^e itself since m < n_i,
+///   so m is the integer e-th root.
+/// - fermat factorisation: if |p - q| is small, n = a² - b² with a close to sqrt(n).
+/// - recovery of p and q from (n, e, d): ed - 1 = 2^t * r is a multiple of λ(n), so for most g
+///   the sequence g^r, g^2r, ... mod n contains a nontrivial square root of 1.
+///
+/// ___
+/// Author:     Christoph J. Scherr <software@cscherr.de>
+/// License:    MIT
+/// Source:     <https://git.cscherr.de/PlexSheep/plexcryptool/>
+
+use crate::cplex::printing::seperator;
+use crate::math::crt::{crt, CrtError};
+use crate::math::gcd::{egcd_big, mod_inverse};
+use crate::math::modexp::modular_exponentiation;
+
+use core::fmt;
+
+use num::Integer;
+use num_bigint::BigInt;
+use num_traits::{One, Zero, Signed};
+
+use pyo3::{prelude::*, exceptions::PyValueError};
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// how many steps fermat factorisation takes by default
+pub const DEFAULT_FERMAT_STEPS: u64 = 1 << 20;
+/// how many bases are tried to recover p and q from d
+pub const MAX_BASES: u64 = 100;
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// errors for the RSA attacks
+pub enum AttackError {
+    /// the public exponents are not coprime, contains their gcd
+    NotCoprime(BigInt),
+    /// a ciphertext is not invertible mod n, contains the gcd with n, which is a factor
+    NotInvertible(BigInt),
+    /// the combined ciphertext is not a perfect e-th power
+    NotAPower,
+    /// the ciphertexts could not be combined
+    Crt(CrtError),
+    /// n must be odd and larger than 1
+    InvalidModulus(BigInt),
+    /// ed - 1 is not a multiple of λ(n)
+    InvalidExponent,
+    /// the attack did not succeed
+    NotFound,
+    /// the public exponent for the broadcast attack must be at least 2
+    ExponentTooSmall(u32),
+}
+
+impl fmt::Display for AttackError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AttackError::NotCoprime(g) => write!(f, "gcd(e1, e2) = {g} != 1"),
+            AttackError::NotInvertible(g) => write!(f, "ciphertext is not invertible, gcd with n is {g}"),
+            AttackError::NotAPower => write!(f, "the combined ciphertext is not a perfect power, more ciphertexts are needed"),
+            AttackError::Crt(e) => write!(f, "{e}"),
+            AttackError::InvalidModulus(n) => write!(f, "{n} is not an odd modulus larger than 1"),
+            AttackError::InvalidExponent => write!(f, "ed - 1 is not a multiple of λ(n)"),
+            AttackError::NotFound => write!(f, "the attack did not succeed"),
+            AttackError::ExponentTooSmall(e) => write!(f, "e = {e} is too small, it must be at least 2"),
+        }
+    }
+}
+
+impl From<CrtError> for AttackError {
+    fn from(e: CrtError) -> Self {
+        AttackError::Crt(e)
+    }
+}
+
+/// c^k mod n for any integer k, negative k use the inverse of c
+fn signed_pow(c: &BigInt, k: &BigInt, n: &BigInt) -> Result<BigInt, AttackError> {
+    if k.is_negative() {
+        let inv = match mod_inverse(c, n) {
+            Ok(inv) => inv,
+            Err(g) => return Err(AttackError::NotInvertible(g))
+        };
+        Ok(modular_exponentiation(inv, -k, n.clone(), false))
+    }
+    else {
+        Ok(modular_exponentiation(c.mod_floor(n), k.clone(), n.clone(), false))
+    }
+}
+
+/// common modulus attack
+///
+/// recovers m from c1 = m^e1 mod n and c2 = m^e2 mod n if gcd(e1, e2) = 1
+pub fn common_modulus(n: &BigInt, e1: &BigInt, c1: &BigInt, e2: &BigInt, c2: &BigInt, verbose: bool)
+    -> Result<BigInt, AttackError> {
+    let (g, a, b) = egcd_big(e1, e2);
+    if verbose {
+        println!("egcd(e1, e2): {e1} * {a} + {e2} * {b} = {g}");
+    }
+    if !g.is_one() {
+        return Err(AttackError::NotCoprime(g));
+    }
+    let x1 = signed_pow(c1, &a, n)?;
+    let x2 = signed_pow(c2, &b, n)?;
+    let m = (&x1 * &x2).mod_floor(n);
+    if verbose {
+        println!("c1^a = {c1}^{a} = {x1} (mod {n})");
+        println!("c2^b = {c2}^{b} = {x2} (mod {n})");
+        println!("m = c1^a * c2^b = m^(a * e1 + b * e2) = {m} (mod {n})");
+    }
+    Ok(m)
+}
+
+/// håstad's broadcast attack
+///
+/// recovers m from c_i = m^e mod n_i. This works if m^e < prod(n_i), which holds for e pairwise
+/// coprime moduli if m < min(n_i).
+pub fn hastad(ciphertexts: &[BigInt], moduli: &[BigInt], e: u32, verbose: bool) -> Result<BigInt, AttackError> {
+    if e < 2 {
+        return Err(AttackError::ExponentTooSmall(e));
+    }
+    let (x, product) = crt(ciphertexts, moduli, verbose)?;
+    let m = x.nth_root(e);
+    if verbose {
+        seperator();
+        println!("m^e = {x} (mod {product})");
+        println!("m = floor(({x})^(1/{e})) = {m}");
+    }
+    if m.pow(e) != x {
+        if verbose {
+            println!("m^{e} != {x}");
+        }
+        return Err(AttackError::NotAPower);
+    }
+    Ok(m)
+}
+
+/// fermat factorisation
+///
+/// searches a = ceil(sqrt(n)), a + 1, ... until a² - n = b² is a square, then n = (a+b)(a-b).
+/// Returns (p, q) with p >= q. This is fast if |p - q| is small.
+pub fn fermat(n: &BigInt, max_steps: u64, verbose: bool) -> Result<(BigInt, BigInt), AttackError> {
+    if *n <= BigInt::one() || n.is_even() {
+        return Err(AttackError::InvalidModulus(n.clone()));
+    }
+    let mut a = n.sqrt();
+    if &a * &a < *n {
+        a += 1u8;
+    }
+    for step in 0..max_steps {
+        let b2: BigInt = &a * &a - n;
+        let b = b2.sqrt();
+        let square = &b * &b == b2;
+        if verbose {
+            println!("{step}. a = {a}, a² - n = {b2}{}", if square { format!(" = {b}²") } else { String::new() });
+        }
+        if square {
+            let p: BigInt = &a + &b;
+            let q: BigInt = &a - &b;
+            if verbose {
+                seperator();
+                println!("n = a² - b² = (a + b)(a - b) = {p} * {q}");
+            }
+            return Ok((p, q));
+        }
+        a += 1u8;
+    }
+    Err(AttackError::NotFound)
+}
+
+/// recover p and q from the key (n, e, d)
+///
+/// ed - 1 = 2^t * r with r odd. For bases g = 2, 3, ... the sequence x_i = g^(2^i * r) mod n ends
+/// in 1. If some x_i != ±1 has x_i² = 1, then gcd(x_i - 1, n) is a factor. Returns (p, q) with
+/// p >= q.
+pub fn factor_with_d(n: &BigInt, e: &BigInt, d: &BigInt, verbose: bool) -> Result<(BigInt, BigInt), AttackError> {
+    if *n <= BigInt::one() || n.is_even() {
+        return Err(AttackError::InvalidModulus(n.clone()));
+    }
+    let k: BigInt = e * d - 1u8;
+    if k.is_zero() || k.is_odd() {
+        return Err(AttackError::InvalidExponent);
+    }
+    let t = k.trailing_zeros().expect("k is not zero");
+    let r: BigInt = &k >> t;
+    let nm1: BigInt = n - 1u8;
+    if verbose {
+        println!("ed - 1 = {k} = 2^{t} * {r}");
+    }
+    for g in 2..(MAX_BASES + 2) {
+        let g = BigInt::from(g);
+        if g >= *n {
+            break;
+        }
+        let common = g.gcd(n);
+        if !common.is_one() {
+            if verbose {
+                println!("gcd({g}, n) = {common}");
+            }
+            let other = n / &common;
+            return Ok((common.clone().max(other.clone()), common.min(other)));
+        }
+        let mut x = modular_exponentiation(g.clone(), r.clone(), n.clone(), false);
+        if verbose {
+            seperator();
+            println!("g = {g}\ng^r = {x} (mod n)");
+        }
+        let mut i = 0;
+        while !x.is_one() && x != nm1 && i < t {
+            i += 1;
+            let y = (&x * &x).mod_floor(n);
+            if verbose {
+                println!("g^(2^{i} * r) = {y} (mod n)");
+            }
+            if y.is_one() {
+                let p = (&x - 1u8).gcd(n);
+                let q = n / &p;
+                if verbose {
+                    seperator();
+                    println!("{x}² = 1 (mod n) and {x} != ±1");
+                    println!("gcd({x} - 1, n) = {p}\nn / {p} = {q}");
+                }
+                return Ok((p.clone().max(q.clone()), p.min(q)));
+            }
+            x = y;
+        }
+        // x = g^(ed-1) must be 1, if the sequence ended without reaching ±1
+        if i == t && !x.is_one() {
+            if verbose {
+                println!("g^(ed-1) != 1 (mod n)");
+            }
+            return Err(AttackError::InvalidExponent);
+        }
+    }
+    Err(AttackError::NotFound)
+}
+
+#[pyfunction]
+#[pyo3(name = "common_modulus")]
+#[pyo3(signature=(n, e1, c1, e2, c2, verbose = false))]
+/// python wrapper for common_modulus
+pub fn py_common_modulus(n: BigInt, e1: BigInt, c1: BigInt, e2: BigInt, c2: BigInt, verbose: bool) -> PyResult<BigInt> {
+    match common_modulus(&n, &e1, &c1, &e2, &c2, verbose) {
+        Ok(m) => Ok(m),
+        Err(e) => Err(PyValueError::new_err(e.to_string()))
+    }
+}
+
+#[pyfunction]
+#[pyo3(name = "hastad")]
+#[pyo3(signature=(ciphertexts, moduli, e, verbose = false))]
+/// python wrapper for hastad
+pub fn py_hastad(ciphertexts: Vec<BigInt>, moduli: Vec<BigInt>, e: u32, verbose: bool) -> PyResult<BigInt> {
+    match hastad(&ciphertexts, &moduli, e, verbose) {
+        Ok(m) => Ok(m),
+        Err(e) => Err(PyValueError::new_err(e.to_string()))
+    }
+}
+
+#[pyfunction]
+#[pyo3(name = "fermat")]
+#[pyo3(signature=(n, max_steps = DEFAULT_FERMAT_STEPS, verbose = false))]
+/// python wrapper for fermat
+pub fn py_fermat(n: BigInt, max_steps: u64, verbose: bool) -> PyResult<(BigInt, BigInt)> {
+    match fermat(&n, max_steps, verbose) {
+        Ok(f) => Ok(f),
+        Err(e) => Err(PyValueError::new_err(e.to_string()))
+    }
+}
+
+#[pyfunction]
+#[pyo3(name = "factor_with_d")]
+#[pyo3(signature=(n, e, d, verbose = false))]
+/// python wrapper for factor_with_d
+pub fn py_factor_with_d(n: BigInt, e: BigInt, d: BigInt, verbose: bool) -> PyResult<(BigInt, BigInt)> {
+    match factor_with_d(&n, &e, &d, verbose) {
+        Ok(f) => Ok(f),
+        Err(e) => Err(PyValueError::new_err(e.to_string()))
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+pub mod test {
+
+    use super::*;
+    use crate::algo::rsa::{RsaPrivateKey, DEFAULT_E};
+
+    use std::str::FromStr;
+
+    fn big(n: i64) -> BigInt {
+        BigInt::from(n)
+    }
+
+    #[test]
+    fn test_common_modulus() {
+        let n = big(3233);
+        let m = big(65);
+        let c1 = modular_exponentiation(m.clone(), big(17), n.clone(), false);
+        let c2 = modular_exponentiation(m.clone(), big(7), n.clone(), false);
+        assert_eq!(common_modulus(&n, &big(17), &c1, &big(7), &c2, true), Ok(m.clone()));
+        assert_eq!(common_modulus(&n, &big(7), &c2, &big(17), &c1, false), Ok(m));
+        assert_eq!(common_modulus(&n, &big(15), &c1, &big(21), &c2, false), Err(AttackError::NotCoprime(big(3))));
+    }
+
+    #[test]
+    fn test_hastad() {
+        let m = BigInt::from_str("123456789012345678901234567890").unwrap();
+        let keys: Vec<RsaPrivateKey> = (0..3).map(|_| RsaPrivateKey::generate(128, &big(3), false).unwrap()).collect();
+        let moduli: Vec<BigInt> = keys.iter().map(|k| k.n.clone()).collect();
+        let ciphertexts: Vec<BigInt> = keys.iter().map(|k| k.public_key().encrypt(&m, false).unwrap()).collect();
+        assert_eq!(hastad(&ciphertexts, &moduli, 3, true), Ok(m));
+        // m^3 is larger than n_1 * n_2
+        assert_eq!(hastad(&ciphertexts[..2], &moduli[..2], 3, false), Err(AttackError::NotAPower));
+        assert_eq!(hastad(&ciphertexts, &moduli, 0, false), Err(AttackError::ExponentTooSmall(0)));
+    }
+
+    #[test]
+    fn test_fermat() {
+        assert_eq!(fermat(&big(5959), 10, true), Ok((big(101), big(59))));
+        let p = BigInt::from_str("57896044618658097711785492504343953926634992332820282019728804349636466054719").unwrap();
+        let q = BigInt::from_str("57896044618658097711785492504343953926634992332820282019728804349635466054657").unwrap();
+        assert_eq!(fermat(&(&p * &q), 10, false), Ok((p, q)));
+        assert_eq!(fermat(&big(3 * 1009), 10, false), Err(AttackError::NotFound));
+        assert_eq!(fermat(&big(5958), 10, false), Err(AttackError::InvalidModulus(big(5958))));
+    }
+
+    #[test]
+    fn test_factor_with_d() {
+        assert_eq!(factor_with_d(&big(3233), &big(17), &big(2753), true), Ok((big(61), big(53))));
+        assert_eq!(factor_with_d(&big(3233), &big(17), &big(2752), false), Err(AttackError::InvalidExponent));
+        let key = RsaPrivateKey::generate(512, &BigInt::from(DEFAULT_E), false).unwrap();
+        let (p, q) = factor_with_d(&key.n, &key.e, &key.d, false).unwrap();
+        assert_eq!(&p * &q, key.n);
+        assert!(p == key.p || p == key.q);
+    }
+}
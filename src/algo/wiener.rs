@@ -0,0 +1,149 @@
+#![allow(dead_code)]
+/// # wiener's attack on RSA
+///
+/// If the private exponent d of an RSA key (n, e) is small, d < n^(1/4) / 3, then k/d is a
+/// convergent of the continued fraction of e/n, where ed - k * φ(n) = 1. For every convergent k/d,
+/// φ = (ed - 1) / k is a candidate for φ(n). p and q are then the roots of
+/// x² - (n - φ + 1)x + n, which are integers only for the right candidate.
+///
+/// ___
+/// Author:     Christoph J. Scherr <software@cscherr.de>
+/// License:    MIT
+/// Source:     <https://git.cscherr.de/PlexSheep/plexcryptool/>
+
+use crate::cplex::printing::seperator;
+use crate::math::contfrac::{expand, convergents};
+
+use core::fmt;
+
+use num::Integer;
+use num_bigint::BigInt;
+use num_traits::{Zero, Signed};
+
+use pyo3::{prelude::*, exceptions::PyValueError};
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// errors for wiener's attack
+pub enum WienerError {
+    /// n and e must be positive
+    InvalidKey,
+    /// no convergent gave a factorisation of n, d is probably too large
+    NotFound,
+}
+
+impl fmt::Display for WienerError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WienerError::InvalidKey => write!(f, "n and e must be positive"),
+            WienerError::NotFound => write!(f, "no convergent of e/n revealed d, d might be too large"),
+        }
+    }
+}
+
+/// try to factor n with the candidate k/d
+///
+/// returns p and q with p >= q if k/d gives the correct φ(n)
+fn try_convergent(n: &BigInt, e: &BigInt, k: &BigInt, d: &BigInt) -> Result<(BigInt, BigInt), String> {
+    if k.is_zero() {
+        return Err(String::from("k = 0"));
+    }
+    let ed1: BigInt = e * d - 1u8;
+    if !ed1.is_multiple_of(k) {
+        return Err(String::from("k does not divide ed-1"));
+    }
+    let phi: BigInt = &ed1 / k;
+    let s: BigInt = n - &phi + 1u8;
+    let discriminant: BigInt = &s * &s - n * 4u8;
+    if discriminant.is_negative() {
+        return Err(format!("φ = {phi}, (n-φ+1)² - 4n < 0"));
+    }
+    let root = discriminant.sqrt();
+    if &root * &root != discriminant {
+        return Err(format!("φ = {phi}, (n-φ+1)² - 4n is not a square"));
+    }
+    let p: BigInt = (&s + &root) / 2u8;
+    let q: BigInt = (&s - &root) / 2u8;
+    if &p * &q != *n {
+        return Err(format!("φ = {phi}, p * q != n"));
+    }
+    Ok((p, q))
+}
+
+/// wiener's attack
+///
+/// recovers (d, p, q) from the public key (n, e) if d < n^(1/4) / 3. The verbose output shows the
+/// continued fraction of e/n and a table of all convergents that were tested.
+pub fn wiener(n: &BigInt, e: &BigInt, verbose: bool) -> Result<(BigInt, BigInt, BigInt), WienerError> {
+    if !n.is_positive() || !e.is_positive() {
+        return Err(WienerError::InvalidKey);
+    }
+    if verbose {
+        println!("expanding e/n:");
+    }
+    let quotients = expand(e, n, verbose).expect("n is not zero");
+    if verbose {
+        seperator();
+        println!("{:>4}  {:<24}  {:<48}  result", "i", "a_i", "k_i/d_i");
+    }
+    for (i, ((k, d), a)) in convergents(&quotients).iter().zip(&quotients).enumerate() {
+        let result = try_convergent(n, e, k, d);
+        if verbose {
+            let status = match &result {
+                Ok((p, q)) => format!("p = {p}, q = {q}"),
+                Err(reason) => reason.clone()
+            };
+            println!("{:>4}  {:<24}  {:<48}  {status}", i, a.to_string(), format!("{k}/{d}"));
+        }
+        if let Ok((p, q)) = result {
+            if verbose {
+                seperator();
+                println!("d = {d}\np = {p}\nq = {q}");
+            }
+            return Ok((d.clone(), p, q));
+        }
+    }
+    Err(WienerError::NotFound)
+}
+
+#[pyfunction]
+#[pyo3(name = "wiener")]
+#[pyo3(signature=(n, e, verbose = false))]
+/// python wrapper for wiener
+pub fn py_wiener(n: BigInt, e: BigInt, verbose: bool) -> PyResult<(BigInt, BigInt, BigInt)> {
+    match wiener(&n, &e, verbose) {
+        Ok(r) => Ok(r),
+        Err(e) => Err(PyValueError::new_err(e.to_string()))
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+pub mod test {
+
+    use super::*;
+
+    use std::str::FromStr;
+
+    #[test]
+    fn test_wiener_small() {
+        let n = BigInt::from(90581);
+        let e = BigInt::from(17993);
+        assert_eq!(wiener(&n, &e, true), Ok((BigInt::from(5), BigInt::from(379), BigInt::from(239))));
+        assert_eq!(wiener(&n, &BigInt::from(0), false), Err(WienerError::InvalidKey));
+    }
+
+    #[test]
+    fn test_wiener_512() {
+        let n = BigInt::from_str("7893835550250389359297216688661450288929008118668349638445540095793204089892549599578948620842920833647521401008507076106394071770921505744490479825984079").unwrap();
+        let e = BigInt::from_str("7129298479713658819448642576793555948030374939274394247009702343168241584761324450909739399326439589342474600350287234696108646053366143090395246725595889").unwrap();
+        let d = BigInt::from_str("1268735707553227622203294139171351617").unwrap();
+        let p = BigInt::from_str("93301183127897979887721110794876656050715228300501085893840517092872032802953").unwrap();
+        let q = BigInt::from_str("84605953382493111959809156664335035311302448996925757115306117397564881630743").unwrap();
+        assert_eq!(wiener(&n, &e, false), Ok((d, p, q)));
+        // same key with a large d
+        let e = BigInt::from_str("3374545527491052197440387481868860868719474695921731966852784420998768459146353091782787428261613495775235335752445234784189737316789807780733963344210343").unwrap();
+        assert_eq!(wiener(&n, &e, false), Err(WienerError::NotFound));
+    }
+}
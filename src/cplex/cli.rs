@@ -6,7 +6,7 @@
 /// License:    MIT
 /// Source:     <https://git.cscherr.de/PlexSheep/plexcryptool/>
 ///
-use clap::{Args, Parser, Subcommand};
+use clap::{Args, Parser, Subcommand, ValueEnum};
 use clap_num::maybe_hex;
 
 ///////////////////////////////////////////////////////////////////////////////////////////////////
@@ -39,6 +39,18 @@ pub enum Commands {
     Binary(BinaryCommand),
     /// Use custom algorithms
     Algo(AlgoCommand),
+    /// Attack cryptosystems
+    Attack(AttackCommand),
+    /// Use textbook RSA
+    Rsa(RsaCommand),
+    /// Use the rabin cryptosystem
+    Rabin(RabinCommand),
+    /// Use the paillier cryptosystem
+    Paillier(PaillierCommand),
+    /// Use elgamal in a prime field or on an elliptic curve
+    Elgamal(ElgamalCommand),
+    /// Simulate protocols
+    Protocol(ProtocolCommand),
     /// Print version
     Version,
 }
@@ -61,6 +73,18 @@ pub struct AlgoCommand {
     pub action: AlgoActions
 }
 
+#[derive(Args, Clone, Debug, PartialEq, Eq)]
+pub struct AttackCommand {
+    #[command(subcommand)]
+    pub action: AttackActions
+}
+
+#[derive(Args, Clone, Debug, PartialEq, Eq)]
+pub struct RsaCommand {
+    #[command(subcommand)]
+    pub action: RsaActions
+}
+
 #[derive(Subcommand, Clone, Debug, PartialEq, Eq)]
 pub enum MathActions {
     #[command(name="modexp")]
@@ -68,8 +92,10 @@ pub enum MathActions {
     Modexp(ModexpArgs),
     /// perform modular reduction
     Modred(ModredArgs),
-    /// p minus 1 prime test
+    /// p minus 1 factorization
     Pm1(PM1Args),
+    /// williams p plus 1 factorization
+    Pp1(PP1Args),
     /// calculate in a gallois field
     /// includes Eliptic curves
     Gallois(GalloisAction),
@@ -77,13 +103,86 @@ pub enum MathActions {
     Gcd(GcdArgs),
     /// factorize a natural number
     Factorize(FactorizeArgs),
+    /// test if a number is prime
+    Primality(PrimalityArgs),
+    /// solve a system of congruences with the chinese remainder theorem
+    Crt(CrtArgs),
+    /// euler's totient function φ(n)
+    Totient(ArithArgs),
+    /// carmichael's function λ(n)
+    Carmichael(ArithArgs),
+    /// multiplicative order of a mod n
+    Order(OrderArgs),
+    /// find primitive roots mod n
+    PrimitiveRoot(PrimitiveRootArgs),
+    /// legendre, jacobi or kronecker symbol (a/n)
+    Jacobi(JacobiArgs),
+    /// all square roots of a mod n for a known factorisation of n
+    Sqrt(SqrtArgs),
 }
 
 #[derive(Args, Clone, Debug, PartialEq, Eq)]
 pub struct ModexpArgs {
     pub base: String,
     pub exp: String,
-    pub field: String
+    pub field: String,
+    #[arg(long, value_enum)]
+    /// exponentiation strategy, also reports the number of squarings and multiplications
+    pub method: Option<ModexpMethod>,
+    #[arg(long, default_value_t = 0)]
+    /// window size for k-ary and sliding window (at most 16), 0 chooses one from the size of the exponent
+    pub window: usize,
+    #[arg(long, value_enum)]
+    /// reduction context, montgomery for odd and plain for even moduli by default
+    pub reduction: Option<ModexpReduction>,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+/// strategies for modular exponentiation
+pub enum ModexpMethod {
+    /// left to right binary square and multiply
+    LeftToRight,
+    /// right to left binary square and multiply
+    RightToLeft,
+    /// fixed windows with a table of all powers
+    KAry,
+    /// sliding windows with a table of the odd powers
+    SlidingWindow,
+    /// montgomery ladder, the same operations for every bit
+    Ladder,
+}
+
+impl From<ModexpMethod> for crate::math::modexp::ExpMethod {
+    fn from(method: ModexpMethod) -> Self {
+        match method {
+            ModexpMethod::LeftToRight => crate::math::modexp::ExpMethod::LeftToRight,
+            ModexpMethod::RightToLeft => crate::math::modexp::ExpMethod::RightToLeft,
+            ModexpMethod::KAry => crate::math::modexp::ExpMethod::KAry,
+            ModexpMethod::SlidingWindow => crate::math::modexp::ExpMethod::SlidingWindow,
+            ModexpMethod::Ladder => crate::math::modexp::ExpMethod::Ladder,
+        }
+    }
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+/// reduction contexts for modular exponentiation
+pub enum ModexpReduction {
+    /// a full division for every reduction
+    Plain,
+    /// barrett reduction
+    Barrett,
+    /// montgomery multiplication, odd moduli only
+    Montgomery,
+}
+
+impl From<ModexpReduction> for crate::math::reduction::ReductionKind {
+    fn from(kind: ModexpReduction) -> Self {
+        match kind {
+            ModexpReduction::Plain => crate::math::reduction::ReductionKind::Plain,
+            ModexpReduction::Barrett => crate::math::reduction::ReductionKind::Barrett,
+            ModexpReduction::Montgomery => crate::math::reduction::ReductionKind::Montgomery,
+        }
+    }
 }
 
 #[derive(Args, Clone, Debug, PartialEq, Eq)]
@@ -96,10 +195,24 @@ pub struct ModredArgs {
 
 #[derive(Args, Clone, Debug, PartialEq, Eq)]
 pub struct PM1Args {
-    #[clap(value_parser=maybe_hex::<u128>)]
-    pub n: u128,
-    #[clap(value_parser=maybe_hex::<u128>)]
-    pub max_prime: u128,
+    pub n: String,
+    #[clap(value_parser=maybe_hex::<u64>)]
+    /// stage 1 bound, the highest prime power to use
+    pub b1: u64,
+    #[arg(long, default_value_t = 0)]
+    /// stage 2 bound, stage 2 is skipped if this is not larger than b1
+    pub b2: u64,
+}
+
+#[derive(Args, Clone, Debug, PartialEq, Eq)]
+pub struct PP1Args {
+    pub n: String,
+    #[clap(value_parser=maybe_hex::<u64>)]
+    /// stage 1 bound, the highest prime power to use
+    pub b1: u64,
+    #[arg(long, default_value_t = 0)]
+    /// stage 2 bound, stage 2 is skipped if this is not larger than b1
+    pub b2: u64,
 }
 
 #[derive(Args, Clone, Debug, PartialEq, Eq)]
@@ -127,8 +240,154 @@ pub struct GcdArgs {
 
 #[derive(Args, Clone, Debug, PartialEq, Eq)]
 pub struct FactorizeArgs {
-    #[clap(value_parser=maybe_hex::<u128>)]
-    pub n: u128,
+    pub n: String,
+    #[arg(long, value_enum, default_value_t = FactorizeAlgorithm::Trial)]
+    /// which algorithm to use
+    pub algorithm: FactorizeAlgorithm,
+    #[arg(long, default_value_t = 2000)]
+    /// smoothness bound for ecm
+    pub b1: u64,
+    #[arg(long, default_value_t = 100)]
+    /// how many curves ecm should try
+    pub curves: usize,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+/// available factorisation algorithms
+pub enum FactorizeAlgorithm {
+    /// trial division, only for n < 2^128
+    Trial,
+    /// pollard's rho method with brent's cycle detection
+    Rho,
+    /// lenstra's elliptic curve factorization
+    Ecm,
+}
+
+#[derive(Args, Clone, Debug, PartialEq, Eq)]
+/// arguments for the primality test
+pub struct PrimalityArgs {
+    /// the number to test
+    pub n: String,
+    #[arg(long, value_enum, default_value_t = PrimalityMethod::Auto)]
+    /// which test to use
+    pub method: PrimalityMethod,
+    #[arg(long, default_value_t = 20)]
+    /// rounds for miller-rabin with random bases
+    pub rounds: usize,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+/// available primality tests
+pub enum PrimalityMethod {
+    /// choose a deterministic test depending on the size of n
+    Auto,
+    /// trial division up to sqrt(n)
+    Trial,
+    /// miller-rabin with random bases
+    MillerRabin,
+    /// baillie-psw
+    Bpsw,
+}
+
+#[derive(Args, Clone, Debug, PartialEq, Eq)]
+/// arguments for the chinese remainder theorem
+pub struct CrtArgs {
+    #[arg(long, value_delimiter = ',', required = true, allow_hyphen_values = true)]
+    /// residues a_i, seperated by commas
+    pub residues: Vec<String>,
+    #[arg(long, value_delimiter = ',', required = true)]
+    /// moduli m_i, seperated by commas
+    pub moduli: Vec<String>,
+}
+
+#[derive(Args, Clone, Debug, PartialEq, Eq)]
+/// arguments for number theoretic functions of n
+pub struct ArithArgs {
+    /// the number n
+    pub n: String,
+}
+
+#[derive(Args, Clone, Debug, PartialEq, Eq)]
+/// arguments for the multiplicative order
+pub struct OrderArgs {
+    #[clap(allow_hyphen_values=true)]
+    /// the element a
+    pub a: String,
+    /// the modulus n
+    pub n: String,
+}
+
+#[derive(Args, Clone, Debug, PartialEq, Eq)]
+/// arguments for primitive roots
+pub struct PrimitiveRootArgs {
+    /// the modulus n
+    pub n: String,
+    #[arg(long, default_value_t = false)]
+    /// list all primitive roots instead of the smallest
+    pub all: bool,
+    #[arg(long, default_value_t = false)]
+    /// only test if there are primitive roots mod n
+    pub exists: bool,
+}
+
+#[derive(Args, Clone, Debug, PartialEq, Eq)]
+/// arguments for the legendre, jacobi and kronecker symbols
+pub struct JacobiArgs {
+    #[clap(allow_hyphen_values=true)]
+    /// the number a
+    pub a: String,
+    #[clap(allow_hyphen_values=true)]
+    /// the number n
+    pub n: String,
+    #[arg(long, value_enum, default_value_t = SymbolKind::Jacobi)]
+    /// which symbol to calculate
+    pub symbol: SymbolKind,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+/// available symbols
+pub enum SymbolKind {
+    /// legendre symbol, n must be an odd prime
+    Legendre,
+    /// jacobi symbol, n must be odd and positive
+    Jacobi,
+    /// kronecker symbol, n can be any integer
+    Kronecker,
+}
+
+#[derive(Args, Clone, Debug, PartialEq, Eq)]
+/// arguments for modular square roots
+pub struct SqrtArgs {
+    #[clap(allow_hyphen_values=true)]
+    /// the number a
+    pub a: String,
+    #[arg(long, value_delimiter = ',', required = true)]
+    /// prime factors of n, seperated by commas, repeated for prime powers
+    pub factors: Vec<String>,
+    #[arg(long, value_enum, default_value_t = SqrtAlgorithm::TonelliShanks)]
+    /// algorithm for the roots modulo the primes
+    pub method: SqrtAlgorithm,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+/// algorithms for square roots modulo a prime
+pub enum SqrtAlgorithm {
+    /// the tonelli-shanks loop of the gallois field
+    TonelliShanks,
+    /// cipolla's algorithm in F_p²
+    Cipolla,
+    /// a^((p+1)/4) for p = 3 mod 4, tonelli-shanks for other primes
+    Blum,
+}
+
+impl From<SqrtAlgorithm> for crate::math::modsqrt::SqrtMethod {
+    fn from(method: SqrtAlgorithm) -> Self {
+        match method {
+            SqrtAlgorithm::TonelliShanks => crate::math::modsqrt::SqrtMethod::TonelliShanks,
+            SqrtAlgorithm::Cipolla => crate::math::modsqrt::SqrtMethod::Cipolla,
+            SqrtAlgorithm::Blum => crate::math::modsqrt::SqrtMethod::Blum,
+        }
+    }
 }
 
 #[derive(Subcommand, Clone, Debug, PartialEq, Eq)]
@@ -140,7 +399,9 @@ pub enum GalloisActions {
     /// calculate the (multiplicative) inverse of n
     Inverse(GalloisInverseArgs),
     /// eliptic curves
-    ECC(ECCAction)
+    ECC(ECCAction),
+    /// discrete logarithm, find x with g^x = h
+    Dlog(GalloisDlogArgs),
 }
 
 #[derive(Args, Clone, Debug, PartialEq, Eq)]
@@ -160,6 +421,47 @@ pub struct GalloisInverseArgs {
     pub n: u128,
 }
 
+#[derive(Args, Clone, Debug, PartialEq, Eq)]
+/// arguments for the discrete logarithm
+pub struct GalloisDlogArgs {
+    #[clap(value_parser=maybe_hex::<u128>)]
+    /// the base g
+    pub g: u128,
+    #[clap(value_parser=maybe_hex::<u128>)]
+    /// the power h = g^x
+    pub h: u128,
+    #[arg(long, value_enum, default_value_t = DlogMethod::Bsgs)]
+    /// which algorithm to use
+    pub method: DlogMethod,
+    #[arg(long, value_parser=maybe_hex::<u128>)]
+    /// order of g for rho, defaults to the order of the multiplicative group
+    pub order: Option<u128>,
+    #[arg(long, value_parser=maybe_hex::<u128>, default_value_t = 0)]
+    /// lower bound of the interval for kangaroo
+    pub lower: u128,
+    #[arg(long, value_parser=maybe_hex::<u128>)]
+    /// upper bound of the interval for kangaroo, defaults to the order of the multiplicative group
+    pub upper: Option<u128>,
+    #[arg(long, default_value_t = 0)]
+    /// largest prime of the factor base for index calculus, 0 chooses one automatically
+    pub bound: u64,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+/// available algorithms for discrete logarithms
+pub enum DlogMethod {
+    /// baby-step giant-step
+    Bsgs,
+    /// pohlig-hellman, for groups with a smooth order
+    PohligHellman,
+    /// pollard's rho for logarithms
+    Rho,
+    /// pollard's lambda (kangaroo), for x in a known interval
+    Kangaroo,
+    /// index calculus, only for prime fields
+    IndexCalculus,
+}
+
 #[derive(Args, Clone, Debug, PartialEq, Eq)]
 pub struct ECCAction {
     #[clap(allow_hyphen_values=true)]   // allow negative inputs like -19
@@ -279,3 +581,531 @@ pub struct Feistel0Args{
     #[arg(short, long, default_value_t = false)]
     pub decrypt: bool,
 }
+
+#[derive(Subcommand, Clone, Debug, PartialEq, Eq)]
+pub enum AttackActions {
+    /// wiener's attack on RSA with a small private exponent
+    Wiener(WienerArgs),
+    /// decrypt a message that was encrypted with two exponents under the same modulus
+    CommonModulus(CommonModulusArgs),
+    /// håstad's broadcast attack, decrypt a message that was sent to e receivers
+    Hastad(HastadArgs),
+    /// fermat factorisation, factor n if p and q are close
+    Fermat(FermatArgs),
+    /// recover p and q from the private exponent d
+    FactorD(FactorDArgs),
+    /// coppersmith's method, find the small roots of a polynomial mod n
+    Coppersmith(CoppersmithArgs),
+    /// bleichenbacher's padding oracle attack against a freshly generated key
+    Bleichenbacher(BleichenbacherArgs),
+    /// manger's attack on RSA-OAEP against a freshly generated key
+    Manger(MangerArgs),
+    /// factor a rabin modulus with a chosen ciphertext attack on a decryption oracle
+    Rabin(RabinAttackArgs),
+}
+
+#[derive(Args, Clone, Debug, PartialEq, Eq)]
+/// arguments for wiener's attack
+pub struct WienerArgs {
+    /// the RSA modulus n
+    pub n: String,
+    /// the public exponent e
+    pub e: String,
+}
+
+#[derive(Args, Clone, Debug, PartialEq, Eq)]
+/// arguments for the common modulus attack
+pub struct CommonModulusArgs {
+    /// the RSA modulus n
+    pub n: String,
+    #[arg(long)]
+    /// the first public exponent
+    pub e1: String,
+    #[arg(long)]
+    /// the ciphertext under e1
+    pub c1: String,
+    #[arg(long)]
+    /// the second public exponent
+    pub e2: String,
+    #[arg(long)]
+    /// the ciphertext under e2
+    pub c2: String,
+}
+
+#[derive(Args, Clone, Debug, PartialEq, Eq)]
+/// arguments for håstad's broadcast attack
+pub struct HastadArgs {
+    #[arg(long)]
+    /// the common public exponent
+    pub e: u32,
+    #[arg(long, value_delimiter = ',', required = true)]
+    /// the ciphertexts, seperated by commas
+    pub ciphertexts: Vec<String>,
+    #[arg(long, value_delimiter = ',', required = true)]
+    /// the moduli, seperated by commas
+    pub moduli: Vec<String>,
+}
+
+#[derive(Args, Clone, Debug, PartialEq, Eq)]
+/// arguments for fermat factorisation
+pub struct FermatArgs {
+    /// the number to factor
+    pub n: String,
+    #[arg(long, default_value_t = 1 << 20)]
+    /// give up after this many steps
+    pub steps: u64,
+}
+
+#[derive(Args, Clone, Debug, PartialEq, Eq)]
+/// arguments for the recovery of p and q from d
+pub struct FactorDArgs {
+    /// the RSA modulus n
+    pub n: String,
+    #[arg(long)]
+    /// the public exponent
+    pub e: String,
+    #[arg(long)]
+    /// the private exponent
+    pub d: String,
+}
+
+#[derive(Args, Clone, Debug, PartialEq, Eq)]
+/// arguments for coppersmith's method
+pub struct CoppersmithArgs {
+    /// the polynomial in x, like "x^3 + 5*x - 7"
+    pub polynomial: String,
+    #[arg(long)]
+    /// the modulus
+    pub n: String,
+    #[arg(long)]
+    /// only find roots with |x| <= bound
+    pub bound: String,
+    #[arg(long, default_value_t = 0)]
+    /// the lattice parameter m, 0 chooses it from the bound
+    pub m: usize,
+    #[arg(long, default_value_t = 0)]
+    /// the number of extra polynomials x^j * f^m
+    pub t: usize,
+}
+
+#[derive(Args, Clone, Debug, PartialEq, Eq)]
+/// arguments for bleichenbacher's attack
+pub struct BleichenbacherArgs {
+    /// the message to encrypt and recover
+    pub message: String,
+    #[arg(long, default_value_t = 256)]
+    /// size of the modulus in bits
+    pub bits: u64,
+    #[arg(long, default_value_t = false)]
+    /// the oracle also checks the padding bytes and the separator
+    pub strict: bool,
+}
+
+#[derive(Args, Clone, Debug, PartialEq, Eq)]
+/// arguments for manger's attack
+pub struct MangerArgs {
+    /// the message to encrypt and recover
+    pub message: String,
+    #[arg(long, default_value_t = 1024)]
+    /// size of the modulus in bits
+    pub bits: u64,
+    #[arg(long, value_enum, default_value_t = HashKind::Sha256)]
+    /// hash function for OAEP
+    pub hash: HashKind,
+}
+
+#[derive(Args, Clone, Debug, PartialEq, Eq)]
+/// arguments for the chosen ciphertext attack on rabin
+pub struct RabinAttackArgs {
+    #[arg(long, default_value_t = 512)]
+    /// size of the modulus in bits
+    pub bits: u64,
+    #[arg(long, default_value_t = 0)]
+    /// the oracle only answers for roots with this many repeated trailing bits
+    pub redundancy: u32,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+/// hash functions for OAEP
+pub enum HashKind {
+    /// SHA-1
+    Sha1,
+    /// SHA-256
+    Sha256,
+}
+
+impl From<HashKind> for crate::algo::oaep::HashFunction {
+    fn from(kind: HashKind) -> Self {
+        match kind {
+            HashKind::Sha1 => crate::algo::oaep::HashFunction::Sha1,
+            HashKind::Sha256 => crate::algo::oaep::HashFunction::Sha256,
+        }
+    }
+}
+
+#[derive(Args, Clone, Debug, PartialEq, Eq)]
+pub struct RabinCommand {
+    #[command(subcommand)]
+    pub action: RabinActions
+}
+
+#[derive(Subcommand, Clone, Debug, PartialEq, Eq)]
+pub enum RabinActions {
+    /// generate a new key with p = q = 3 mod 4
+    Keygen(RabinKeygenArgs),
+    /// encrypt a message
+    Encrypt(RabinEncryptArgs),
+    /// decrypt a ciphertext, all four roots or the one with a valid redundancy
+    Decrypt(RabinDecryptArgs),
+}
+
+#[derive(Args, Clone, Debug, PartialEq, Eq)]
+/// arguments for rabin key generation
+pub struct RabinKeygenArgs {
+    /// size of the modulus in bits
+    pub bits: u64,
+}
+
+#[derive(Args, Clone, Debug, PartialEq, Eq)]
+/// arguments for rabin encryption
+pub struct RabinEncryptArgs {
+    /// the message
+    pub m: String,
+    #[arg(long)]
+    /// the modulus
+    pub n: String,
+    #[arg(long, default_value_t = 0)]
+    /// repeat this many trailing bits of the message
+    pub redundancy: u32,
+}
+
+#[derive(Args, Clone, Debug, PartialEq, Eq)]
+/// arguments for rabin decryption
+pub struct RabinDecryptArgs {
+    /// the ciphertext
+    pub c: String,
+    #[arg(long)]
+    /// the first prime factor of n
+    pub p: String,
+    #[arg(long)]
+    /// the second prime factor of n
+    pub q: String,
+    #[arg(long, default_value_t = 0)]
+    /// choose the root with this many repeated trailing bits
+    pub redundancy: u32,
+}
+
+#[derive(Args, Clone, Debug, PartialEq, Eq)]
+pub struct PaillierCommand {
+    #[command(subcommand)]
+    pub action: PaillierActions
+}
+
+#[derive(Subcommand, Clone, Debug, PartialEq, Eq)]
+pub enum PaillierActions {
+    /// generate a new key
+    Keygen(PaillierKeygenArgs),
+    /// encrypt a message
+    Encrypt(PaillierEncryptArgs),
+    /// decrypt a ciphertext
+    Decrypt(PaillierDecryptArgs),
+    /// add the plaintexts of two ciphertexts
+    Add(PaillierAddArgs),
+    /// multiply the plaintext of a ciphertext with a scalar
+    Mul(PaillierMulArgs),
+    /// encrypt the plaintext of a ciphertext again with fresh randomness
+    Rerandomize(PaillierRerandomizeArgs),
+    /// tally an encrypted election with a fresh key
+    Tally(PaillierTallyArgs),
+}
+
+#[derive(Args, Clone, Debug, PartialEq, Eq)]
+/// arguments for paillier key generation
+pub struct PaillierKeygenArgs {
+    /// size of the modulus in bits
+    pub bits: u64,
+}
+
+#[derive(Args, Clone, Debug, PartialEq, Eq)]
+/// arguments for paillier encryption
+pub struct PaillierEncryptArgs {
+    /// the message
+    pub m: String,
+    #[arg(long)]
+    /// the modulus
+    pub n: String,
+    #[arg(long)]
+    /// the randomness, random if not given
+    pub r: Option<String>,
+}
+
+#[derive(Args, Clone, Debug, PartialEq, Eq)]
+/// arguments for paillier decryption
+pub struct PaillierDecryptArgs {
+    /// the ciphertext
+    pub c: String,
+    #[arg(long)]
+    /// the first prime factor of n
+    pub p: String,
+    #[arg(long)]
+    /// the second prime factor of n
+    pub q: String,
+}
+
+#[derive(Args, Clone, Debug, PartialEq, Eq)]
+/// arguments for homomorphic addition
+pub struct PaillierAddArgs {
+    /// the first ciphertext
+    pub c1: String,
+    /// the second ciphertext
+    pub c2: String,
+    #[arg(long)]
+    /// the modulus
+    pub n: String,
+}
+
+#[derive(Args, Clone, Debug, PartialEq, Eq)]
+/// arguments for homomorphic scalar multiplication
+pub struct PaillierMulArgs {
+    /// the ciphertext
+    pub c: String,
+    #[arg(allow_hyphen_values = true)]
+    /// the scalar, negative values subtract
+    pub k: String,
+    #[arg(long)]
+    /// the modulus
+    pub n: String,
+}
+
+#[derive(Args, Clone, Debug, PartialEq, Eq)]
+/// arguments for re-randomisation
+pub struct PaillierRerandomizeArgs {
+    /// the ciphertext
+    pub c: String,
+    #[arg(long)]
+    /// the modulus
+    pub n: String,
+}
+
+#[derive(Args, Clone, Debug, PartialEq, Eq)]
+/// arguments for the e-voting demo
+pub struct PaillierTallyArgs {
+    /// the chosen candidate of each voter, starting at 0
+    pub votes: Vec<usize>,
+    #[arg(long, default_value_t = 2)]
+    /// number of candidates
+    pub candidates: usize,
+    #[arg(long, default_value_t = 512)]
+    /// size of the modulus in bits
+    pub bits: u64,
+}
+
+#[derive(Args, Clone, Debug, PartialEq, Eq)]
+pub struct ElgamalCommand {
+    #[command(flatten)]
+    pub group: ElgamalGroupArgs,
+    #[command(subcommand)]
+    pub action: ElgamalActions
+}
+
+#[derive(Args, Clone, Debug, PartialEq, Eq)]
+/// the group for elgamal
+pub struct ElgamalGroupArgs {
+    #[arg(long)]
+    /// the prime of the field
+    pub p: String,
+    #[arg(long)]
+    /// the generator, a number or a point x,y on the curve
+    pub g: String,
+    #[arg(long)]
+    /// the order of g, p - 1 by default for fields, required for curves
+    pub order: Option<String>,
+    #[arg(long, allow_hyphen_values = true, requires = "order")]
+    /// use the curve y² = x³ + ax + b over F_p, given as a,b
+    pub curve: Option<String>,
+}
+
+#[derive(Subcommand, Clone, Debug, PartialEq, Eq)]
+pub enum ElgamalActions {
+    /// generate a key, encrypt, decrypt, sign and verify
+    Demo(ElgamalDemoArgs),
+    /// change a ciphertext of m into one of m * t without the key
+    Malleability(ElgamalMalleabilityArgs),
+    /// recover the key from two signatures with the same k and forge a third
+    Reuse(ElgamalReuseArgs),
+}
+
+#[derive(Args, Clone, Debug, PartialEq, Eq)]
+/// arguments for the elgamal demo
+pub struct ElgamalDemoArgs {
+    /// the message, a number or a point x,y on the curve
+    pub m: String,
+}
+
+#[derive(Args, Clone, Debug, PartialEq, Eq)]
+/// arguments for the malleability demo
+pub struct ElgamalMalleabilityArgs {
+    /// the message, a number or a point x,y on the curve
+    pub m: String,
+    /// the factor, a number or a point x,y on the curve
+    pub t: String,
+}
+
+#[derive(Args, Clone, Debug, PartialEq, Eq)]
+/// arguments for the k reuse demo
+pub struct ElgamalReuseArgs {
+    /// the first signed message
+    pub m1: String,
+    /// the second signed message
+    pub m2: String,
+    /// the message to forge a signature for
+    pub m3: String,
+}
+
+#[derive(Args, Clone, Debug, PartialEq, Eq)]
+pub struct ProtocolCommand {
+    #[command(subcommand)]
+    pub action: ProtocolActions
+}
+
+#[derive(Subcommand, Clone, Debug, PartialEq, Eq)]
+pub enum ProtocolActions {
+    /// diffie-hellman key exchange in F_p or on an elliptic curve
+    Dh(DhArgs),
+}
+
+#[derive(Args, Clone, Debug, PartialEq, Eq)]
+/// arguments for the diffie-hellman simulation
+pub struct DhArgs {
+    #[arg(long)]
+    /// the prime of the field
+    pub p: String,
+    #[arg(long)]
+    /// the generator, a number or a point x,y on the curve
+    pub g: String,
+    #[arg(long)]
+    /// the order of g, p - 1 by default for fields, required for curves
+    pub order: Option<String>,
+    #[arg(long, allow_hyphen_values = true, requires = "order")]
+    /// use ECDH on the curve y² = x³ + ax + b over F_p, given as a,b
+    pub curve: Option<String>,
+    #[arg(long, default_value_t = false)]
+    /// Mallory substitutes the public values with her own
+    pub mallory: bool,
+    #[arg(long, conflicts_with = "mallory")]
+    /// Mallory confines the key to the subgroup of this order
+    pub confine: Option<u64>,
+}
+
+#[derive(Subcommand, Clone, Debug, PartialEq, Eq)]
+pub enum RsaActions {
+    /// generate a new key
+    Keygen(RsaKeygenArgs),
+    /// encrypt a message
+    Encrypt(RsaPublicArgs),
+    /// decrypt a ciphertext
+    Decrypt(RsaPrivateArgs),
+    /// sign a message
+    Sign(RsaPrivateArgs),
+    /// verify a signature
+    Verify(RsaVerifyArgs),
+    /// encrypt a text message with OAEP padding
+    OaepEncrypt(RsaOaepEncryptArgs),
+    /// decrypt an OAEP padded ciphertext
+    OaepDecrypt(RsaOaepDecryptArgs),
+}
+
+#[derive(Args, Clone, Debug, PartialEq, Eq)]
+/// arguments for RSA key generation
+pub struct RsaKeygenArgs {
+    /// size of the modulus in bits
+    pub bits: u64,
+    #[arg(long, default_value = "65537")]
+    /// the public exponent
+    pub e: String,
+}
+
+#[derive(Args, Clone, Debug, PartialEq, Eq)]
+/// arguments for RSA encryption
+pub struct RsaPublicArgs {
+    /// the message
+    pub m: String,
+    #[arg(long)]
+    /// the modulus
+    pub n: String,
+    #[arg(long, default_value = "65537")]
+    /// the public exponent
+    pub e: String,
+}
+
+#[derive(Args, Clone, Debug, PartialEq, Eq)]
+/// arguments for RSA decryption and signatures
+pub struct RsaPrivateArgs {
+    /// the ciphertext or message
+    pub input: String,
+    #[arg(long)]
+    /// the modulus
+    pub n: String,
+    #[arg(long)]
+    /// the private exponent
+    pub d: String,
+    #[arg(long, requires = "q")]
+    /// the first prime factor of n, enables decryption with the chinese remainder theorem
+    pub p: Option<String>,
+    #[arg(long, requires = "p")]
+    /// the second prime factor of n
+    pub q: Option<String>,
+}
+
+#[derive(Args, Clone, Debug, PartialEq, Eq)]
+/// arguments for RSA signature verification
+pub struct RsaVerifyArgs {
+    /// the message
+    pub m: String,
+    /// the signature
+    pub s: String,
+    #[arg(long)]
+    /// the modulus
+    pub n: String,
+    #[arg(long, default_value = "65537")]
+    /// the public exponent
+    pub e: String,
+}
+
+#[derive(Args, Clone, Debug, PartialEq, Eq)]
+/// arguments for RSA-OAEP encryption
+pub struct RsaOaepEncryptArgs {
+    /// the message as text
+    pub message: String,
+    #[arg(long)]
+    /// the modulus
+    pub n: String,
+    #[arg(long, default_value = "65537")]
+    /// the public exponent
+    pub e: String,
+    #[arg(long, default_value = "")]
+    /// the label L
+    pub label: String,
+    #[arg(long, value_enum, default_value_t = HashKind::Sha256)]
+    /// hash function for OAEP and MGF1
+    pub hash: HashKind,
+}
+
+#[derive(Args, Clone, Debug, PartialEq, Eq)]
+/// arguments for RSA-OAEP decryption
+pub struct RsaOaepDecryptArgs {
+    /// the ciphertext
+    pub c: String,
+    #[arg(long)]
+    /// the modulus
+    pub n: String,
+    #[arg(long)]
+    /// the private exponent
+    pub d: String,
+    #[arg(long, default_value = "")]
+    /// the label L
+    pub label: String,
+    #[arg(long, value_enum, default_value_t = HashKind::Sha256)]
+    /// hash function for OAEP and MGF1
+    pub hash: HashKind,
+}
@@ -24,6 +24,7 @@ mod binary;
 mod math;
 mod algo;
 mod cplex;
+mod protocol;
 
 #[pymodule]
 fn register_binary_module(py: Python, parent_module: &PyModule) -> PyResult<()> {
@@ -51,12 +52,40 @@ fn register_cplex_module(py: Python, parent_module: &PyModule) -> PyResult<()> {
 fn register_math_module(py: Python, parent_module: &PyModule) -> PyResult<()> {
     let math_module = PyModule::new(py, "math")?;
     math_module.add_function(wrap_pyfunction!(math::modexp::py_modular_exponentiation, math_module)?)?;
+    math_module.add_function(wrap_pyfunction!(math::modexp::py_modular_exponentiation_with, math_module)?)?;
     math_module.add_function(wrap_pyfunction!(math::pm1::py_p_minus_one, math_module)?)?;
+    math_module.add_function(wrap_pyfunction!(math::pp1::py_p_plus_one, math_module)?)?;
     math_module.add_function(wrap_pyfunction!(math::gcd::gcd, math_module)?)?;
     math_module.add_function(wrap_pyfunction!(math::gcd::egcd, math_module)?)?;
     math_module.add_function(wrap_pyfunction!(math::gcd::alt_egcd, math_module)?)?;
     math_module.add_function(wrap_pyfunction!(math::factorise::prime_factors , math_module)?)?;
+    math_module.add_function(wrap_pyfunction!(math::factorise::py_pollard_rho_factors, math_module)?)?;
+    math_module.add_function(wrap_pyfunction!(math::ecm::py_ecm_factors, math_module)?)?;
+    math_module.add_function(wrap_pyfunction!(math::primality::py_is_prime, math_module)?)?;
+    math_module.add_function(wrap_pyfunction!(math::primality::py_miller_rabin, math_module)?)?;
+    math_module.add_function(wrap_pyfunction!(math::primality::py_bpsw, math_module)?)?;
+    math_module.add_function(wrap_pyfunction!(math::crt::py_crt, math_module)?)?;
+    math_module.add_function(wrap_pyfunction!(math::indexcalculus::py_index_calculus, math_module)?)?;
+    math_module.add_function(wrap_pyfunction!(math::arith::py_totient, math_module)?)?;
+    math_module.add_function(wrap_pyfunction!(math::arith::py_carmichael, math_module)?)?;
+    math_module.add_function(wrap_pyfunction!(math::arith::py_multiplicative_order, math_module)?)?;
+    math_module.add_function(wrap_pyfunction!(math::arith::py_has_primitive_roots, math_module)?)?;
+    math_module.add_function(wrap_pyfunction!(math::arith::py_primitive_root, math_module)?)?;
+    math_module.add_function(wrap_pyfunction!(math::arith::py_primitive_roots, math_module)?)?;
+    math_module.add_function(wrap_pyfunction!(math::jacobi::py_jacobi, math_module)?)?;
+    math_module.add_function(wrap_pyfunction!(math::jacobi::py_legendre, math_module)?)?;
+    math_module.add_function(wrap_pyfunction!(math::jacobi::py_kronecker, math_module)?)?;
+    math_module.add_function(wrap_pyfunction!(math::modsqrt::py_cipolla, math_module)?)?;
+    math_module.add_function(wrap_pyfunction!(math::modsqrt::py_hensel_lift, math_module)?)?;
+    math_module.add_function(wrap_pyfunction!(math::modsqrt::py_sqrt_mod, math_module)?)?;
+    math_module.add_function(wrap_pyfunction!(math::contfrac::py_expand, math_module)?)?;
+    math_module.add_function(wrap_pyfunction!(math::contfrac::py_convergents, math_module)?)?;
+    math_module.add_function(wrap_pyfunction!(math::contfrac::py_reconstruct, math_module)?)?;
+    math_module.add_function(wrap_pyfunction!(math::lll::py_lll, math_module)?)?;
+    math_module.add_function(wrap_pyfunction!(math::coppersmith::py_small_roots, math_module)?)?;
+    math_module.add_function(wrap_pyfunction!(math::coppersmith::py_parse, math_module)?)?;
     math_module.add_class::<math::gallois::GalloisField>()?;
+    math_module.add_class::<math::biggallois::BigGalloisField>()?;
     math_module.add_class::<math::ecc::EllipticCurve>()?;
     math_module.add_class::<math::ecc::EllipticCurvePoint>()?;
     parent_module.add_submodule(math_module)?;
@@ -72,10 +101,66 @@ fn register_algo_module(py: Python, parent_module: &PyModule) -> PyResult<()> {
     feistel0_module.add_function(wrap_pyfunction!(algo::feistel0::sbox, feistel0_module)?)?;
     feistel0_module.add_function(wrap_pyfunction!(algo::feistel0::key_scheduler, feistel0_module)?)?;
     algo_module.add_submodule(feistel0_module)?;
+    let wiener_module = PyModule::new(py, "wiener")?;
+    wiener_module.add_function(wrap_pyfunction!(algo::wiener::py_wiener, wiener_module)?)?;
+    algo_module.add_submodule(wiener_module)?;
+    let rsa_module = PyModule::new(py, "rsa")?;
+    rsa_module.add_function(wrap_pyfunction!(algo::rsa::py_random_prime, rsa_module)?)?;
+    rsa_module.add_class::<algo::rsa::RsaPublicKey>()?;
+    rsa_module.add_class::<algo::rsa::RsaPrivateKey>()?;
+    algo_module.add_submodule(rsa_module)?;
+    let rsa_attacks_module = PyModule::new(py, "rsa_attacks")?;
+    rsa_attacks_module.add_function(wrap_pyfunction!(algo::rsa_attacks::py_common_modulus, rsa_attacks_module)?)?;
+    rsa_attacks_module.add_function(wrap_pyfunction!(algo::rsa_attacks::py_hastad, rsa_attacks_module)?)?;
+    rsa_attacks_module.add_function(wrap_pyfunction!(algo::rsa_attacks::py_fermat, rsa_attacks_module)?)?;
+    rsa_attacks_module.add_function(wrap_pyfunction!(algo::rsa_attacks::py_factor_with_d, rsa_attacks_module)?)?;
+    algo_module.add_submodule(rsa_attacks_module)?;
+    let bleichenbacher_module = PyModule::new(py, "bleichenbacher")?;
+    bleichenbacher_module.add_function(wrap_pyfunction!(algo::bleichenbacher::py_attack, bleichenbacher_module)?)?;
+    bleichenbacher_module.add_function(wrap_pyfunction!(algo::bleichenbacher::py_attack_local, bleichenbacher_module)?)?;
+    bleichenbacher_module.add_function(wrap_pyfunction!(algo::bleichenbacher::py_pad, bleichenbacher_module)?)?;
+    bleichenbacher_module.add_function(wrap_pyfunction!(algo::bleichenbacher::py_unpad, bleichenbacher_module)?)?;
+    algo_module.add_submodule(bleichenbacher_module)?;
+    let oaep_module = PyModule::new(py, "oaep")?;
+    oaep_module.add_function(wrap_pyfunction!(algo::oaep::py_mgf1, oaep_module)?)?;
+    oaep_module.add_function(wrap_pyfunction!(algo::oaep::py_encode, oaep_module)?)?;
+    oaep_module.add_function(wrap_pyfunction!(algo::oaep::py_encode_block, oaep_module)?)?;
+    oaep_module.add_function(wrap_pyfunction!(algo::oaep::py_decode, oaep_module)?)?;
+    algo_module.add_submodule(oaep_module)?;
+    let manger_module = PyModule::new(py, "manger")?;
+    manger_module.add_function(wrap_pyfunction!(algo::manger::py_attack_local, manger_module)?)?;
+    algo_module.add_submodule(manger_module)?;
+    let rabin_module = PyModule::new(py, "rabin")?;
+    rabin_module.add_function(wrap_pyfunction!(algo::rabin::py_add_redundancy, rabin_module)?)?;
+    rabin_module.add_function(wrap_pyfunction!(algo::rabin::py_strip_redundancy, rabin_module)?)?;
+    rabin_module.add_function(wrap_pyfunction!(algo::rabin::py_attack_local, rabin_module)?)?;
+    rabin_module.add_class::<algo::rabin::RabinPublicKey>()?;
+    rabin_module.add_class::<algo::rabin::RabinPrivateKey>()?;
+    algo_module.add_submodule(rabin_module)?;
+    let paillier_module = PyModule::new(py, "paillier")?;
+    paillier_module.add_function(wrap_pyfunction!(algo::paillier::py_tally, paillier_module)?)?;
+    paillier_module.add_class::<algo::paillier::PaillierPublicKey>()?;
+    paillier_module.add_class::<algo::paillier::PaillierPrivateKey>()?;
+    algo_module.add_submodule(paillier_module)?;
+    let elgamal_module = PyModule::new(py, "elgamal")?;
+    elgamal_module.add_class::<algo::elgamal::ElGamalField>()?;
+    elgamal_module.add_class::<algo::elgamal::ElGamalCurve>()?;
+    algo_module.add_submodule(elgamal_module)?;
     parent_module.add_submodule(algo_module)?;
     Ok(())
 }
 
+#[pymodule]
+fn register_protocol_module(py: Python, parent_module: &PyModule) -> PyResult<()> {
+    let protocol_module = PyModule::new(py, "protocol")?;
+    let dh_module = PyModule::new(py, "dh")?;
+    dh_module.add_function(wrap_pyfunction!(protocol::dh::py_dh, dh_module)?)?;
+    dh_module.add_function(wrap_pyfunction!(protocol::dh::py_ecdh, dh_module)?)?;
+    protocol_module.add_submodule(dh_module)?;
+    parent_module.add_submodule(protocol_module)?;
+    Ok(())
+}
+
 #[pymodule]
 fn register_scripts_module(py: Python, parent_module: &PyModule) -> PyResult<()> {
     let scripts_module = PyModule::new(py, "scripts")?;
@@ -90,6 +175,7 @@ fn plexcryptool(py: Python, m: &PyModule) -> PyResult<()> {
     register_math_module(py, m)?;
     register_cplex_module(py, m)?;
     register_algo_module(py, m)?;
+    register_protocol_module(py, m)?;
     register_scripts_module(py, m)?;
     Ok(())
 }
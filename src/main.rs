@@ -20,6 +20,7 @@ mod binary;
 mod math;
 mod algo;
 mod cplex;
+mod protocol;
 
 use cplex::cli::*;
 
@@ -27,6 +28,7 @@ use std::str::FromStr;
 
 use clap::Parser;
 use num_bigint;
+use num_traits::ToPrimitive;
 
 /*************************************************************************************************/
 /// main function of plexcryptool.
@@ -45,17 +47,51 @@ pub fn main() {
                     let b = num_bigint::BigInt::from_str(&mod_exp_args.base.as_str()).expect("could not make bigint");
                     let e = num_bigint::BigInt::from_str(&mod_exp_args.exp.as_str()).expect("could not make bigint");
                     let f = num_bigint::BigInt::from_str(&mod_exp_args.field.as_str()).expect("could not make bigint");
-                    let num = math::modexp::modular_exponentiation(b.clone(), e, f, args.verbose);
-                    cplex::printing::proc_num(num, args);
+                    if mod_exp_args.method.is_none() && mod_exp_args.reduction.is_none() {
+                        let result = math::modexp::checked_modular_exponentiation(b.clone(), e, f, args.verbose);
+                        cplex::printing::proc_result_num(result, args);
+                    }
+                    else {
+                        let method = mod_exp_args.method.unwrap_or(ModexpMethod::LeftToRight);
+                        let result = math::modexp::modular_exponentiation_with(
+                            &b,
+                            &e,
+                            &f,
+                            method.into(),
+                            mod_exp_args.window,
+                            mod_exp_args.reduction.map(|r| r.into()),
+                            args.verbose
+                        );
+                        match result {
+                            Ok((num, ops)) => {
+                                cplex::printing::proc_display(format!("{num} ({ops})"), args);
+                            }
+                            Err(e) => {
+                                cplex::printing::proc_err(e, args);
+                            }
+                        }
+                    }
                 }
                 MathActions::Modred(mod_red_args) => {
                     let result = math::modred::modred(mod_red_args.polynomial, mod_red_args.relation, args.verbose);
                     cplex::printing::proc_result_num(result, args);
                 }
                 MathActions::Pm1(pm1_args) => {
-                    let vec: Result<Vec<u128>, String> = math::pm1::p_minus_one(
-                        pm1_args.n, 
-                        pm1_args.max_prime,
+                    let n = num_bigint::BigInt::from_str(&pm1_args.n.as_str()).expect("could not make bigint");
+                    let vec = math::pm1::p_minus_one(
+                        &n,
+                        pm1_args.b1,
+                        pm1_args.b2,
+                        args.verbose
+                        );
+                    cplex::printing::proc_result_vec(vec, args);
+                }
+                MathActions::Pp1(pp1_args) => {
+                    let n = num_bigint::BigInt::from_str(&pp1_args.n.as_str()).expect("could not make bigint");
+                    let vec = math::pp1::p_plus_one(
+                        &n,
+                        pp1_args.b1,
+                        pp1_args.b2,
                         args.verbose
                         );
                     cplex::printing::proc_result_vec(vec, args);
@@ -75,6 +111,36 @@ pub fn main() {
                             let result = field.inverse(gal_inv_args.n);
                             cplex::printing::proc_result_num(result, args);
                         }
+                        GalloisActions::Dlog(dlog_args) => {
+                            let result = match dlog_args.method {
+                                DlogMethod::Bsgs => {
+                                    math::dlog::dlog(&field, &dlog_args.g, &dlog_args.h, args.verbose)
+                                }
+                                DlogMethod::PohligHellman => {
+                                    let order = num_bigint::BigUint::from(field.base - 1);
+                                    math::dlog::pohlig_hellman(&field, &dlog_args.g, &dlog_args.h, &order, args.verbose)
+                                }
+                                DlogMethod::Rho => {
+                                    let order = num_bigint::BigUint::from(dlog_args.order.unwrap_or(field.base - 1));
+                                    math::dlog::pollard_rho_dlog(&field, &dlog_args.g, &dlog_args.h, &order, args.verbose)
+                                }
+                                DlogMethod::Kangaroo => {
+                                    let a = num_bigint::BigUint::from(dlog_args.lower);
+                                    let b = num_bigint::BigUint::from(dlog_args.upper.unwrap_or(field.base - 1));
+                                    math::dlog::kangaroo(&field, &dlog_args.g, &dlog_args.h, &a, &b, args.verbose)
+                                }
+                                DlogMethod::IndexCalculus => {
+                                    math::indexcalculus::index_calculus(
+                                        &num_bigint::BigInt::from(field.base),
+                                        &num_bigint::BigInt::from(dlog_args.g),
+                                        &num_bigint::BigInt::from(dlog_args.h),
+                                        dlog_args.bound,
+                                        args.verbose
+                                    ).map(|x| x.to_biguint().expect("log is negative"))
+                                }
+                            };
+                            cplex::printing::proc_result_num(result, args);
+                        }
                         GalloisActions::ECC(ecc_args) => {
                             let ec = math::ecc::EllipticCurve::new(field, ecc_args.a, ecc_args.b, args.verbose).expect("Could not create eliptic curve");
                             match ecc_args.action {
@@ -127,8 +193,106 @@ pub fn main() {
                     }
                 }
                 MathActions::Factorize(fac_args) => {
-                    let vec = math::factorise::prime_factors(fac_args.n, args.verbose);
-                    cplex::printing::proc_vec(vec, args);
+                    let n = num_bigint::BigInt::from_str(&fac_args.n.as_str()).expect("could not make bigint");
+                    match fac_args.algorithm {
+                        FactorizeAlgorithm::Trial => {
+                            let n = n.to_u128().expect("trial division only works for n < 2^128");
+                            let vec = math::factorise::prime_factors(n, args.verbose);
+                            cplex::printing::proc_vec(vec, args);
+                        }
+                        FactorizeAlgorithm::Rho => {
+                            let vec = math::factorise::pollard_rho_factors(&n, args.verbose);
+                            cplex::printing::proc_vec(vec, args);
+                        }
+                        FactorizeAlgorithm::Ecm => {
+                            let vec = math::ecm::ecm_factors(&n, fac_args.b1, fac_args.curves, args.verbose);
+                            cplex::printing::proc_result_vec(vec, args);
+                        }
+                    }
+                }
+                MathActions::Primality(prim_args) => {
+                    let n = num_bigint::BigInt::from_str(&prim_args.n.as_str()).expect("could not make bigint");
+                    let result: bool = match prim_args.method {
+                        PrimalityMethod::Auto => {
+                            math::primality::is_prime(&n, args.verbose)
+                        }
+                        PrimalityMethod::Trial => {
+                            n > num_bigint::BigInt::from(1) &&
+                                math::primality::trial_division(&n, u64::MAX, args.verbose).is_none()
+                        }
+                        PrimalityMethod::MillerRabin => {
+                            math::primality::miller_rabin(&n, prim_args.rounds, args.verbose)
+                        }
+                        PrimalityMethod::Bpsw => {
+                            math::primality::bpsw(&n, args.verbose)
+                        }
+                    };
+                    if args.verbose {
+                        cplex::printing::seperator();
+                    }
+                    cplex::printing::proc_display(result, args);
+                }
+                MathActions::Crt(crt_args) => {
+                    let residues: Vec<num_bigint::BigInt> = crt_args.residues.iter()
+                        .map(|a| num_bigint::BigInt::from_str(a.as_str()).expect("could not make bigint"))
+                        .collect();
+                    let moduli: Vec<num_bigint::BigInt> = crt_args.moduli.iter()
+                        .map(|m| num_bigint::BigInt::from_str(m.as_str()).expect("could not make bigint"))
+                        .collect();
+                    let result = math::crt::crt(&residues, &moduli, args.verbose);
+                    cplex::printing::proc_result_tup_num(result, args);
+                }
+                MathActions::Totient(arith_args) => {
+                    let n = num_bigint::BigInt::from_str(arith_args.n.as_str()).expect("could not make bigint");
+                    let result = math::arith::totient(&n, args.verbose);
+                    cplex::printing::proc_result_num(result, args);
+                }
+                MathActions::Carmichael(arith_args) => {
+                    let n = num_bigint::BigInt::from_str(arith_args.n.as_str()).expect("could not make bigint");
+                    let result = math::arith::carmichael(&n, args.verbose);
+                    cplex::printing::proc_result_num(result, args);
+                }
+                MathActions::Order(ord_args) => {
+                    let a = num_bigint::BigInt::from_str(ord_args.a.as_str()).expect("could not make bigint");
+                    let n = num_bigint::BigInt::from_str(ord_args.n.as_str()).expect("could not make bigint");
+                    let result = math::arith::multiplicative_order(&a, &n, args.verbose);
+                    cplex::printing::proc_result_num(result, args);
+                }
+                MathActions::PrimitiveRoot(root_args) => {
+                    let n = num_bigint::BigInt::from_str(root_args.n.as_str()).expect("could not make bigint");
+                    if root_args.exists {
+                        let result = math::arith::has_primitive_roots(&n);
+                        cplex::printing::proc_display(result, args);
+                    }
+                    else if root_args.all {
+                        let result = math::arith::primitive_roots(&n, args.verbose);
+                        cplex::printing::proc_result_vec(result, args);
+                    }
+                    else {
+                        let result = math::arith::primitive_root(&n, args.verbose);
+                        cplex::printing::proc_result_num(result, args);
+                    }
+                }
+                MathActions::Jacobi(jacobi_args) => {
+                    let a = num_bigint::BigInt::from_str(jacobi_args.a.as_str()).expect("could not make bigint");
+                    let n = num_bigint::BigInt::from_str(jacobi_args.n.as_str()).expect("could not make bigint");
+                    let result = match jacobi_args.symbol {
+                        SymbolKind::Legendre => math::jacobi::legendre(&a, &n, args.verbose),
+                        SymbolKind::Jacobi => math::jacobi::jacobi(&a, &n, args.verbose),
+                        SymbolKind::Kronecker => Ok(math::jacobi::kronecker(&a, &n, args.verbose)),
+                    };
+                    match result {
+                        Ok(symbol) => cplex::printing::proc_display(symbol, args),
+                        Err(e) => cplex::printing::proc_err(e, args),
+                    }
+                }
+                MathActions::Sqrt(sqrt_args) => {
+                    let a = num_bigint::BigInt::from_str(sqrt_args.a.as_str()).expect("could not make bigint");
+                    let factors: Vec<num_bigint::BigInt> = sqrt_args.factors.iter()
+                        .map(|p| num_bigint::BigInt::from_str(p.as_str()).expect("could not make bigint"))
+                        .collect();
+                    let result = math::modsqrt::sqrt_mod(&a, &factors, sqrt_args.method.into(), args.verbose);
+                    cplex::printing::proc_result_vec(result, args);
                 }
                 MathActions::Gcd(gcd_args) => {
                     if gcd_args.ext {
@@ -187,5 +351,348 @@ pub fn main() {
                 }
             }
         }
+        Commands::Attack(action) => {
+            match action.action {
+                AttackActions::Wiener(wiener_args) => {
+                    let n = num_bigint::BigInt::from_str(wiener_args.n.as_str()).expect("could not make bigint");
+                    let e = num_bigint::BigInt::from_str(wiener_args.e.as_str()).expect("could not make bigint");
+                    let result = algo::wiener::wiener(&n, &e, args.verbose).map(|(d, p, q)| vec![d, p, q]);
+                    cplex::printing::proc_result_vec(result, args);
+                }
+                AttackActions::CommonModulus(cm_args) => {
+                    let n = num_bigint::BigInt::from_str(cm_args.n.as_str()).expect("could not make bigint");
+                    let e1 = num_bigint::BigInt::from_str(cm_args.e1.as_str()).expect("could not make bigint");
+                    let c1 = num_bigint::BigInt::from_str(cm_args.c1.as_str()).expect("could not make bigint");
+                    let e2 = num_bigint::BigInt::from_str(cm_args.e2.as_str()).expect("could not make bigint");
+                    let c2 = num_bigint::BigInt::from_str(cm_args.c2.as_str()).expect("could not make bigint");
+                    let result = algo::rsa_attacks::common_modulus(&n, &e1, &c1, &e2, &c2, args.verbose);
+                    cplex::printing::proc_result_num(result, args);
+                }
+                AttackActions::Hastad(hastad_args) => {
+                    let ciphertexts: Vec<num_bigint::BigInt> = hastad_args.ciphertexts.iter()
+                        .map(|c| num_bigint::BigInt::from_str(c.as_str()).expect("could not make bigint"))
+                        .collect();
+                    let moduli: Vec<num_bigint::BigInt> = hastad_args.moduli.iter()
+                        .map(|n| num_bigint::BigInt::from_str(n.as_str()).expect("could not make bigint"))
+                        .collect();
+                    let result = algo::rsa_attacks::hastad(&ciphertexts, &moduli, hastad_args.e, args.verbose);
+                    cplex::printing::proc_result_num(result, args);
+                }
+                AttackActions::Fermat(fermat_args) => {
+                    let n = num_bigint::BigInt::from_str(fermat_args.n.as_str()).expect("could not make bigint");
+                    let result = algo::rsa_attacks::fermat(&n, fermat_args.steps, args.verbose);
+                    cplex::printing::proc_result_tup_num(result, args);
+                }
+                AttackActions::FactorD(factor_args) => {
+                    let n = num_bigint::BigInt::from_str(factor_args.n.as_str()).expect("could not make bigint");
+                    let e = num_bigint::BigInt::from_str(factor_args.e.as_str()).expect("could not make bigint");
+                    let d = num_bigint::BigInt::from_str(factor_args.d.as_str()).expect("could not make bigint");
+                    let result = algo::rsa_attacks::factor_with_d(&n, &e, &d, args.verbose);
+                    cplex::printing::proc_result_tup_num(result, args);
+                }
+                AttackActions::Coppersmith(cs_args) => {
+                    let n = num_bigint::BigInt::from_str(cs_args.n.as_str()).expect("could not make bigint");
+                    let bound = num_bigint::BigInt::from_str(cs_args.bound.as_str()).expect("could not make bigint");
+                    match math::coppersmith::parse(cs_args.polynomial.as_str()) {
+                        Ok(f) => {
+                            let result = math::coppersmith::small_roots(&f, &n, &bound, cs_args.m, cs_args.t, args.verbose);
+                            cplex::printing::proc_result_vec(result, args);
+                        }
+                        Err(e) => cplex::printing::proc_err(e, args)
+                    }
+                }
+                AttackActions::Manger(manger_args) => {
+                    let hash: algo::oaep::HashFunction = manger_args.hash.into();
+                    match algo::manger::demo(manger_args.message.as_bytes(), manger_args.bits, hash, args.verbose) {
+                        Ok((r, message)) => {
+                            let text = format!("{r}\nmessage = {}", String::from_utf8_lossy(&message));
+                            cplex::printing::proc_display(text, args)
+                        }
+                        Err(e) => cplex::printing::proc_err(e, args)
+                    }
+                }
+                AttackActions::Rabin(rabin_args) => {
+                    match algo::rabin::demo(rabin_args.bits, rabin_args.redundancy, args.verbose) {
+                        Ok(r) => cplex::printing::proc_display(r, args),
+                        Err(e) => cplex::printing::proc_err(e, args)
+                    }
+                }
+                AttackActions::Bleichenbacher(bb_args) => {
+                    let result = algo::bleichenbacher::demo(bb_args.message.as_bytes(), bb_args.bits, bb_args.strict, args.verbose);
+                    match result {
+                        Ok(r) => cplex::printing::proc_display(r, args),
+                        Err(e) => cplex::printing::proc_err(e, args)
+                    }
+                }
+            }
+        }
+        Commands::Rabin(action) => {
+            match action.action {
+                RabinActions::Keygen(keygen_args) => {
+                    match algo::rabin::RabinPrivateKey::generate(keygen_args.bits, args.verbose) {
+                        Ok(key) => cplex::printing::proc_display(key, args),
+                        Err(e) => cplex::printing::proc_err(e, args),
+                    }
+                }
+                RabinActions::Encrypt(enc_args) => {
+                    let m = num_bigint::BigInt::from_str(enc_args.m.as_str()).expect("could not make bigint");
+                    let n = num_bigint::BigInt::from_str(enc_args.n.as_str()).expect("could not make bigint");
+                    let result = algo::rabin::encrypt(&m, &n, enc_args.redundancy, args.verbose);
+                    cplex::printing::proc_result_num(result, args);
+                }
+                RabinActions::Decrypt(dec_args) => {
+                    let c = num_bigint::BigInt::from_str(dec_args.c.as_str()).expect("could not make bigint");
+                    let p = num_bigint::BigInt::from_str(dec_args.p.as_str()).expect("could not make bigint");
+                    let q = num_bigint::BigInt::from_str(dec_args.q.as_str()).expect("could not make bigint");
+                    if dec_args.redundancy > 0 {
+                        let result = algo::rabin::decrypt_redundant(&c, &p, &q, dec_args.redundancy, args.verbose);
+                        cplex::printing::proc_result_num(result, args);
+                    }
+                    else {
+                        let result = algo::rabin::decrypt(&c, &p, &q, args.verbose);
+                        cplex::printing::proc_result_vec(result, args);
+                    }
+                }
+            }
+        }
+        Commands::Paillier(action) => {
+            match action.action {
+                PaillierActions::Keygen(keygen_args) => {
+                    match algo::paillier::PaillierPrivateKey::generate(keygen_args.bits, args.verbose) {
+                        Ok(key) => cplex::printing::proc_display(key, args),
+                        Err(e) => cplex::printing::proc_err(e, args),
+                    }
+                }
+                PaillierActions::Encrypt(enc_args) => {
+                    let m = num_bigint::BigInt::from_str(enc_args.m.as_str()).expect("could not make bigint");
+                    let n = num_bigint::BigInt::from_str(enc_args.n.as_str()).expect("could not make bigint");
+                    let result = match enc_args.r {
+                        Some(r) => {
+                            let r = num_bigint::BigInt::from_str(r.as_str()).expect("could not make bigint");
+                            algo::paillier::encrypt_with(&m, &r, &n, args.verbose)
+                        }
+                        None => algo::paillier::encrypt(&m, &n, args.verbose)
+                    };
+                    cplex::printing::proc_result_num(result, args);
+                }
+                PaillierActions::Decrypt(dec_args) => {
+                    let c = num_bigint::BigInt::from_str(dec_args.c.as_str()).expect("could not make bigint");
+                    let p = num_bigint::BigInt::from_str(dec_args.p.as_str()).expect("could not make bigint");
+                    let q = num_bigint::BigInt::from_str(dec_args.q.as_str()).expect("could not make bigint");
+                    let result = match algo::paillier::PaillierPrivateKey::from_primes(&p, &q, args.verbose) {
+                        Ok(key) => key.decrypt(&c, args.verbose),
+                        Err(e) => Err(e)
+                    };
+                    cplex::printing::proc_result_num(result, args);
+                }
+                PaillierActions::Add(add_args) => {
+                    let c1 = num_bigint::BigInt::from_str(add_args.c1.as_str()).expect("could not make bigint");
+                    let c2 = num_bigint::BigInt::from_str(add_args.c2.as_str()).expect("could not make bigint");
+                    let n = num_bigint::BigInt::from_str(add_args.n.as_str()).expect("could not make bigint");
+                    let result = algo::paillier::add(&c1, &c2, &n, args.verbose);
+                    cplex::printing::proc_result_num(result, args);
+                }
+                PaillierActions::Mul(mul_args) => {
+                    let c = num_bigint::BigInt::from_str(mul_args.c.as_str()).expect("could not make bigint");
+                    let k = num_bigint::BigInt::from_str(mul_args.k.as_str()).expect("could not make bigint");
+                    let n = num_bigint::BigInt::from_str(mul_args.n.as_str()).expect("could not make bigint");
+                    let result = algo::paillier::scalar_mul(&c, &k, &n, args.verbose);
+                    cplex::printing::proc_result_num(result, args);
+                }
+                PaillierActions::Rerandomize(rr_args) => {
+                    let c = num_bigint::BigInt::from_str(rr_args.c.as_str()).expect("could not make bigint");
+                    let n = num_bigint::BigInt::from_str(rr_args.n.as_str()).expect("could not make bigint");
+                    let result = algo::paillier::rerandomize(&c, &n, args.verbose);
+                    cplex::printing::proc_result_num(result, args);
+                }
+                PaillierActions::Tally(tally_args) => {
+                    match algo::paillier::demo(&tally_args.votes, tally_args.candidates, tally_args.bits, args.verbose) {
+                        Ok(t) => cplex::printing::proc_display(t, args),
+                        Err(e) => cplex::printing::proc_err(e, args)
+                    }
+                }
+            }
+        }
+        Commands::Elgamal(action) => {
+            let group = action.group;
+            let p = num_bigint::BigUint::from_str(group.p.as_str()).expect("could not make bigint");
+            let order = group.order.map(|q| num_bigint::BigUint::from_str(q.as_str()).expect("could not make bigint"));
+            let parse_int = |x: &String| num_bigint::BigUint::from_str(x.as_str()).expect("could not make bigint");
+            let parse_pair = |x: &String| -> (u128, u128) {
+                let (a, b) = x.split_once(',').expect("expected two numbers separated by a comma");
+                (a.trim().parse().expect("could not parse number"), b.trim().parse().expect("could not parse number"))
+            };
+            match group.curve {
+                Some(curve) => {
+                    let (a, b) = curve.split_once(',').expect("expected the curve as a,b");
+                    let a: i128 = a.trim().parse().expect("could not parse a");
+                    let b: i128 = b.trim().parse().expect("could not parse b");
+                    let p = p.to_u128().expect("the prime is too large for a curve");
+                    let order = order.expect("clap requires the order for curves");
+                    let scheme = match algo::elgamal::curve_scheme(p, a, b, parse_pair(&group.g), order) {
+                        Ok(s) => s,
+                        Err(e) => return cplex::printing::proc_err(e, args)
+                    };
+                    let parse_point = |x: &String| {
+                        let (r, s) = parse_pair(x);
+                        scheme.group.new_point(r, s).expect("not a point on the curve")
+                    };
+                    match action.action {
+                        ElgamalActions::Demo(demo_args) => {
+                            match algo::elgamal::demo(&scheme, &parse_point(&demo_args.m), args.verbose) {
+                                Ok(r) => cplex::printing::proc_display(r, args),
+                                Err(e) => cplex::printing::proc_err(e, args)
+                            }
+                        }
+                        ElgamalActions::Malleability(mal_args) => {
+                            let (m, t) = (parse_point(&mal_args.m), parse_point(&mal_args.t));
+                            match algo::elgamal::malleability(&scheme, &m, &t, args.verbose) {
+                                Ok(r) => cplex::printing::proc_display(r, args),
+                                Err(e) => cplex::printing::proc_err(e, args)
+                            }
+                        }
+                        ElgamalActions::Reuse(reuse_args) => {
+                            let (m1, m2, m3) = (parse_int(&reuse_args.m1), parse_int(&reuse_args.m2), parse_int(&reuse_args.m3));
+                            match algo::elgamal::reuse(&scheme, &m1, &m2, &m3, args.verbose) {
+                                Ok(r) => cplex::printing::proc_display(r, args),
+                                Err(e) => cplex::printing::proc_err(e, args)
+                            }
+                        }
+                    }
+                }
+                None => {
+                    let scheme = match algo::elgamal::field_scheme(&p, &parse_int(&group.g), order) {
+                        Ok(s) => s,
+                        Err(e) => return cplex::printing::proc_err(e, args)
+                    };
+                    match action.action {
+                        ElgamalActions::Demo(demo_args) => {
+                            match algo::elgamal::demo(&scheme, &parse_int(&demo_args.m), args.verbose) {
+                                Ok(r) => cplex::printing::proc_display(r, args),
+                                Err(e) => cplex::printing::proc_err(e, args)
+                            }
+                        }
+                        ElgamalActions::Malleability(mal_args) => {
+                            let (m, t) = (parse_int(&mal_args.m), parse_int(&mal_args.t));
+                            match algo::elgamal::malleability(&scheme, &m, &t, args.verbose) {
+                                Ok(r) => cplex::printing::proc_display(r, args),
+                                Err(e) => cplex::printing::proc_err(e, args)
+                            }
+                        }
+                        ElgamalActions::Reuse(reuse_args) => {
+                            let (m1, m2, m3) = (parse_int(&reuse_args.m1), parse_int(&reuse_args.m2), parse_int(&reuse_args.m3));
+                            match algo::elgamal::reuse(&scheme, &m1, &m2, &m3, args.verbose) {
+                                Ok(r) => cplex::printing::proc_display(r, args),
+                                Err(e) => cplex::printing::proc_err(e, args)
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        Commands::Protocol(action) => {
+            match action.action {
+                ProtocolActions::Dh(dh_args) => {
+                    let adversary = match (dh_args.mallory, dh_args.confine) {
+                        (_, Some(t)) => protocol::dh::Adversary::Confine(t),
+                        (true, None) => protocol::dh::Adversary::Substitute,
+                        (false, None) => protocol::dh::Adversary::Absent
+                    };
+                    let order = dh_args.order.map(|q| num_bigint::BigUint::from_str(q.as_str()).expect("could not make bigint"));
+                    match dh_args.curve {
+                        Some(curve) => {
+                            let (a, b) = curve.split_once(',').expect("expected the curve as a,b");
+                            let a: i128 = a.trim().parse().expect("could not parse a");
+                            let b: i128 = b.trim().parse().expect("could not parse b");
+                            let (x, y) = dh_args.g.split_once(',').expect("expected g as x,y");
+                            let g: (u128, u128) = (x.trim().parse().expect("could not parse x"), y.trim().parse().expect("could not parse y"));
+                            let p: u128 = dh_args.p.parse().expect("the prime is too large for a curve");
+                            let order = order.expect("clap requires the order for curves");
+                            let result = protocol::dh::CurveGroup::new(p, a, b, g, order)
+                                .and_then(|group| protocol::dh::exchange(&group, adversary, args.verbose));
+                            match result {
+                                Ok(t) => cplex::printing::proc_display(t, args),
+                                Err(e) => cplex::printing::proc_err(e, args)
+                            }
+                        }
+                        None => {
+                            let p = num_bigint::BigInt::from_str(dh_args.p.as_str()).expect("could not make bigint");
+                            let g = num_bigint::BigInt::from_str(dh_args.g.as_str()).expect("could not make bigint");
+                            let result = protocol::dh::FieldGroup::new(p, g, order)
+                                .and_then(|group| protocol::dh::exchange(&group, adversary, args.verbose));
+                            match result {
+                                Ok(t) => cplex::printing::proc_display(t, args),
+                                Err(e) => cplex::printing::proc_err(e, args)
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        Commands::Rsa(action) => {
+            let signing = matches!(action.action, RsaActions::Sign(_));
+            match action.action {
+                RsaActions::Keygen(keygen_args) => {
+                    let e = num_bigint::BigInt::from_str(keygen_args.e.as_str()).expect("could not make bigint");
+                    match algo::rsa::RsaPrivateKey::generate(keygen_args.bits, &e, args.verbose) {
+                        Ok(key) => cplex::printing::proc_display(key, args),
+                        Err(e) => cplex::printing::proc_err(e, args),
+                    }
+                }
+                RsaActions::Encrypt(enc_args) => {
+                    let m = num_bigint::BigInt::from_str(enc_args.m.as_str()).expect("could not make bigint");
+                    let n = num_bigint::BigInt::from_str(enc_args.n.as_str()).expect("could not make bigint");
+                    let e = num_bigint::BigInt::from_str(enc_args.e.as_str()).expect("could not make bigint");
+                    let result = algo::rsa::encrypt(&m, &n, &e, args.verbose);
+                    cplex::printing::proc_result_num(result, args);
+                }
+                RsaActions::Decrypt(priv_args) | RsaActions::Sign(priv_args) => {
+                    let input = num_bigint::BigInt::from_str(priv_args.input.as_str()).expect("could not make bigint");
+                    let n = num_bigint::BigInt::from_str(priv_args.n.as_str()).expect("could not make bigint");
+                    let d = num_bigint::BigInt::from_str(priv_args.d.as_str()).expect("could not make bigint");
+                    let result = match (priv_args.p, priv_args.q) {
+                        (Some(p), Some(q)) => {
+                            let p = num_bigint::BigInt::from_str(p.as_str()).expect("could not make bigint");
+                            let q = num_bigint::BigInt::from_str(q.as_str()).expect("could not make bigint");
+                            let pq = &p * &q;
+                            if pq != n {
+                                Err(algo::rsa::RsaError::WrongFactors(pq))
+                            }
+                            else {
+                                algo::rsa::decrypt_crt(&input, &p, &q, &d, args.verbose)
+                            }
+                        }
+                        _ if signing => algo::rsa::sign(&input, &n, &d, args.verbose),
+                        _ => algo::rsa::decrypt(&input, &n, &d, args.verbose)
+                    };
+                    cplex::printing::proc_result_num(result, args);
+                }
+                RsaActions::Verify(verify_args) => {
+                    let m = num_bigint::BigInt::from_str(verify_args.m.as_str()).expect("could not make bigint");
+                    let s = num_bigint::BigInt::from_str(verify_args.s.as_str()).expect("could not make bigint");
+                    let n = num_bigint::BigInt::from_str(verify_args.n.as_str()).expect("could not make bigint");
+                    let e = num_bigint::BigInt::from_str(verify_args.e.as_str()).expect("could not make bigint");
+                    let result = algo::rsa::verify(&m, &s, &n, &e, args.verbose);
+                    cplex::printing::proc_display(result, args);
+                }
+                RsaActions::OaepEncrypt(enc_args) => {
+                    let n = num_bigint::BigInt::from_str(enc_args.n.as_str()).expect("could not make bigint");
+                    let e = num_bigint::BigInt::from_str(enc_args.e.as_str()).expect("could not make bigint");
+                    let hash: algo::oaep::HashFunction = enc_args.hash.into();
+                    let result = algo::oaep::encrypt(enc_args.message.as_bytes(), enc_args.label.as_bytes(), &n, &e, hash, args.verbose);
+                    cplex::printing::proc_result_num(result, args);
+                }
+                RsaActions::OaepDecrypt(dec_args) => {
+                    let c = num_bigint::BigInt::from_str(dec_args.c.as_str()).expect("could not make bigint");
+                    let n = num_bigint::BigInt::from_str(dec_args.n.as_str()).expect("could not make bigint");
+                    let d = num_bigint::BigInt::from_str(dec_args.d.as_str()).expect("could not make bigint");
+                    let hash: algo::oaep::HashFunction = dec_args.hash.into();
+                    match algo::oaep::decrypt(&c, dec_args.label.as_bytes(), &n, &d, hash, args.verbose) {
+                        Ok(message) => cplex::printing::proc_display(String::from_utf8_lossy(&message), args),
+                        Err(e) => cplex::printing::proc_err(e, args)
+                    }
+                }
+            }
+        }
     }
 }
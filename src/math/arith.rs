@@ -0,0 +1,387 @@
+#![allow(dead_code)]
+/// number theoretic functions
+///
+/// Euler's totient φ(n), Carmichael's λ(n), the multiplicative order of a modulo n and primitive
+/// roots. All of these are calculated from the prime factorisation of n or φ(n), which is found
+/// with [pollard_rho_factors].
+///
+/// Author:     Christoph J. Scherr <software@cscherr.de>
+/// License:    MIT
+/// Source:     <https://git.cscherr.de/PlexSheep/plexcryptool/>
+
+use crate::cplex::printing::seperator;
+use crate::math::factorise::pollard_rho_factors;
+use crate::math::modexp::modular_exponentiation;
+
+use core::fmt;
+
+use num::Integer;
+use num_bigint::BigInt;
+use num_traits::{One, ToPrimitive};
+
+use pyo3::{prelude::*, exceptions::PyValueError};
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// maximum amount of primitive roots that are listed
+pub const MAX_PRIMITIVE_ROOTS: u64 = 1 << 20;
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// errors for number theoretic functions
+pub enum ArithError {
+    /// n must be positive
+    NotPositive(BigInt),
+    /// a has no multiplicative order mod n
+    NotCoprime {
+        /// the element
+        a: BigInt,
+        /// the modulus
+        n: BigInt
+    },
+    /// n is not 1, 2, 4, p^k or 2p^k
+    NoPrimitiveRoot(BigInt),
+    /// there are too many primitive roots to list them
+    TooMany(BigInt),
+}
+
+impl fmt::Display for ArithError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ArithError::NotPositive(n) => write!(f, "{n} is not positive"),
+            ArithError::NotCoprime { a, n } => write!(f, "gcd({a}, {n}) != 1, {a} has no order mod {n}"),
+            ArithError::NoPrimitiveRoot(n) => write!(f, "there are no primitive roots mod {n}"),
+            ArithError::TooMany(count) => write!(f, "there are {count} primitive roots, too many to list"),
+        }
+    }
+}
+
+/// display a factorisation like 2^3 * 5
+fn display_factors(factors: &[(BigInt, u32)]) -> String {
+    if factors.is_empty() {
+        return String::from("1");
+    }
+    let parts: Vec<String> = factors.iter().map(|(p, e)| {
+        if *e == 1 { format!("{p}") } else { format!("{p}^{e}") }
+    }).collect();
+    parts.join(" * ")
+}
+
+/// Euler's totient function
+///
+/// φ(n) = prod(p^(e-1) * (p-1)) for n = prod(p^e)
+pub fn totient(n: &BigInt, verbose: bool) -> Result<BigInt, ArithError> {
+    if *n < BigInt::one() {
+        return Err(ArithError::NotPositive(n.clone()));
+    }
+    let factors = pollard_rho_factors(n, false);
+    let mut phi = BigInt::one();
+    if verbose {
+        println!("n = {n} = {}", display_factors(&factors));
+    }
+    for (p, e) in &factors {
+        let part = p.pow(e - 1) * (p - 1u8);
+        if verbose {
+            println!("φ({p}^{e}) = {p}^{} * ({p} - 1) = {part}", e - 1);
+        }
+        phi *= part;
+    }
+    if verbose {
+        println!("φ({n}) = {phi}");
+    }
+    Ok(phi)
+}
+
+/// Carmichael's function
+///
+/// λ(n) is the smallest m with a^m = 1 (mod n) for every a coprime to n.
+/// λ(2) = 1, λ(4) = 2, λ(2^e) = 2^(e-2) for e >= 3, λ(p^e) = φ(p^e) for odd primes and
+/// λ(n) = lcm(λ(p^e)) for n = prod(p^e)
+pub fn carmichael(n: &BigInt, verbose: bool) -> Result<BigInt, ArithError> {
+    if *n < BigInt::one() {
+        return Err(ArithError::NotPositive(n.clone()));
+    }
+    let factors = pollard_rho_factors(n, false);
+    if verbose {
+        println!("n = {n} = {}", display_factors(&factors));
+    }
+    let mut lambda = BigInt::one();
+    for (p, e) in &factors {
+        let part: BigInt = if *p == BigInt::from(2) && *e >= 3 {
+            BigInt::one() << (e - 2)
+        }
+        else {
+            p.pow(e - 1) * (p - 1u8)
+        };
+        if verbose {
+            println!("λ({p}^{e}) = {part}");
+        }
+        lambda = lambda.lcm(&part);
+    }
+    if verbose {
+        println!("λ({n}) = lcm(...) = {lambda}");
+    }
+    Ok(lambda)
+}
+
+/// multiplicative order of a mod n
+///
+/// the smallest k > 0 with a^k = 1 (mod n). Starts with k = φ(n) and divides out prime factors q
+/// of φ(n) as long as a^(k/q) = 1 (mod n).
+pub fn multiplicative_order(a: &BigInt, n: &BigInt, verbose: bool) -> Result<BigInt, ArithError> {
+    if *n < BigInt::one() {
+        return Err(ArithError::NotPositive(n.clone()));
+    }
+    let a = a.mod_floor(n);
+    if !a.gcd(n).is_one() {
+        return Err(ArithError::NotCoprime { a, n: n.clone() });
+    }
+    if n.is_one() {
+        return Ok(BigInt::one());
+    }
+    let phi = totient(n, false)?;
+    let factors = pollard_rho_factors(&phi, false);
+    if verbose {
+        println!("φ({n}) = {phi} = {}", display_factors(&factors));
+    }
+    let mut k = phi;
+    for (q, _) in &factors {
+        while k.is_multiple_of(q) {
+            let candidate: BigInt = &k / q;
+            let r = modular_exponentiation(a.clone(), candidate.clone(), n.clone(), false);
+            if verbose {
+                println!("{a}^({k}/{q}) = {a}^{candidate} = {r} (mod {n})");
+            }
+            if !r.is_one() {
+                break;
+            }
+            k = candidate;
+        }
+    }
+    if verbose {
+        println!("ord_{n}({a}) = {k}");
+    }
+    Ok(k)
+}
+
+/// test if there are primitive roots mod n
+///
+/// that is the case if n is 1, 2, 4, p^k or 2p^k for an odd prime p
+pub fn has_primitive_roots(n: &BigInt) -> bool {
+    if *n < BigInt::one() {
+        return false;
+    }
+    if *n <= BigInt::from(4) {
+        return true;
+    }
+    let m = if n.is_even() { n / 2u8 } else { n.clone() };
+    if m.is_even() {
+        return false;
+    }
+    pollard_rho_factors(&m, false).len() == 1
+}
+
+/// test if g is a primitive root, using the prime factors of φ(n)
+fn is_primitive_root(g: &BigInt, n: &BigInt, phi: &BigInt, primes: &[BigInt], verbose: bool) -> bool {
+    if !g.gcd(n).is_one() {
+        return false;
+    }
+    for q in primes {
+        let r = modular_exponentiation(g.clone(), phi / q, n.clone(), false);
+        if verbose {
+            println!("{g}^(φ/{q}) = {g}^{} = {r} (mod {n})", phi / q);
+        }
+        if r.is_one() {
+            return false;
+        }
+    }
+    true
+}
+
+/// find the smallest primitive root mod n
+///
+/// g is a primitive root if g^(φ(n)/q) != 1 (mod n) for every prime q dividing φ(n)
+pub fn primitive_root(n: &BigInt, verbose: bool) -> Result<BigInt, ArithError> {
+    if *n < BigInt::one() {
+        return Err(ArithError::NotPositive(n.clone()));
+    }
+    if !has_primitive_roots(n) {
+        return Err(ArithError::NoPrimitiveRoot(n.clone()));
+    }
+    if *n <= BigInt::from(2) {
+        return Ok(n - 1u8);
+    }
+    let phi = totient(n, false)?;
+    let primes: Vec<BigInt> = pollard_rho_factors(&phi, false).into_iter().map(|(q, _)| q).collect();
+    if verbose {
+        println!("φ({n}) = {phi}, prime factors: {:?}", primes.iter().map(|q| q.to_string()).collect::<Vec<String>>());
+    }
+    let mut g = BigInt::from(2);
+    while &g < n {
+        if verbose {
+            seperator();
+            println!("g = {g}");
+        }
+        if is_primitive_root(&g, n, &phi, &primes, verbose) {
+            if verbose {
+                seperator();
+                println!("{g} is a primitive root mod {n}");
+            }
+            return Ok(g);
+        }
+        g += 1u8;
+    }
+    Err(ArithError::NoPrimitiveRoot(n.clone()))
+}
+
+/// find all primitive roots mod n in ascending order
+///
+/// with a primitive root g, these are g^k for all k coprime to φ(n). There are φ(φ(n)) of them.
+pub fn primitive_roots(n: &BigInt, verbose: bool) -> Result<Vec<BigInt>, ArithError> {
+    let g = primitive_root(n, verbose)?;
+    let phi = totient(n, false)?;
+    let count = totient(&phi, false)?;
+    if verbose {
+        seperator();
+        println!("there are φ(φ({n})) = φ({phi}) = {count} primitive roots");
+    }
+    if count > BigInt::from(MAX_PRIMITIVE_ROOTS) {
+        return Err(ArithError::TooMany(count));
+    }
+    let phi_u = phi.to_u64().expect("φ(n) is small if there are few primitive roots");
+    let mut roots: Vec<BigInt> = Vec::new();
+    let mut power = BigInt::one();
+    for k in 1..=phi_u {
+        power = (power * &g).mod_floor(n);
+        if k.gcd(&phi_u) == 1 {
+            roots.push(power.clone());
+        }
+    }
+    roots.sort();
+    Ok(roots)
+}
+
+#[pyfunction]
+#[pyo3(name = "totient")]
+#[pyo3(signature=(n, verbose = false))]
+/// python wrapper for totient
+pub fn py_totient(n: BigInt, verbose: bool) -> PyResult<BigInt> {
+    match totient(&n, verbose) {
+        Ok(v) => Ok(v),
+        Err(e) => Err(PyValueError::new_err(e.to_string()))
+    }
+}
+
+#[pyfunction]
+#[pyo3(name = "carmichael")]
+#[pyo3(signature=(n, verbose = false))]
+/// python wrapper for carmichael
+pub fn py_carmichael(n: BigInt, verbose: bool) -> PyResult<BigInt> {
+    match carmichael(&n, verbose) {
+        Ok(v) => Ok(v),
+        Err(e) => Err(PyValueError::new_err(e.to_string()))
+    }
+}
+
+#[pyfunction]
+#[pyo3(name = "multiplicative_order")]
+#[pyo3(signature=(a, n, verbose = false))]
+/// python wrapper for multiplicative_order
+pub fn py_multiplicative_order(a: BigInt, n: BigInt, verbose: bool) -> PyResult<BigInt> {
+    match multiplicative_order(&a, &n, verbose) {
+        Ok(v) => Ok(v),
+        Err(e) => Err(PyValueError::new_err(e.to_string()))
+    }
+}
+
+#[pyfunction]
+#[pyo3(name = "has_primitive_roots")]
+/// python wrapper for has_primitive_roots
+pub fn py_has_primitive_roots(n: BigInt) -> bool {
+    has_primitive_roots(&n)
+}
+
+#[pyfunction]
+#[pyo3(name = "primitive_root")]
+#[pyo3(signature=(n, verbose = false))]
+/// python wrapper for primitive_root
+pub fn py_primitive_root(n: BigInt, verbose: bool) -> PyResult<BigInt> {
+    match primitive_root(&n, verbose) {
+        Ok(v) => Ok(v),
+        Err(e) => Err(PyValueError::new_err(e.to_string()))
+    }
+}
+
+#[pyfunction]
+#[pyo3(name = "primitive_roots")]
+#[pyo3(signature=(n, verbose = false))]
+/// python wrapper for primitive_roots
+pub fn py_primitive_roots(n: BigInt, verbose: bool) -> PyResult<Vec<BigInt>> {
+    match primitive_roots(&n, verbose) {
+        Ok(v) => Ok(v),
+        Err(e) => Err(PyValueError::new_err(e.to_string()))
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+pub mod test {
+
+    use super::*;
+
+    fn big(n: i64) -> BigInt {
+        BigInt::from(n)
+    }
+
+    #[test]
+    fn test_totient() {
+        let phis = [1, 1, 2, 2, 4, 2, 6, 4, 6, 4, 10, 4];
+        for (i, phi) in phis.iter().enumerate() {
+            assert_eq!(totient(&big(i as i64 + 1), false), Ok(big(*phi)));
+        }
+        assert_eq!(totient(&big(1000003 * 1000033), true), Ok(big(1000002 * 1000032)));
+        assert_eq!(totient(&big(0), false), Err(ArithError::NotPositive(big(0))));
+    }
+
+    #[test]
+    fn test_carmichael() {
+        let lambdas = [1, 1, 2, 2, 4, 2, 6, 2, 6, 4, 10, 2, 12, 6, 4, 4];
+        for (i, lambda) in lambdas.iter().enumerate() {
+            assert_eq!(carmichael(&big(i as i64 + 1), false), Ok(big(*lambda)));
+        }
+        // smallest carmichael number
+        assert_eq!(carmichael(&big(561), true), Ok(big(80)));
+    }
+
+    #[test]
+    fn test_multiplicative_order() {
+        assert_eq!(multiplicative_order(&big(2), &big(7), true), Ok(big(3)));
+        assert_eq!(multiplicative_order(&big(3), &big(7), false), Ok(big(6)));
+        assert_eq!(multiplicative_order(&big(-1), &big(7), false), Ok(big(2)));
+        assert_eq!(multiplicative_order(&big(2), &big(15), false), Ok(big(4)));
+        assert_eq!(multiplicative_order(&big(7), &big(561), false), Ok(big(80)));
+        assert_eq!(multiplicative_order(&big(3), &big(15), false), Err(ArithError::NotCoprime { a: big(3), n: big(15) }));
+        assert_eq!(multiplicative_order(&big(36), &big(8101), false), Ok(big(4050)));
+    }
+
+    #[test]
+    fn test_primitive_roots() {
+        let with = [1, 2, 3, 4, 5, 6, 7, 9, 10, 11, 13, 14, 18, 25, 27, 50, 1019];
+        let without = [8, 12, 15, 16, 20, 21, 24, 28, 561];
+        for n in with {
+            assert!(has_primitive_roots(&big(n)), "{n} has primitive roots");
+        }
+        for n in without {
+            assert!(!has_primitive_roots(&big(n)), "{n} has no primitive roots");
+            assert_eq!(primitive_root(&big(n), false), Err(ArithError::NoPrimitiveRoot(big(n))));
+        }
+        assert_eq!(primitive_root(&big(7), true), Ok(big(3)));
+        assert_eq!(primitive_root(&big(8101), false), Ok(big(6)));
+        assert_eq!(primitive_root(&big(2147483579), false), Ok(big(2)));
+        assert_eq!(primitive_roots(&big(7), false), Ok(vec![big(3), big(5)]));
+        assert_eq!(primitive_roots(&big(13), false), Ok(vec![big(2), big(6), big(7), big(11)]));
+        assert_eq!(primitive_roots(&big(18), false), Ok(vec![big(5), big(11)]));
+        assert_eq!(primitive_roots(&big(2), false), Ok(vec![big(1)]));
+    }
+}
@@ -0,0 +1,681 @@
+#![allow(dead_code)]
+/// calculation in a gallois field with arbitrary precision
+///
+/// [GalloisField](super::gallois::GalloisField) is limited to u128, which is not enough for the
+/// fields that are actually used, like the 256 bit prime fields behind P-256 or secp256k1.
+/// This module implements the same functionality on BigUint.
+///
+/// Like the u128 version, prime bases and powers of two (with a relation) are supported.
+///
+/// Author:     Christoph J. Scherr <software@cscherr.de>
+/// License:    MIT
+/// Source:     <https://git.cscherr.de/PlexSheep/plexcryptool/>
+
+use crate::{
+    math::reduction::ModContext,
+    math::gcd::mod_inverse,
+    math::modred::modred_big,
+    math::group::Group,
+    math::dlog::{dlog, pohlig_hellman, pollard_rho_dlog, kangaroo},
+    math::primality::is_prime,
+    math::jacobi::{jacobi, legendre},
+    math::gallois::{
+        GalloisField,
+        DivisionByZeroError,
+        NoRootError,
+        F_8_DEFAULT_RELATION,
+        F_16_DEFAULT_RELATION,
+        F_256_DEFAULT_RELATION
+    },
+    cplex::printing::seperator
+};
+
+use core::fmt;
+
+use num::Integer;
+use num_bigint::{BigInt, BigUint};
+use num_traits::{One, Zero, ToPrimitive};
+
+use pyo3::{prelude::*, exceptions::PyValueError};
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+#[derive(Debug)]
+/// used when trying to find an inverse for a number which does not have one.
+pub struct BigNoInverseError {
+    /// the number without inverse
+    pub n: BigUint
+}
+
+impl fmt::Display for BigNoInverseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "inverse for {} does not exist", self.n)
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[pyclass]
+/// represent a gallois field with arbitrary precision
+///
+/// PartialEq and Eq might behave badly when verbosity is not the same FIXME
+pub struct BigGalloisField {
+    /// number of elements in the field
+    pub base: BigUint,
+    /// characteristic of the field
+    pub cha: BigUint,
+    /// print steps
+    pub verbose: bool,
+    /// true if the base is prime, false if it is a power of two
+    pub prime_base: bool,
+    /// relation (irreducible polynomial) for non prime bases
+    pub relation: Option<BigUint>,
+    /// reduction context for prime bases, shared by all exponentiations
+    ctx: Option<ModContext>
+}
+
+/// implementations for the big gallois field
+impl BigGalloisField {
+    /// make a new gallois field
+    pub fn new(base: BigUint, verbose: bool, mut relation: Option<BigUint>) -> Self {
+        let prime_base: bool = is_prime(&BigInt::from(base.clone()), false);
+        if !prime_base {
+            println!("Non prime bases for a field are currently very experimental.\nUse them at your own risk! ({} is not a prime.)", base);
+            if relation.is_none() {
+                match base.to_u128() {
+                    Some(8) => {
+                        relation = Some(BigUint::from(F_8_DEFAULT_RELATION));
+                    }
+                    Some(16) => {
+                        relation = Some(BigUint::from(F_16_DEFAULT_RELATION));
+                    }
+                    Some(256) => {
+                        relation = Some(BigUint::from(F_256_DEFAULT_RELATION));
+                    }
+                    _ => {
+                        panic!("You did not specify a relation and none could be found.");
+                    }
+                }
+            }
+        }
+        let mut field = BigGalloisField {
+            cha: base.clone(),
+            base,
+            verbose,
+            prime_base,
+            relation,
+            ctx: None
+        };
+        field.ctx = field.make_context();
+        field.cha = field.calc_char();
+        if verbose {
+            println!("In Gallois Field F_{}", field.base);
+        }
+        field
+    }
+
+    /// reduce any integer to fit into the gallois field
+    pub fn reduce<T>(&self, n: T) -> BigUint
+        where
+        T: Into<BigInt>
+    {
+        let n: BigInt = n.into();
+        if self.prime_base {
+            n.mod_floor(&BigInt::from(self.base.clone()))
+                .to_biguint()
+                .expect("reduced number is negative")
+        }
+        else {
+            let n = n.to_biguint().expect("reduction for negative numbers not implemented.");
+            modred_big(n, self.relation.as_ref().unwrap(), false)
+        }
+    }
+
+    /// multiplication in the field
+    ///
+    /// if the base is a prime power, the elements are multiplied as polynomials and reduced by
+    /// the relation of the field.
+    pub fn mul(&self, a: &BigUint, b: &BigUint) -> BigUint {
+        if self.prime_base {
+            self.reduce(a * b)
+        }
+        else {
+            let a = self.reduce(a.clone());
+            let b = self.reduce(b.clone());
+            let mut r = BigUint::zero();
+            for index in 0..b.bits() {
+                if b.bit(index) {
+                    r ^= &a << index;
+                }
+            }
+            self.reduce(r)
+        }
+    }
+
+    /// the reduction context for a prime base
+    fn make_context(&self) -> Option<ModContext> {
+        if self.prime_base {
+            Some(ModContext::new(&BigInt::from(self.base.clone())))
+        }
+        else {
+            None
+        }
+    }
+
+    /// calculate the exponent of a base in the field
+    pub fn pow(&self, base: &BigUint, exp: &BigUint) -> BigUint {
+        if let Some(ctx) = &self.ctx {
+            let r = ctx.pow(&BigInt::from(base.clone()), &BigInt::from(exp.clone()));
+            return self.reduce(r);
+        }
+        // square and multiply with polynomials
+        let mut res = BigUint::one();
+        for index in (0..exp.bits()).rev() {
+            res = self.mul(&res, &res);
+            if exp.bit(index) {
+                res = self.mul(&res, base);
+            }
+        }
+        res
+    }
+
+    /// find the additive inverse of a number
+    pub fn a_inverse(&self, n: &BigUint) -> BigUint {
+        if !self.prime_base {
+            // every element is its own additive inverse in characteristic 2
+            return self.reduce(n.clone());
+        }
+        self.reduce(BigInt::from(self.base.clone()) - BigInt::from(self.reduce(n.clone())))
+    }
+
+    /// find the multiplicative inverse of a number
+    pub fn inverse(&self, n: &BigUint) -> Result<BigUint, BigNoInverseError> {
+        let n = self.reduce(n.clone());
+        if n.is_zero() {
+            return Err(BigNoInverseError{n});
+        }
+        if self.prime_base {
+            match mod_inverse(&BigInt::from(n.clone()), &BigInt::from(self.base.clone())) {
+                Ok(inv) => Ok(self.reduce(inv)),
+                Err(_) => Err(BigNoInverseError{n})
+            }
+        }
+        else {
+            // a^(q-2) = a^(-1) for every element of F_q
+            let exp = &self.base - BigUint::from(2u8);
+            Ok(self.pow(&n, &exp))
+        }
+    }
+
+    /// addition in the field
+    ///
+    /// in case of a prime base, addition works as normal,
+    /// if the base is a prime power, all elements are treated as polynomials, so the
+    /// operations are changed too.
+    pub fn add(&self, a: &BigUint, b: &BigUint) -> BigUint {
+        let a = self.reduce(a.clone());
+        let b = self.reduce(b.clone());
+        if self.prime_base {
+            self.reduce(a + b)
+        }
+        else {
+            let r = &a ^ &b;
+            if self.verbose {
+                println!("r = a ^ b = {a:b} ^ {b:b} = {r:b}\n\
+                        r = a + b = ({}) + ({}) = {}",
+                        self.display(&a),
+                        self.display(&b),
+                        self.display(&r),
+                        );
+            }
+            self.reduce(r)
+        }
+    }
+
+    /// subtraction in the field
+    ///
+    /// in case of a prime base, subtraction works as normal,
+    /// if the base is a prime power, all elements are treated as polynomials, so the
+    /// operations are changed too.
+    pub fn sub(&self, a: &BigUint, b: &BigUint) -> BigUint {
+        if self.prime_base {
+            let a = BigInt::from(self.reduce(a.clone()));
+            let b = BigInt::from(self.reduce(b.clone()));
+            self.reduce(a - b)
+        }
+        else {
+            // subtraction and addition are the same for polynomials over F_2
+            self.add(a, b)
+        }
+    }
+
+    /// division in the field
+    pub fn divide(&self, a: &BigUint, b: &BigUint) -> Result<BigUint, DivisionByZeroError> {
+        match self.inverse(b) {
+            Ok(r) => Ok(self.mul(a, &r)),
+            Err(e) => {
+                if self.verbose {
+                    println!("{e}");
+                }
+                Err(DivisionByZeroError)
+            }
+        }
+    }
+
+    /// calculate the square root of a number in a field
+    ///
+    /// only prime fields are supported.
+    pub fn sqrt(&self, a: &BigUint) -> Result<(BigUint, BigUint), NoRootError> {
+        if !self.prime_base {
+            panic!("square roots are only implemented for prime fields");
+        }
+        let a = self.reduce(a.clone());
+        let one = BigUint::one();
+        let pm1 = &self.base - &one;
+        let pm1_2: BigUint = &pm1 >> 1;
+        // in F_2 every element is its own root, the legendre symbol needs an odd prime
+        if a.is_zero() || self.base == BigUint::from(2u8) {
+            return Ok((a.clone(), a));
+        }
+        // a has a root iff the legendre symbol (a/p) is 1
+        let base = BigInt::from(self.base.clone());
+        let symbol = match legendre(&BigInt::from(a.clone()), &base, self.verbose) {
+            Ok(s) => s,
+            Err(_) => return Err(NoRootError)
+        };
+        if symbol != 1 {
+            if self.verbose {
+                println!("({a}/{}) != 1 => a has no root.", self.base);
+            }
+            return Err(NoRootError);
+        }
+
+        // 4 | (p + 1):
+        if (&self.base + &one).is_multiple_of(&BigUint::from(4u8)) {
+            let exp: BigUint = (&self.base + &one) >> 2;
+            let w1 = self.pow(&a, &exp);
+            let w2 = self.a_inverse(&w1);
+            if self.verbose {
+                seperator();
+                println!("4 divides p+1");
+                println!("w_1 = a^([p+1]/[4]) = {a}^({exp}) = {w1} (mod {})", self.base);
+                println!("w_2 = -w_1 = -{w1} = {w2} (mod {})", self.base);
+                println!("found sqrt of {a} as ({w1}, {w2})");
+            }
+            return Ok((w1, w2));
+        }
+        // 4 !| (p + 1):
+        if self.verbose {
+            seperator();
+            println!("4 does not divide p+1");
+            seperator();
+        }
+        // [p-1]/[2] = 2^l * t with t odd
+        let l: u64 = pm1_2.trailing_zeros().unwrap_or(0);
+        let t: BigUint = &pm1_2 >> l;
+        // chose a b so that (b/p) == -1
+        let mut b_candidate = BigUint::from(2u8);
+        let b: BigUint = loop {
+            if b_candidate >= self.base {
+                if self.verbose {
+                    seperator();
+                    println!("found no fitting b");
+                }
+                return Err(NoRootError);
+            }
+            if jacobi(&BigInt::from(b_candidate.clone()), &base, false) == Ok(-1) {
+                if self.verbose {
+                    println!("({b_candidate}/{}) = -1", self.base);
+                    println!("found a b that fits the criteria: {b_candidate}");
+                    seperator();
+                }
+                break b_candidate;
+            }
+            b_candidate += 1u8;
+        };
+        let pm1_4: BigUint = &pm1 >> 2;
+        let mut n: Vec<BigUint> = vec![BigUint::zero()];
+        if self.verbose {
+            println!("l = {l}\tt = {t}\tb = {b}");
+            println!("let n_0 = 0");
+        }
+        for index in 0..l {
+            let i = index as usize;
+            // a^(2^[l-(i+1)]*t) * b^(n_i)
+            let exp: BigUint = &t << (l - (index + 1));
+            let c = self.mul(&self.pow(&a, &exp), &self.pow(&b, &n[i]));
+            if self.verbose {
+                println!("{index}.\ta^(2^[l-(i+1)]*t) * b^(n_{index}) = {a}^(2^[{l}-({index}+1)]*{t}) * {b}^({}) = {c} (mod {})",
+                         n[i],
+                         self.base
+                        );
+                println!("{index}.\tc_{index} = {c}");
+            }
+            if c.is_one() {
+                n.push(&n[i] >> 1);
+                if self.verbose {
+                    println!("{index}.\tc_{index} = 1 => n_{} = [n_{index}]/[2] = {}", index + 1, n[i + 1]);
+                }
+            }
+            else {
+                n.push((&n[i] >> 1) + &pm1_4);
+                if self.verbose {
+                    println!("{index}.\tc_{index} != 1 => n_{} = [n_{index}]/[2] + [p-1]/[4] = [{}]/[2] + [{pm1}]/[4] = {}",
+                             index + 1,
+                             n[i],
+                             n[i + 1]
+                            );
+                }
+            }
+        }
+        let exp: BigUint = (&t + &one) >> 1;
+        let w1 = self.mul(&self.pow(&a, &exp), &self.pow(&b, &n[l as usize]));
+        if self.verbose {
+            seperator();
+            println!("w_1 = [a^(t+1)]/[2] * b^(n_l) = [{a}^([{t}+1])]/[2] * {b}^{} = {} (mod {})",
+                     n[l as usize],
+                     w1,
+                     self.base
+                    );
+        }
+        let w2 = self.a_inverse(&w1);
+        if self.verbose {
+            println!("w_2 = -w_1 = -{w1} = {w2} (mod {})", self.base);
+            println!("found sqrt of {a} as ({w1}, {w2})");
+        }
+        Ok((w1, w2))
+    }
+
+    /// calculate the characteristic of the field
+    pub fn calc_char(&self) -> BigUint {
+        if self.prime_base {
+            return self.base.clone();
+        }
+        if self.base.count_ones() == 1 {
+            return BigUint::from(2u8);
+        }
+        panic!("GalloisField for bases other then primes or powers of two not implemented.")
+    }
+
+    /// display an element in the field
+    ///
+    /// n is a polynomial or a number in the prime field
+    pub fn display(&self, n: &BigUint) -> String {
+        let n = self.reduce(n.clone());
+        if self.prime_base {
+            return n.to_string();
+        }
+        let mut buf: String = String::new();
+        for index in (0..n.bits()).rev() {
+            if !n.bit(index) {
+                continue;
+            }
+            if !buf.is_empty() {
+                buf += " + ";
+            }
+            if index == 0 {
+                buf += "1";
+            }
+            else {
+                buf += format!("α^{}", index).as_str();
+            }
+        }
+        if self.verbose {
+            println!("{n:#x} as polynomial:\n{buf}");
+        }
+        buf
+    }
+}
+
+impl From<GalloisField> for BigGalloisField {
+    fn from(field: GalloisField) -> Self {
+        BigGalloisField {
+            base: BigUint::from(field.base),
+            cha: BigUint::from(field.cha),
+            verbose: field.verbose,
+            prime_base: field.prime_base,
+            relation: field.relation.map(BigUint::from),
+            ctx: if field.prime_base { Some(field.context()) } else { None }
+        }
+    }
+}
+
+#[pymethods]
+/// python wrappers for the big gallois field
+impl BigGalloisField {
+    #[new]
+    #[pyo3(signature=(base, verbose = false, relation = None))]
+    /// make a new gallois field
+    pub fn py_new(base: BigUint, verbose: bool, relation: Option<BigUint>) -> Self {
+        BigGalloisField::new(base, verbose, relation)
+    }
+
+    #[pyo3(name="pow")]
+    /// calculate the exponent of a base in the field
+    pub fn py_pow(&self, base: BigUint, exp: BigUint) -> BigUint {
+        self.pow(&base, &exp)
+    }
+
+    #[pyo3(name="mul")]
+    /// multiply two elements of the field
+    pub fn py_mul(&self, a: BigUint, b: BigUint) -> BigUint {
+        self.mul(&a, &b)
+    }
+
+    #[pyo3(name="add")]
+    /// add two elements of the field
+    pub fn py_add(&self, a: BigUint, b: BigUint) -> BigUint {
+        self.add(&a, &b)
+    }
+
+    #[pyo3(name="sub")]
+    /// subtract two elements of the field
+    pub fn py_sub(&self, a: BigUint, b: BigUint) -> BigUint {
+        self.sub(&a, &b)
+    }
+
+    #[pyo3(name="reduce")]
+    /// reduce any int
+    pub fn py_reduce(&self, n: BigInt) -> BigUint {
+        self.reduce(n)
+    }
+
+    #[pyo3(name="a_inverse")]
+    /// find the additive inverse of a number
+    pub fn py_a_inverse(&self, n: BigUint) -> BigUint {
+        self.a_inverse(&n)
+    }
+
+    #[pyo3(name="sqrt")]
+    /// calculate the square root of a number in a field
+    pub fn py_sqrt(&self, a: BigUint) -> PyResult<(BigUint, BigUint)> {
+        match self.sqrt(&a) {
+            Ok(v) => Ok(v),
+            Err(e) => Err(PyValueError::new_err(e.to_string()))
+        }
+    }
+
+    #[pyo3(name="inverse")]
+    /// get multiplicative inverse
+    pub fn py_inverse(&self, n: BigUint) -> PyResult<BigUint> {
+        match self.inverse(&n) {
+            Ok(v) => Ok(v),
+            Err(e) => Err(PyValueError::new_err(e.to_string()))
+        }
+    }
+
+    #[pyo3(name="divide")]
+    /// divide a by b
+    pub fn py_divide(&self, a: BigUint, b: BigUint) -> PyResult<BigUint> {
+        match self.divide(&a, &b) {
+            Ok(v) => Ok(v),
+            Err(e) => Err(PyValueError::new_err(e.to_string()))
+        }
+    }
+
+    #[pyo3(name="dlog", signature=(g, h, verbose = false))]
+    /// find x with g^x = h using baby-step giant-step
+    pub fn py_dlog(&self, g: BigUint, h: BigUint, verbose: bool) -> PyResult<BigUint> {
+        match dlog(self, &g, &h, verbose) {
+            Ok(x) => Ok(x),
+            Err(e) => Err(PyValueError::new_err(e.to_string()))
+        }
+    }
+
+    #[pyo3(name="pohlig_hellman", signature=(g, h, verbose = false))]
+    /// find x with g^x = h using pohlig-hellman
+    pub fn py_pohlig_hellman(&self, g: BigUint, h: BigUint, verbose: bool) -> PyResult<BigUint> {
+        match pohlig_hellman(self, &g, &h, &self.order_bound(), verbose) {
+            Ok(x) => Ok(x),
+            Err(e) => Err(PyValueError::new_err(e.to_string()))
+        }
+    }
+
+    #[pyo3(name="rho_dlog", signature=(g, h, order = None, verbose = false))]
+    /// find x with g^x = h using pollard's rho, order is the order of g
+    pub fn py_rho_dlog(&self, g: BigUint, h: BigUint, order: Option<BigUint>, verbose: bool) -> PyResult<BigUint> {
+        let order = order.unwrap_or_else(|| self.order_bound());
+        match pollard_rho_dlog(self, &g, &h, &order, verbose) {
+            Ok(x) => Ok(x),
+            Err(e) => Err(PyValueError::new_err(e.to_string()))
+        }
+    }
+
+    #[pyo3(name="kangaroo", signature=(g, h, a, b, verbose = false))]
+    /// find x in [a, b] with g^x = h using pollard's kangaroo
+    pub fn py_kangaroo(&self, g: BigUint, h: BigUint, a: BigUint, b: BigUint, verbose: bool) -> PyResult<BigUint> {
+        match kangaroo(self, &g, &h, &a, &b, verbose) {
+            Ok(x) => Ok(x),
+            Err(e) => Err(PyValueError::new_err(e.to_string()))
+        }
+    }
+
+    #[pyo3(name="display")]
+    /// display an element of the field
+    pub fn py_display(&self, n: BigUint) -> String {
+        self.display(&n)
+    }
+
+    /// string representation
+    fn __str__(&self) -> PyResult<String>   {
+        Ok(format!("{}", self))
+    }
+
+    /// representation for the repl
+    fn __repr__(&self) -> PyResult<String> {
+        Ok(format!("{}", self))
+    }
+}
+
+/// the multiplicative group of the field
+impl Group for BigGalloisField {
+    type Element = BigUint;
+
+    fn identity(&self) -> BigUint {
+        BigUint::one()
+    }
+
+    fn op(&self, a: &BigUint, b: &BigUint) -> BigUint {
+        self.mul(a, b)
+    }
+
+    fn inv(&self, a: &BigUint) -> BigUint {
+        self.inverse(a).expect("0 is not part of the multiplicative group")
+    }
+
+    fn order_bound(&self) -> BigUint {
+        &self.base - 1u8
+    }
+
+    fn exp(&self, a: &BigUint, k: &BigUint) -> BigUint {
+        self.pow( a, &(k % (&self.base - 1u8)))
+    }
+}
+
+impl std::fmt::Display for BigGalloisField {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "F_{}", self.base)
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+pub mod test {
+
+    use super::*;
+
+    use std::str::FromStr;
+
+    /// prime of the field behind P-256
+    const P256: &str = "115792089210356248762697446949407573530086143415290314195533631308867097853951";
+    /// prime of the field behind secp256k1
+    const SECP256K1: &str = "115792089237316195423570985008687907853269984665640564039457584007908834671663";
+
+    #[test]
+    fn test_big_gallois_sqrt() {
+        let field = BigGalloisField::new(BigUint::from(977u32), true, None);
+        assert_eq!(field.sqrt(&BigUint::from(269u32)).expect("function says there is no root but there is"),
+            (BigUint::from(313u32), BigUint::from(664u32)));
+        assert_eq!(field.sqrt(&BigUint::from(524u32)).expect("function says there is no root but there is"),
+            (BigUint::from(115u32), BigUint::from(862u32)));
+        assert!(field.sqrt(&BigUint::from(3u32)).is_err());
+        let field = BigGalloisField::new(BigUint::from(2u32), false, None);
+        assert_eq!(field.sqrt(&BigUint::one()).unwrap(), (BigUint::one(), BigUint::one()));
+
+        for p in [P256, SECP256K1] {
+            let field = BigGalloisField::new(BigUint::from_str(p).unwrap(), false, None);
+            let a = BigUint::from_str("1234567891011121314151617181920").unwrap();
+            let sq = field.mul(&a, &a);
+            let (w1, w2) = field.sqrt(&sq).expect("function says there is no root but there is");
+            assert!(w1 == a || w2 == a);
+            assert_eq!(field.add(&w1, &w2), BigUint::zero());
+        }
+    }
+
+    #[test]
+    fn test_big_gallois_inverse() {
+        let field = BigGalloisField::new(BigUint::from(31u32), true, None);
+        assert_eq!(field.inverse(&BigUint::from(12u32)).unwrap(), BigUint::from(13u32));
+        assert!(field.inverse(&BigUint::zero()).is_err());
+
+        let field = BigGalloisField::new(BigUint::from_str(P256).unwrap(), false, None);
+        let a = BigUint::from_str("987654321987654321987654321").unwrap();
+        let inv = field.inverse(&a).unwrap();
+        assert!(field.mul(&a, &inv).is_one());
+        assert_eq!(field.divide(&a, &a).unwrap(), BigUint::one());
+
+        // AES field: {53} * {ca} = {01}
+        let field = BigGalloisField::new(BigUint::from(256u32), true, None);
+        assert_eq!(field.inverse(&BigUint::from(0x53u32)).unwrap(), BigUint::from(0xcau32));
+    }
+
+    #[test]
+    fn test_big_gallois_arith() {
+        let small = GalloisField::new(977, false, None);
+        let field = BigGalloisField::from(small);
+        for i in [0u128, 1, 132, 976, 1232] {
+            for n in [132u128, 121, 424] {
+                assert_eq!(field.add(&BigUint::from(i), &BigUint::from(n)), BigUint::from(small.add(i, n)));
+                assert_eq!(field.sub(&BigUint::from(i), &BigUint::from(n)), BigUint::from(small.sub(i, n)));
+                assert_eq!(field.pow(&BigUint::from(i), &BigUint::from(n)), BigUint::from(small.pow(i, n)));
+            }
+        }
+        assert_eq!(field.reduce(-1), BigUint::from(976u32));
+
+        let field = BigGalloisField::new(BigUint::from(16u32), true, None);
+        assert_eq!(field.add(&BigUint::from(0b1111u32), &BigUint::from(0b1011u32)), BigUint::from(0b0100u32));
+        assert_eq!(field.sub(&BigUint::from(0b1000u32), &BigUint::from(0b111u32)), BigUint::from(0b1111u32));
+        // AES field: {57} * {83} = {c1}
+        let field = BigGalloisField::new(BigUint::from(256u32), true, None);
+        assert_eq!(field.mul(&BigUint::from(0x57u32), &BigUint::from(0x83u32)), BigUint::from(0xc1u32));
+    }
+
+    #[test]
+    fn test_big_display_c2() {
+        let f = BigGalloisField::new(BigUint::from(16u32), true, None);
+        assert_eq!(f.display(&BigUint::from(0b01u32)), String::from("1"));
+        assert_eq!(f.display(&BigUint::from(0b110u32)), String::from("α^2 + α^1"));
+        assert_eq!(f.display(&BigUint::from(0b1101u32)), String::from("α^3 + α^2 + 1"));
+        assert_eq!(f.calc_char(), BigUint::from(2u32));
+    }
+}
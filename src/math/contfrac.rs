@@ -0,0 +1,177 @@
+#![allow(dead_code)]
+/// continued fractions
+///
+/// A rational number a/b can be written as a finite continued fraction
+/// [a_0; a_1, ..., a_m] = a_0 + 1/(a_1 + 1/(... + 1/a_m)). The partial quotients a_i are the
+/// quotients of the euclidean algorithm. The convergents h_i/k_i = [a_0; a_1, ..., a_i] are the
+/// best rational approximations of a/b, they satisfy
+///
+/// h_i = a_i * h_(i-1) + h_(i-2), h_-1 = 1, h_-2 = 0
+/// k_i = a_i * k_(i-1) + k_(i-2), k_-1 = 0, k_-2 = 1
+///
+/// Author:     Christoph J. Scherr <software@cscherr.de>
+/// License:    MIT
+/// Source:     <https://git.cscherr.de/PlexSheep/plexcryptool/>
+
+use core::fmt;
+
+use num::Integer;
+use num_bigint::BigInt;
+use num_traits::{One, Zero, Signed};
+
+use pyo3::{prelude::*, exceptions::PyValueError};
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// errors for continued fractions
+pub enum ContFracError {
+    /// the denominator of the fraction is zero
+    ZeroDenominator,
+    /// the continued fraction has no partial quotients
+    Empty,
+}
+
+impl fmt::Display for ContFracError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ContFracError::ZeroDenominator => write!(f, "the denominator is zero"),
+            ContFracError::Empty => write!(f, "the continued fraction is empty"),
+        }
+    }
+}
+
+/// expand a/b into a continued fraction
+///
+/// returns the partial quotients [a_0; a_1, ..., a_m]. Uses floor division, so only a_0 can be
+/// negative.
+pub fn expand(a: &BigInt, b: &BigInt, verbose: bool) -> Result<Vec<BigInt>, ContFracError> {
+    if b.is_zero() {
+        return Err(ContFracError::ZeroDenominator);
+    }
+    // keep the denominator positive
+    let (mut a, mut b) = if b.is_negative() { (-a, -b) } else { (a.clone(), b.clone()) };
+    let mut quotients: Vec<BigInt> = Vec::new();
+    while !b.is_zero() {
+        let (q, r) = a.div_mod_floor(&b);
+        if verbose {
+            println!("{a} = {q} * {b} + {r}");
+        }
+        quotients.push(q);
+        a = b;
+        b = r;
+    }
+    if verbose {
+        println!("continued fraction: {}", display(&quotients));
+    }
+    Ok(quotients)
+}
+
+/// format partial quotients like [a_0; a_1, ..., a_m]
+pub fn display(quotients: &[BigInt]) -> String {
+    match quotients.split_first() {
+        None => String::from("[]"),
+        Some((first, rest)) => {
+            let rest: Vec<String> = rest.iter().map(|q| q.to_string()).collect();
+            if rest.is_empty() {
+                format!("[{first}]")
+            }
+            else {
+                format!("[{first}; {}]", rest.join(", "))
+            }
+        }
+    }
+}
+
+/// calculate all convergents h_i/k_i of a continued fraction
+pub fn convergents(quotients: &[BigInt]) -> Vec<(BigInt, BigInt)> {
+    let mut h = (BigInt::zero(), BigInt::one());
+    let mut k = (BigInt::one(), BigInt::zero());
+    let mut result: Vec<(BigInt, BigInt)> = Vec::with_capacity(quotients.len());
+    for q in quotients {
+        let hi = q * &h.1 + &h.0;
+        let ki = q * &k.1 + &k.0;
+        h = (h.1, hi.clone());
+        k = (k.1, ki.clone());
+        result.push((hi, ki));
+    }
+    result
+}
+
+/// reconstruct the fraction h/k from its partial quotients
+///
+/// the result is reduced and k is positive, unless a partial quotient was negative.
+pub fn reconstruct(quotients: &[BigInt]) -> Result<(BigInt, BigInt), ContFracError> {
+    match convergents(quotients).pop() {
+        Some(fraction) => Ok(fraction),
+        None => Err(ContFracError::Empty)
+    }
+}
+
+#[pyfunction]
+#[pyo3(name = "continued_fraction")]
+#[pyo3(signature=(a, b, verbose = false))]
+/// python wrapper for expand
+pub fn py_expand(a: BigInt, b: BigInt, verbose: bool) -> PyResult<Vec<BigInt>> {
+    match expand(&a, &b, verbose) {
+        Ok(q) => Ok(q),
+        Err(e) => Err(PyValueError::new_err(e.to_string()))
+    }
+}
+
+#[pyfunction]
+#[pyo3(name = "convergents")]
+/// python wrapper for convergents
+pub fn py_convergents(quotients: Vec<BigInt>) -> Vec<(BigInt, BigInt)> {
+    convergents(&quotients)
+}
+
+#[pyfunction]
+#[pyo3(name = "reconstruct")]
+/// python wrapper for reconstruct
+pub fn py_reconstruct(quotients: Vec<BigInt>) -> PyResult<(BigInt, BigInt)> {
+    match reconstruct(&quotients) {
+        Ok(f) => Ok(f),
+        Err(e) => Err(PyValueError::new_err(e.to_string()))
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+pub mod test {
+
+    use super::*;
+
+    fn big(n: i64) -> BigInt {
+        BigInt::from(n)
+    }
+
+    fn bigs(v: &[i64]) -> Vec<BigInt> {
+        v.iter().map(|n| big(*n)).collect()
+    }
+
+    #[test]
+    fn test_expand() {
+        assert_eq!(expand(&big(415), &big(93), true), Ok(bigs(&[4, 2, 6, 7])));
+        assert_eq!(expand(&big(-415), &big(93), false), Ok(bigs(&[-5, 1, 1, 6, 7])));
+        assert_eq!(expand(&big(415), &big(-93), false), Ok(bigs(&[-5, 1, 1, 6, 7])));
+        assert_eq!(expand(&big(17993), &big(90581), false), Ok(bigs(&[0, 5, 29, 4, 1, 3, 2, 4, 3])));
+        assert_eq!(expand(&big(7), &big(1), false), Ok(bigs(&[7])));
+        assert_eq!(expand(&big(7), &big(0), false), Err(ContFracError::ZeroDenominator));
+        assert_eq!(display(&bigs(&[4, 2, 6, 7])), "[4; 2, 6, 7]");
+    }
+
+    #[test]
+    fn test_convergents() {
+        assert_eq!(convergents(&bigs(&[4, 2, 6, 7])), vec![
+            (big(4), big(1)),
+            (big(9), big(2)),
+            (big(58), big(13)),
+            (big(415), big(93)),
+        ]);
+        assert_eq!(reconstruct(&bigs(&[-5, 1, 1, 6, 7])), Ok((big(-415), big(93))));
+        assert_eq!(reconstruct(&[]), Err(ContFracError::Empty));
+        // expanding and reconstructing gives the reduced fraction
+        assert_eq!(reconstruct(&expand(&big(1000), &big(250), false).unwrap()), Ok((big(4), big(1))));
+    }
+}
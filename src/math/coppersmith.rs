@@ -0,0 +1,473 @@
+#![allow(dead_code)]
+/// coppersmith's method for small roots
+///
+/// Finds all x with f(x) = 0 (mod N) and |x| <= X for a monic polynomial f of degree d, as long
+/// as X is smaller than about N^(1/d). This uses the formulation of Howgrave-Graham:
+///
+/// 1. build the polynomials g_(i,j)(x) = x^j * N^(m-i) * f(x)^i for 0 <= i < m, 0 <= j < d and
+///    h_j(x) = x^j * f(x)^m for 0 <= j < t. All of them have x_0 as root modulo N^m.
+/// 2. reduce the lattice of the coefficient vectors of g(xX) with [LLL](super::lll).
+/// 3. a short vector is a polynomial h with |h(x_0)| < N^m, so h(x_0) = 0 over the integers.
+///    Its integer roots are found exactly with bisection.
+///
+/// Polynomials are vectors of coefficients, starting with the constant term.
+///
+/// Author:     Christoph J. Scherr <software@cscherr.de>
+/// License:    MIT
+/// Source:     <https://git.cscherr.de/PlexSheep/plexcryptool/>
+
+use crate::cplex::printing::seperator;
+use crate::math::gcd::mod_inverse;
+use crate::math::lll::{lll, fraction, DEFAULT_DELTA, LllError};
+
+use core::fmt;
+
+use num::Integer;
+use num_bigint::BigInt;
+use num_traits::{One, Zero, Signed, ToPrimitive};
+
+use pyo3::{prelude::*, exceptions::PyValueError};
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// the largest m that is chosen automatically
+pub const MAX_M: usize = 8;
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// errors for coppersmith's method
+pub enum CoppersmithError {
+    /// the polynomial could not be parsed
+    Parse(String),
+    /// the polynomial is constant
+    Constant,
+    /// the leading coefficient is not invertible mod N, contains the gcd
+    NotMonic(BigInt),
+    /// the bound is too large for the degree, X must be smaller than N^(1/d)
+    BoundTooLarge,
+    /// the lattice reduction failed
+    Lll(LllError),
+}
+
+impl fmt::Display for CoppersmithError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CoppersmithError::Parse(e) => write!(f, "could not parse polynomial: {e}"),
+            CoppersmithError::Constant => write!(f, "the polynomial is constant"),
+            CoppersmithError::NotMonic(g) => write!(f, "the leading coefficient is not invertible, gcd with N is {g}"),
+            CoppersmithError::BoundTooLarge => write!(f, "the bound is too large, X must be smaller than N^(1/d)"),
+            CoppersmithError::Lll(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl From<LllError> for CoppersmithError {
+    fn from(e: LllError) -> Self {
+        CoppersmithError::Lll(e)
+    }
+}
+
+/// remove leading zero coefficients
+fn trim(mut f: Vec<BigInt>) -> Vec<BigInt> {
+    while f.len() > 1 && f.last().is_some_and(|c| c.is_zero()) {
+        f.pop();
+    }
+    f
+}
+
+/// degree of a polynomial, 0 for constants
+pub fn degree(f: &[BigInt]) -> usize {
+    f.iter().rposition(|c| !c.is_zero()).unwrap_or(0)
+}
+
+/// evaluate f(x) with horner's method
+pub fn eval(f: &[BigInt], x: &BigInt) -> BigInt {
+    f.iter().rev().fold(BigInt::zero(), |acc, c| acc * x + c)
+}
+
+/// multiply two polynomials
+pub fn mul(f: &[BigInt], g: &[BigInt]) -> Vec<BigInt> {
+    let mut result = vec![BigInt::zero(); f.len() + g.len() - 1];
+    for (i, a) in f.iter().enumerate() {
+        for (j, b) in g.iter().enumerate() {
+            result[i + j] += a * b;
+        }
+    }
+    trim(result)
+}
+
+/// derivative of a polynomial
+pub fn derivative(f: &[BigInt]) -> Vec<BigInt> {
+    if f.len() <= 1 {
+        return vec![BigInt::zero()];
+    }
+    f.iter().enumerate().skip(1).map(|(i, c)| c * i).collect()
+}
+
+/// format a polynomial like 3*x^2 - x + 5
+pub fn display(f: &[BigInt]) -> String {
+    let mut out = String::new();
+    for (i, c) in f.iter().enumerate().rev() {
+        if c.is_zero() {
+            continue;
+        }
+        let sign = if c.is_negative() { "-" } else { "+" };
+        if out.is_empty() {
+            if c.is_negative() {
+                out.push('-');
+            }
+        }
+        else {
+            out.push_str(&format!(" {sign} "));
+        }
+        let a = c.abs();
+        let monomial = match i {
+            0 => String::new(),
+            1 => String::from("x"),
+            _ => format!("x^{i}"),
+        };
+        if i == 0 {
+            out.push_str(&a.to_string());
+        }
+        else if a.is_one() {
+            out.push_str(&monomial);
+        }
+        else {
+            out.push_str(&format!("{a}*{monomial}"));
+        }
+    }
+    if out.is_empty() {
+        out.push('0');
+    }
+    out
+}
+
+/// parse a polynomial in x like "x^3 + 5*x - 7" or "2x^2-1"
+pub fn parse(s: &str) -> Result<Vec<BigInt>, CoppersmithError> {
+    let s: String = s.chars().filter(|c| !c.is_whitespace()).collect();
+    if s.is_empty() {
+        return Err(CoppersmithError::Parse(String::from("empty polynomial")));
+    }
+    let mut coefficients: Vec<BigInt> = Vec::new();
+    // split into terms, keeping the sign
+    let mut terms: Vec<String> = Vec::new();
+    for c in s.chars() {
+        if (c == '+' || c == '-') && terms.last().is_some_and(|t| !t.is_empty() && !t.ends_with('^')) {
+            terms.push(String::new());
+        }
+        if terms.is_empty() {
+            terms.push(String::new());
+        }
+        terms.last_mut().expect("there is a term").push(c);
+    }
+    for term in terms {
+        let (negative, body) = match term.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, term.strip_prefix('+').unwrap_or(&term))
+        };
+        let (coefficient, exponent) = match body.find('x') {
+            None => (body, 0usize),
+            Some(pos) => {
+                let c = body[..pos].trim_end_matches('*');
+                let rest = &body[pos + 1..];
+                let e = if rest.is_empty() {
+                    1
+                }
+                else {
+                    match rest.strip_prefix('^').map(|e| e.parse::<usize>()) {
+                        Some(Ok(e)) => e,
+                        _ => return Err(CoppersmithError::Parse(format!("invalid exponent in {term}")))
+                    }
+                };
+                (c, e)
+            }
+        };
+        let mut coefficient: BigInt = if coefficient.is_empty() {
+            BigInt::one()
+        }
+        else {
+            match coefficient.parse::<BigInt>() {
+                Ok(c) => c,
+                Err(_) => return Err(CoppersmithError::Parse(format!("invalid coefficient in {term}")))
+            }
+        };
+        if negative {
+            coefficient = -coefficient;
+        }
+        if coefficients.len() <= exponent {
+            coefficients.resize(exponent + 1, BigInt::zero());
+        }
+        coefficients[exponent] += coefficient;
+    }
+    Ok(trim(coefficients))
+}
+
+/// sign of f(x) as -1, 0 or 1
+fn sign_at(f: &[BigInt], x: &BigInt) -> i8 {
+    let v = eval(f, x);
+    if v.is_zero() { 0 } else if v.is_negative() { -1 } else { 1 }
+}
+
+/// find integers x in [lo, hi] so that f has a real root in [x, x + 1)
+///
+/// every real root in [lo, hi] is found, there may be additional candidates. Between two real
+/// roots of f' the polynomial is monotonic, so the roots of f can be found with bisection.
+fn root_floors(f: &[BigInt], lo: &BigInt, hi: &BigInt) -> Vec<BigInt> {
+    if degree(f) == 0 {
+        return Vec::new();
+    }
+    let critical = root_floors(&derivative(f), lo, hi);
+    let mut points: Vec<BigInt> = vec![lo.clone(), hi.clone()];
+    for c in &critical {
+        points.push(c.clone());
+        if c < hi {
+            points.push(c + 1u8);
+        }
+    }
+    points.sort();
+    points.dedup();
+    // the critical points themselves are candidates
+    let mut result: Vec<BigInt> = critical;
+    for w in points.windows(2) {
+        let (a, b) = (&w[0], &w[1]);
+        let sa = sign_at(f, a);
+        let sb = sign_at(f, b);
+        if sa == 0 {
+            result.push(a.clone());
+        }
+        if sb == 0 {
+            result.push(b.clone());
+        }
+        if sa * sb >= 0 {
+            continue;
+        }
+        let mut low = a.clone();
+        let mut high = b.clone();
+        while &high - &low > BigInt::one() {
+            let mid: BigInt = (&low + &high).div_floor(&BigInt::from(2));
+            let sm = sign_at(f, &mid);
+            if sm == 0 {
+                low = mid;
+                break;
+            }
+            if sm == sa {
+                low = mid;
+            }
+            else {
+                high = mid;
+            }
+        }
+        result.push(low);
+    }
+    result.sort();
+    result.dedup();
+    result
+}
+
+/// all integer roots x of f with |x| <= bound
+pub fn integer_roots(f: &[BigInt], bound: &BigInt) -> Vec<BigInt> {
+    let f = trim(f.to_vec());
+    if degree(&f) == 0 {
+        return Vec::new();
+    }
+    let lo = -bound;
+    root_floors(&f, &lo, bound).into_iter().filter(|x| eval(&f, x).is_zero()).collect()
+}
+
+/// choose m so that the lattice is large enough for X = N^(1/d - ε)
+fn choose_m(n: &BigInt, d: usize, bound: &BigInt) -> Result<usize, CoppersmithError> {
+    let log_n = n.bits() as f64;
+    let log_x = bound.bits() as f64;
+    let epsilon = 1.0 / d as f64 - log_x / log_n;
+    if epsilon <= 0.0 {
+        return Err(CoppersmithError::BoundTooLarge);
+    }
+    let m = (1.0 / (d as f64 * epsilon)).ceil();
+    Ok((m as usize).clamp(1, MAX_M))
+}
+
+/// find all small roots of f mod n
+///
+/// returns all x with f(x) = 0 (mod n) and |x| <= bound. m and t are the lattice parameters, an m
+/// of 0 chooses it from the bound. If no root is found, increasing m helps.
+pub fn small_roots(f: &[BigInt], n: &BigInt, bound: &BigInt, m: usize, t: usize, verbose: bool)
+    -> Result<Vec<BigInt>, CoppersmithError> {
+    let f = trim(f.iter().map(|c| c.mod_floor(n)).collect());
+    let d = degree(&f);
+    if d == 0 {
+        return Err(CoppersmithError::Constant);
+    }
+    // make f monic
+    let lead_inv = match mod_inverse(&f[d], n) {
+        Ok(inv) => inv,
+        Err(g) => return Err(CoppersmithError::NotMonic(g))
+    };
+    let f: Vec<BigInt> = f.iter().map(|c| (c * &lead_inv).mod_floor(n)).collect();
+    let m = if m == 0 { choose_m(n, d, bound)? } else { m };
+    let dim = d * m + t;
+    if verbose {
+        println!("f(x) = {} (mod {n})", display(&f));
+        println!("d = {d}, m = {m}, t = {t}, dimension = {dim}, X = {bound}");
+    }
+    // powers of f and N
+    let mut f_powers: Vec<Vec<BigInt>> = vec![vec![BigInt::one()]];
+    for i in 1..=m {
+        let next = mul(&f_powers[i - 1], &f);
+        f_powers.push(next);
+    }
+    let mut polynomials: Vec<Vec<BigInt>> = Vec::with_capacity(dim);
+    for (i, f_power) in f_powers.iter().take(m).enumerate() {
+        let n_power = n.pow((m - i) as u32);
+        for j in 0..d {
+            let mut g = vec![BigInt::zero(); j];
+            g.extend(f_power.iter().map(|c| c * &n_power));
+            polynomials.push(g);
+        }
+    }
+    for j in 0..t {
+        let mut h = vec![BigInt::zero(); j];
+        h.extend(f_powers[m].iter().cloned());
+        polynomials.push(h);
+    }
+    // coefficient vectors of g(xX)
+    let x_powers: Vec<BigInt> = (0..dim).map(|k| bound.pow(k as u32)).collect();
+    let basis: Vec<Vec<BigInt>> = polynomials.iter().map(|g| {
+        (0..dim).map(|k| g.get(k).map_or(BigInt::zero(), |c| c * &x_powers[k])).collect()
+    }).collect();
+    if verbose {
+        seperator();
+        println!("lattice polynomials:");
+        for (i, g) in polynomials.iter().enumerate() {
+            println!("g_{i}(x) = {}", display(g));
+        }
+        seperator();
+    }
+    let reduced = lll(&basis, &fraction(DEFAULT_DELTA.0, DEFAULT_DELTA.1), false)?;
+    let modulus = n.pow(m as u32);
+    let mut roots: Vec<BigInt> = Vec::new();
+    for (i, v) in reduced.iter().enumerate() {
+        // undo the substitution x -> xX
+        let h: Vec<BigInt> = v.iter().zip(&x_powers).map(|(c, xp)| c / xp).collect();
+        let h = trim(h);
+        let candidates = integer_roots(&h, bound);
+        if verbose {
+            println!("h_{i}(x) = {}", display(&h));
+            if !candidates.is_empty() {
+                println!("integer roots: {:?}", candidates.iter().map(|x| x.to_string()).collect::<Vec<String>>());
+            }
+        }
+        for x in candidates {
+            if eval(&f, &x).is_multiple_of(n) && !roots.contains(&x) {
+                roots.push(x);
+            }
+        }
+    }
+    roots.sort();
+    if verbose {
+        seperator();
+        println!("|h(x_0)| < N^m = {modulus} means h(x_0) = 0 over the integers");
+        for x in &roots {
+            println!("f({x}) = 0 (mod {n})");
+        }
+    }
+    Ok(roots)
+}
+
+/// estimate the largest bound that works for this degree, N^(1/d) / 2
+pub fn max_bound(n: &BigInt, d: usize) -> BigInt {
+    let d = d.to_u32().unwrap_or(u32::MAX).max(1);
+    n.nth_root(d) / 2u8
+}
+
+#[pyfunction]
+#[pyo3(name = "small_roots")]
+#[pyo3(signature=(coefficients, n, bound, m = 0, t = 0, verbose = false))]
+/// python wrapper for small_roots, the coefficients start with the constant term
+pub fn py_small_roots(coefficients: Vec<BigInt>, n: BigInt, bound: BigInt, m: usize, t: usize, verbose: bool)
+    -> PyResult<Vec<BigInt>> {
+    match small_roots(&coefficients, &n, &bound, m, t, verbose) {
+        Ok(r) => Ok(r),
+        Err(e) => Err(PyValueError::new_err(e.to_string()))
+    }
+}
+
+#[pyfunction]
+#[pyo3(name = "parse_polynomial")]
+/// python wrapper for parse
+pub fn py_parse(s: &str) -> PyResult<Vec<BigInt>> {
+    match parse(s) {
+        Ok(f) => Ok(f),
+        Err(e) => Err(PyValueError::new_err(e.to_string()))
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+pub mod test {
+
+    use super::*;
+    use crate::math::modexp::modular_exponentiation;
+
+    use std::str::FromStr;
+
+    fn bigs(v: &[i64]) -> Vec<BigInt> {
+        v.iter().map(|n| BigInt::from(*n)).collect()
+    }
+
+    #[test]
+    fn test_polynomials() {
+        assert_eq!(parse("x^3 + 5*x - 7"), Ok(bigs(&[-7, 5, 0, 1])));
+        assert_eq!(parse("-2x^2+x"), Ok(bigs(&[0, 1, -2])));
+        assert_eq!(parse("3 - x + x"), Ok(bigs(&[3])));
+        assert!(parse("x^").is_err());
+        assert!(parse("").is_err());
+        assert_eq!(display(&bigs(&[-7, 5, 0, 1])), "x^3 + 5*x - 7");
+        assert_eq!(display(&bigs(&[0, 1, -2])), "-2*x^2 + x");
+        assert_eq!(mul(&bigs(&[1, 1]), &bigs(&[-1, 1])), bigs(&[-1, 0, 1]));
+        assert_eq!(eval(&bigs(&[-7, 5, 0, 1]), &BigInt::from(2)), BigInt::from(11));
+    }
+
+    #[test]
+    fn test_integer_roots() {
+        // (x - 3)(x + 5)(x - 1000)
+        let f = mul(&mul(&bigs(&[-3, 1]), &bigs(&[5, 1])), &bigs(&[-1000, 1]));
+        assert_eq!(integer_roots(&f, &BigInt::from(10000)), bigs(&[-5, 3, 1000]));
+        assert_eq!(integer_roots(&f, &BigInt::from(10)), bigs(&[-5, 3]));
+        // (x - 2)² (x² + 1)
+        let f = mul(&mul(&bigs(&[-2, 1]), &bigs(&[-2, 1])), &bigs(&[1, 0, 1]));
+        assert_eq!(integer_roots(&f, &BigInt::from(100)), bigs(&[2]));
+        // 2x - 1 has no integer root
+        assert_eq!(integer_roots(&bigs(&[-1, 2]), &BigInt::from(100)), bigs(&[]));
+    }
+
+    #[test]
+    fn test_small_roots() {
+        // stereotyped message: m = prefix * 2^32 + x with e = 3, x is unknown
+        let n = BigInt::from_str("65883356559342216400988874739917500406422869368181727881499552988477158577859").unwrap();
+        let prefix: BigInt = BigInt::from_str("12345678901234567890").unwrap() << 32;
+        let x0 = BigInt::from(3141592653u64);
+        let c = modular_exponentiation(&prefix + &x0, BigInt::from(3), n.clone(), false);
+        // f(x) = (prefix + x)^3 - c
+        let g = vec![prefix.clone(), BigInt::one()];
+        let f: Vec<BigInt> = mul(&mul(&g, &g), &g);
+        let mut f = f;
+        f[0] -= &c;
+        let bound = BigInt::one() << 32;
+        assert_eq!(small_roots(&f, &n, &bound, 0, 0, true), Ok(vec![x0]));
+        assert_eq!(small_roots(&bigs(&[5]), &n, &bound, 0, 0, false), Err(CoppersmithError::Constant));
+        assert_eq!(small_roots(&f, &n, &(BigInt::one() << 100), 0, 0, false), Err(CoppersmithError::BoundTooLarge));
+    }
+
+    #[test]
+    fn test_small_roots_simple() {
+        // f(x) = x^2 + ax + b with a small root mod a 27 bit n
+        let n = BigInt::from(10001u64 * 10007u64);
+        let x0 = BigInt::from(42);
+        let a = BigInt::from(123456);
+        let b = (-(&x0 * &x0) - &a * &x0).mod_floor(&n);
+        let f = vec![b, a, BigInt::one()];
+        let roots = small_roots(&f, &n, &BigInt::from(100), 2, 1, false).unwrap();
+        assert!(roots.contains(&x0));
+    }
+}
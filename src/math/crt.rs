@@ -0,0 +1,194 @@
+#![allow(dead_code)]
+/// chinese remainder theorem
+///
+/// Solve systems of congruences x = a_i (mod m_i).
+///
+/// If the moduli are pairwise coprime, the solution is x = sum(a_i * M_i * y_i) mod M with
+/// M = prod(m_i), M_i = M / m_i and y_i = M_i^-1 (mod m_i).
+///
+/// Moduli that are not coprime are merged two at a time: x = a_1 (mod m_1) and x = a_2 (mod m_2)
+/// have a solution iff gcd(m_1, m_2) divides a_2 - a_1, the solution is then unique modulo
+/// lcm(m_1, m_2).
+///
+/// Author:     Christoph J. Scherr <software@cscherr.de>
+/// License:    MIT
+/// Source:     <https://git.cscherr.de/PlexSheep/plexcryptool/>
+
+use crate::cplex::printing::seperator;
+use crate::math::gcd::{egcd_big, mod_inverse};
+
+use core::fmt;
+
+use num::Integer;
+use num_bigint::BigInt;
+use num_traits::{One, Zero};
+
+use pyo3::{prelude::*, exceptions::PyValueError};
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// errors for the chinese remainder theorem
+pub enum CrtError {
+    /// there must be exactly one modulus per residue
+    LengthMismatch {
+        /// amount of residues
+        residues: usize,
+        /// amount of moduli
+        moduli: usize
+    },
+    /// the system has no congruences
+    Empty,
+    /// moduli must be positive
+    InvalidModulus(BigInt),
+    /// two congruences contradict each other
+    Inconsistent {
+        /// first modulus
+        m1: BigInt,
+        /// second modulus
+        m2: BigInt,
+        /// difference a_2 - a_1 of the residues
+        diff: BigInt
+    },
+}
+
+impl fmt::Display for CrtError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CrtError::LengthMismatch { residues, moduli } => {
+                write!(f, "got {residues} residues but {moduli} moduli")
+            }
+            CrtError::Empty => write!(f, "no congruences given"),
+            CrtError::InvalidModulus(m) => write!(f, "invalid modulus: {m}"),
+            CrtError::Inconsistent { m1, m2, diff } => {
+                write!(f, "inconsistent congruences, gcd({m1}, {m2}) does not divide a_2 - a_1 = {diff}")
+            }
+        }
+    }
+}
+
+/// merge x = a1 (mod m1) and x = a2 (mod m2) into x = a (mod lcm(m1, m2))
+///
+/// works for moduli that are not coprime
+pub fn crt_pair(a1: &BigInt, m1: &BigInt, a2: &BigInt, m2: &BigInt, verbose: bool)
+    -> Result<(BigInt, BigInt), CrtError> {
+    let (g, u, _) = egcd_big(m1, m2);
+    let diff: BigInt = a2 - a1;
+    if !diff.is_multiple_of(&g) {
+        if verbose {
+            println!("gcd({m1}, {m2}) = {g} does not divide {a2} - {a1} = {diff}");
+        }
+        return Err(CrtError::Inconsistent { m1: m1.clone(), m2: m2.clone(), diff });
+    }
+    let lcm: BigInt = m1 / &g * m2;
+    // m1 * u = g (mod m2), so a1 + m1 * u * diff / g = a2 (mod m2)
+    let x = (a1 + m1 * u * (diff / &g)).mod_floor(&lcm);
+    if verbose {
+        println!("x = {a1} (mod {m1}), x = {a2} (mod {m2}): g = {g}, lcm = {lcm}, x = {x} (mod {lcm})");
+    }
+    Ok((x, lcm))
+}
+
+/// solve the system x = a_i (mod m_i)
+///
+/// returns (x, M) with 0 <= x < M, where M is the lcm of all moduli. Every solution of the system
+/// is congruent to x modulo M.
+pub fn crt(residues: &[BigInt], moduli: &[BigInt], verbose: bool) -> Result<(BigInt, BigInt), CrtError> {
+    if residues.len() != moduli.len() {
+        return Err(CrtError::LengthMismatch { residues: residues.len(), moduli: moduli.len() });
+    }
+    if moduli.is_empty() {
+        return Err(CrtError::Empty);
+    }
+    if let Some(m) = moduli.iter().find(|m| **m < BigInt::one()) {
+        return Err(CrtError::InvalidModulus(m.clone()));
+    }
+    let coprime = moduli.iter().enumerate().all(|(i, mi)| {
+        moduli[i + 1..].iter().all(|mj| mi.gcd(mj).is_one())
+    });
+    if verbose {
+        seperator();
+        for (a, m) in residues.iter().zip(moduli) {
+            println!("x = {a} (mod {m})");
+        }
+        seperator();
+    }
+    if !coprime {
+        if verbose {
+            println!("moduli are not pairwise coprime, merging congruences");
+        }
+        let mut x = residues[0].mod_floor(&moduli[0]);
+        let mut modulus = moduli[0].clone();
+        for (a, m) in residues.iter().zip(moduli).skip(1) {
+            (x, modulus) = crt_pair(&x, &modulus, &a.mod_floor(m), m, verbose)?;
+        }
+        return Ok((x, modulus));
+    }
+    let big_m: BigInt = moduli.iter().product();
+    if verbose {
+        println!("M = {big_m}");
+    }
+    let mut x = BigInt::zero();
+    for (a, m) in residues.iter().zip(moduli) {
+        let mi: BigInt = &big_m / m;
+        let yi = mod_inverse(&mi, m).expect("moduli are coprime, so M_i is invertible");
+        let term = a * &mi * &yi;
+        if verbose {
+            println!("m_i = {m}\tM_i = {mi}\ty_i = M_i^-1 = {yi} (mod {m})\ta_i * M_i * y_i = {term}");
+        }
+        x += term;
+    }
+    x = x.mod_floor(&big_m);
+    if verbose {
+        println!("x = sum(a_i * M_i * y_i) = {x} (mod {big_m})");
+    }
+    Ok((x, big_m))
+}
+
+#[pyfunction]
+#[pyo3(name = "crt")]
+#[pyo3(signature=(residues, moduli, verbose = false))]
+/// python wrapper for crt
+pub fn py_crt(residues: Vec<BigInt>, moduli: Vec<BigInt>, verbose: bool) -> PyResult<(BigInt, BigInt)> {
+    match crt(&residues, &moduli, verbose) {
+        Ok(v) => Ok(v),
+        Err(e) => Err(PyValueError::new_err(e.to_string()))
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+pub mod test {
+
+    use super::*;
+
+    fn big(v: &[i64]) -> Vec<BigInt> {
+        v.iter().map(|x| BigInt::from(*x)).collect()
+    }
+
+    #[test]
+    fn test_crt_coprime() {
+        assert_eq!(crt(&big(&[2, 3, 2]), &big(&[3, 5, 7]), true), Ok((BigInt::from(23), BigInt::from(105))));
+        assert_eq!(crt(&big(&[-1, 12]), &big(&[4, 9]), false), Ok((BigInt::from(3), BigInt::from(36))));
+        let p = BigInt::from(1000003);
+        let q = BigInt::from(1000033);
+        let x = BigInt::from(123456789123u64);
+        assert_eq!(crt(&[&x % &p, &x % &q], &[p.clone(), q.clone()], false), Ok((x, p * q)));
+    }
+
+    #[test]
+    fn test_crt_not_coprime() {
+        assert_eq!(crt(&big(&[3, 5]), &big(&[4, 6]), true), Ok((BigInt::from(11), BigInt::from(12))));
+        assert_eq!(crt(&big(&[1, 7, 7]), &big(&[6, 10, 15]), false), Ok((BigInt::from(7), BigInt::from(30))));
+        assert_eq!(crt(&big(&[1, 2]), &big(&[4, 6]), false), Err(CrtError::Inconsistent {
+            m1: BigInt::from(4), m2: BigInt::from(6), diff: BigInt::from(1)
+        }));
+    }
+
+    #[test]
+    fn test_crt_errors() {
+        assert_eq!(crt(&big(&[1]), &big(&[3, 5]), false), Err(CrtError::LengthMismatch { residues: 1, moduli: 2 }));
+        assert_eq!(crt(&[], &[], false), Err(CrtError::Empty));
+        assert_eq!(crt(&big(&[1]), &big(&[0]), false), Err(CrtError::InvalidModulus(BigInt::zero())));
+    }
+}
@@ -0,0 +1,589 @@
+#![allow(dead_code)]
+/// discrete logarithms
+///
+/// Find x with g^x = h in a cyclic group. The algorithms work for any [Group], like the
+/// multiplicative group of a [GalloisField](super::gallois::GalloisField), including GF(2^k),
+/// or the multiplicative group of a prime field with big integers, see
+/// [BigGalloisField](super::biggallois::BigGalloisField).
+///
+/// Baby-step giant-step: with m = ceil(sqrt(n)), where n is the order of g, every x < n can be
+/// written as x = i*m + j with 0 <= i, j < m. The baby steps g^j are stored in a table, then the
+/// giant steps h * (g^-m)^i are calculated until one of them is in the table.
+///
+/// Pohlig-Hellman: if the order n of g factors as n = prod(q_i^e_i), x mod q_i^e_i can be
+/// calculated in the subgroup of order q_i^e_i generated by g^(n/q_i^e_i). x mod q^e is found one
+/// q-adic digit at a time, each digit is a logarithm in the subgroup of order q. The results are
+/// combined with the [chinese remainder theorem](super::crt).
+///
+/// Pollard rho: a pseudo random walk x_i = g^a_i * h^b_i, where the next step depends on which of
+/// three partitions x_i is in, eventually runs into a cycle. Brent's cycle detection finds two
+/// equal elements g^a_1 * h^b_1 = g^a_2 * h^b_2, so x * (b_1 - b_2) = a_2 - a_1 (mod n).
+///
+/// Pollard lambda (kangaroo): if x is known to be in an interval [a, b], a tame kangaroo starts at
+/// g^b and jumps a fixed amount of times, then sets a trap. A wild kangaroo starts at h and jumps
+/// by the same rules. Once it lands on any place the tame kangaroo visited, it follows its path
+/// into the trap, and x can be calculated from the distances both have travelled.
+///
+/// Rho and kangaroo only need constant memory, unlike baby-step giant-step.
+///
+/// Author:     Christoph J. Scherr <software@cscherr.de>
+/// License:    MIT
+/// Source:     <https://git.cscherr.de/PlexSheep/plexcryptool/>
+
+use crate::cplex::printing::seperator;
+use crate::math::group::Group;
+use crate::math::factorise::pollard_rho_factors;
+use crate::math::crt::crt;
+use crate::math::gcd::mod_inverse;
+
+use core::fmt;
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use num::Integer;
+use num_bigint::{BigInt, BigUint, RandBigInt};
+use num_traits::{One, ToPrimitive, Zero};
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// maximum amount of baby steps, so that the table still fits into memory
+pub const MAX_BABY_STEPS: u64 = 1 << 24;
+/// how often rho and kangaroo restart with other parameters before giving up
+pub const MAX_ATTEMPTS: u64 = 16;
+/// maximum amount of candidates tested when a rho collision has multiple solutions
+const MAX_RHO_CANDIDATES: u64 = 1 << 16;
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// errors for discrete logarithms
+pub enum DlogError {
+    /// h is not a power of g
+    NoSolution,
+    /// the order of g is zero, so there is nothing to search
+    InvalidOrder(BigUint),
+    /// the baby step table would not fit into memory
+    TooLarge(BigUint),
+    /// index calculus could not find enough smooth relations
+    NotEnoughRelations(usize),
+    /// index calculus needs a prime modulus
+    NotPrime(BigInt),
+    /// the interval for the kangaroo algorithm is empty
+    InvalidInterval {
+        /// lower bound
+        a: BigUint,
+        /// upper bound
+        b: BigUint
+    },
+}
+
+impl fmt::Display for DlogError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DlogError::NoSolution => write!(f, "h is not a power of g"),
+            DlogError::InvalidOrder(n) => write!(f, "invalid order: {n}"),
+            DlogError::TooLarge(n) => write!(f, "the order {n} is too large for baby-step giant-step"),
+            DlogError::NotEnoughRelations(n) => write!(f, "not enough relations, found {n}"),
+            DlogError::NotPrime(p) => write!(f, "{p} is not prime"),
+            DlogError::InvalidInterval { a, b } => write!(f, "invalid interval: [{a}, {b}]"),
+        }
+    }
+}
+
+/// baby-step giant-step
+///
+/// finds the smallest x with g^x = h, where order is the order of g or an upper bound for it.
+pub fn bsgs<G: Group>(group: &G, g: &G::Element, h: &G::Element, order: &BigUint, verbose: bool)
+    -> Result<BigUint, DlogError> {
+    if order.is_zero() {
+        return Err(DlogError::InvalidOrder(order.clone()));
+    }
+    let mut m: u64 = match order.sqrt().to_u64() {
+        Some(m) if m < MAX_BABY_STEPS => m,
+        _ => return Err(DlogError::TooLarge(order.clone()))
+    };
+    if BigUint::from(m) * m < *order {
+        m += 1;
+    }
+    if verbose {
+        seperator();
+        println!("solving {g}^x = {h}, n = {order}, m = ceil(sqrt(n)) = {m}");
+        seperator();
+        println!("baby steps:\nj\tg^j");
+    }
+    let mut table: HashMap<G::Element, u64> = HashMap::new();
+    let mut e = group.identity();
+    for j in 0..m {
+        if verbose {
+            println!("{j}\t{e}");
+        }
+        // keep the smallest j for each element
+        table.entry(e.clone()).or_insert(j);
+        e = group.op(&e, g);
+    }
+    let factor = group.inv(&group.exp(g, &BigUint::from(m)));
+    if verbose {
+        seperator();
+        println!("g^-m = {factor}");
+        println!("giant steps:\ni\th * g^(-im)");
+    }
+    let mut gamma = h.clone();
+    for i in 0..m {
+        if let Some(j) = table.get(&gamma) {
+            let x = BigUint::from(i) * m + j;
+            if verbose {
+                println!("{i}\t{gamma} = g^{j}");
+                seperator();
+                println!("x = i*m + j = {i}*{m} + {j} = {x}");
+            }
+            return Ok(x);
+        }
+        if verbose {
+            println!("{i}\t{gamma}");
+        }
+        gamma = group.op(&gamma, &factor);
+    }
+    if verbose {
+        seperator();
+        println!("no match, h is not a power of g");
+    }
+    Err(DlogError::NoSolution)
+}
+
+/// baby-step giant-step with the order of the group as bound
+pub fn dlog<G: Group>(group: &G, g: &G::Element, h: &G::Element, verbose: bool) -> Result<BigUint, DlogError> {
+    bsgs(group, g, h, &group.order_bound(), verbose)
+}
+
+/// solve g^x = h in a subgroup of prime power order q^e
+///
+/// g must have order exactly q^e, otherwise γ below is 1 and no digit can be found.
+///
+/// finds the q-adic digits x = d_0 + d_1*q + ... + d_(e-1)*q^(e-1) one after another:
+/// (g^-x_k * h)^(q^(e-1-k)) = (g^(q^(e-1)))^d_k, where x_k is the part of x that is already known
+pub fn pohlig_hellman_prime_power<G: Group>(group: &G, g: &G::Element, h: &G::Element, q: &BigUint,
+    e: u32, verbose: bool) -> Result<BigUint, DlogError> {
+    let gamma = group.exp(g, &q.pow(e - 1));
+    if verbose {
+        println!("γ = g^(q^(e-1)) = {g}^({q}^{}) = {gamma}", e - 1);
+    }
+    let mut x = BigUint::zero();
+    let mut qk = BigUint::one();
+    for k in 0..e {
+        let hk = group.exp(&group.op(&group.inv(&group.exp(g, &x)), h), &q.pow(e - 1 - k));
+        let dk = bsgs(group, &gamma, &hk, q, false)?;
+        if verbose {
+            println!("h_{k} = (g^-{x} * h)^(q^{}) = {hk} = γ^{dk}	=> d_{k} = {dk}", e - 1 - k);
+        }
+        x += &dk * &qk;
+        qk *= q;
+    }
+    Ok(x)
+}
+
+/// Pohlig-Hellman
+///
+/// finds x with g^x = h, where order is the order of g or a multiple of it, like the order of
+/// the group. The order is factorised with [pollard_rho_factors].
+pub fn pohlig_hellman<G: Group>(group: &G, g: &G::Element, h: &G::Element, order: &BigUint,
+    verbose: bool) -> Result<BigUint, DlogError> {
+    if order.is_zero() {
+        return Err(DlogError::InvalidOrder(order.clone()));
+    }
+    let factors = pollard_rho_factors(&BigInt::from(order.clone()), false);
+    if verbose {
+        seperator();
+        let parts: Vec<String> = factors.iter().map(|(q, e)| format!("{q}^{e}")).collect();
+        println!("solving {g}^x = {h}, n = {order} = {}", parts.join(" * "));
+    }
+    let mut residues: Vec<BigInt> = Vec::new();
+    let mut moduli: Vec<BigInt> = Vec::new();
+    for (q, e) in factors {
+        let q = q.to_biguint().expect("prime factor is negative");
+        let qe = q.pow(e);
+        let cofactor = order / &qe;
+        let gi = group.exp(g, &cofactor);
+        let hi = group.exp(h, &cofactor);
+        if verbose {
+            seperator();
+            println!("q^e = {q}^{e} = {qe}");
+            println!("g_i = g^(n/q^e) = {gi}\th_i = h^(n/q^e) = {hi}");
+        }
+        // n is only a multiple of the order of g, g_i might have order q^f with f < e
+        let mut f = e;
+        while f > 0 && group.exp(&gi, &q.pow(f - 1)) == group.identity() {
+            f -= 1;
+        }
+        if f == 0 {
+            if verbose {
+                println!("g_i = 1, q does not divide the order of g");
+            }
+            continue;
+        }
+        let qf = q.pow(f);
+        if verbose && f < e {
+            println!("g_i has order q^{f} = {qf}");
+        }
+        let xi = pohlig_hellman_prime_power(group, &gi, &hi, &q, f, verbose)?;
+        if verbose {
+            println!("x = {xi} (mod {qf})");
+        }
+        residues.push(BigInt::from(xi));
+        moduli.push(BigInt::from(qf));
+    }
+    // g = 1 leaves no congruences at all
+    let x = if moduli.is_empty() {
+        BigUint::zero()
+    } else {
+        let (x, _) = crt(&residues, &moduli, verbose).expect("prime powers are coprime");
+        x.to_biguint().expect("crt result is negative")
+    };
+    // h might not be in the subgroup generated by g
+    if group.exp(g, &x) != *h {
+        if verbose {
+            println!("g^{x} != h, h is not a power of g");
+        }
+        return Err(DlogError::NoSolution);
+    }
+    if verbose {
+        seperator();
+        println!("x = {x}");
+    }
+    Ok(x)
+}
+
+/// pseudo random but deterministic choice of a partition for an element
+fn partition<E: Hash>(x: &E, salt: u64, parts: u64) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    salt.hash(&mut hasher);
+    x.hash(&mut hasher);
+    hasher.finish() % parts
+}
+
+/// one step of the rho walk, keeps x = g^a * h^b
+fn rho_step<G: Group>(group: &G, g: &G::Element, h: &G::Element, n: &BigUint, salt: u64,
+    (x, a, b): (G::Element, BigUint, BigUint)) -> (G::Element, BigUint, BigUint) {
+    match partition(&x, salt, 3) {
+        0 => (group.op(&x, g), (a + 1u8) % n, b),
+        1 => (group.op(&x, &x), (a * 2u8) % n, (b * 2u8) % n),
+        _ => (group.op(&x, h), a, (b + 1u8) % n),
+    }
+}
+
+/// Pollard's rho for logarithms
+///
+/// finds x with g^x = h, where order is the order of g or a multiple of it. Works best if the
+/// order is prime, use [pohlig_hellman] otherwise. A collision that does not give x restarts the
+/// walk from a new random point with a new partition, up to [MAX_ATTEMPTS] times.
+pub fn pollard_rho_dlog<G: Group>(group: &G, g: &G::Element, h: &G::Element, order: &BigUint,
+    verbose: bool) -> Result<BigUint, DlogError> {
+    if order.is_zero() {
+        return Err(DlogError::InvalidOrder(order.clone()));
+    }
+    if order.is_one() {
+        return Ok(BigUint::zero());
+    }
+    let mut rng = rand::thread_rng();
+    for salt in 0..MAX_ATTEMPTS {
+        let a0 = rng.gen_biguint_below(order);
+        let b0 = rng.gen_biguint_below(order);
+        let x0 = group.op(&group.exp(g, &a0), &group.exp(h, &b0));
+        if verbose {
+            seperator();
+            println!("attempt {salt}: x_0 = g^{a0} * h^{b0} = {x0}");
+        }
+        // brent: the tortoise waits at powers of two for the hare
+        let mut tortoise = (x0.clone(), a0.clone(), b0.clone());
+        let mut hare = rho_step(group, g, h, order, salt, (x0, a0, b0));
+        let mut power: u64 = 1;
+        let mut lam: u64 = 1;
+        let mut steps: u64 = 1;
+        while tortoise.0 != hare.0 {
+            if power == lam {
+                tortoise = hare.clone();
+                power *= 2;
+                lam = 0;
+                if verbose {
+                    println!("i = {steps}	x_i = {}	a_i = {}	b_i = {}", hare.0, hare.1, hare.2);
+                }
+            }
+            hare = rho_step(group, g, h, order, salt, hare);
+            lam += 1;
+            steps += 1;
+        }
+        let (_, a1, b1) = tortoise;
+        let (x, a2, b2) = hare;
+        if verbose {
+            println!("collision after {steps} steps: {x} = g^{a1} * h^{b1} = g^{a2} * h^{b2}");
+        }
+        // x * (b1 - b2) = a2 - a1 (mod n)
+        let n = BigInt::from(order.clone());
+        let r = (BigInt::from(b1) - BigInt::from(b2)).mod_floor(&n);
+        let t = (BigInt::from(a2) - BigInt::from(a1)).mod_floor(&n);
+        let d = r.gcd(&n);
+        if r.is_zero() || !t.is_multiple_of(&d) || d > BigInt::from(MAX_RHO_CANDIDATES) {
+            if verbose {
+                println!("x * {r} = {t} (mod {n}) can not be solved, restarting");
+            }
+            continue;
+        }
+        let nd: BigInt = &n / &d;
+        let inv = mod_inverse(&(&r / &d), &nd).expect("r / d and n / d are coprime");
+        let mut x = ((&t / &d) * inv).mod_floor(&nd);
+        if verbose {
+            println!("x * {r} = {t} (mod {n}), gcd = {d}, x = {x} (mod {nd})");
+        }
+        for _ in 0..d.to_u64().expect("too many candidates") {
+            let candidate = x.to_biguint().expect("x is negative");
+            if group.exp(g, &candidate) == *h {
+                if verbose {
+                    seperator();
+                    println!("x = {candidate}");
+                }
+                return Ok(candidate);
+            }
+            x += &nd;
+        }
+        // with a multiple of the order of g, the congruence only holds modulo the real order
+        if verbose {
+            println!("no candidate fits, restarting with a new partition");
+        }
+    }
+    if verbose {
+        println!("giving up after {MAX_ATTEMPTS} attempts, h is probably not a power of g");
+    }
+    Err(DlogError::NoSolution)
+}
+
+/// Pollard's lambda (kangaroo) method
+///
+/// finds x in [a, b] with g^x = h. An interval wider than [Group::order_bound] is clamped to
+/// [a, a + n - 1], which still holds a solution if there is one. If the wild kangaroo went around
+/// the group, x is reduced modulo the order bound, which works when it is a multiple of the order
+/// of g, like in a field. Otherwise the attempt is restarted.
+pub fn kangaroo<G: Group>(group: &G, g: &G::Element, h: &G::Element, a: &BigUint, b: &BigUint,
+    verbose: bool) -> Result<BigUint, DlogError> {
+    if b < a {
+        return Err(DlogError::InvalidInterval { a: a.clone(), b: b.clone() });
+    }
+    let bound = group.order_bound();
+    let b: &BigUint = &if !bound.is_zero() && b - a >= bound {
+        a + &bound - 1u8
+    } else {
+        b.clone()
+    };
+    let width: BigUint = b - a;
+    let root = width.sqrt();
+    // jumps are powers of two with a mean of about sqrt(b - a) / 2
+    let mut k: u32 = 1;
+    while (BigUint::one() << k) / k < &root / 2u8 + 1u8 {
+        k += 1;
+    }
+    let distances: Vec<BigUint> = (0..k).map(|i| BigUint::one() << i).collect();
+    let jumps: Vec<G::Element> = distances.iter().map(|s| group.exp(g, s)).collect();
+    let tame_steps: BigUint = &root * 2u8 + 1u8;
+    if verbose {
+        seperator();
+        println!("solving {g}^x = {h} with x in [{a}, {b}]");
+        println!("jumps: g^s with s in {:?}", distances.iter().map(|s| s.to_string()).collect::<Vec<String>>());
+    }
+    for salt in 0..MAX_ATTEMPTS {
+        // the tame kangaroo starts at the upper bound
+        let mut tame = group.exp(g, b);
+        let mut tame_distance = BigUint::zero();
+        let mut step = BigUint::zero();
+        while step < tame_steps {
+            let i = partition(&tame, salt, k as u64) as usize;
+            tame = group.op(&tame, &jumps[i]);
+            tame_distance += &distances[i];
+            step += 1u8;
+        }
+        if verbose {
+            seperator();
+            println!("attempt {salt}: tame kangaroo set a trap at g^(b + {tame_distance}) = {tame}");
+        }
+        let mut wild = h.clone();
+        let mut wild_distance = BigUint::zero();
+        let limit: BigUint = &width + &tame_distance;
+        while wild_distance <= limit {
+            if wild == tame {
+                let mut x: BigUint = b + &tame_distance - &wild_distance;
+                if verbose {
+                    println!("wild kangaroo fell into the trap after a distance of {wild_distance}");
+                    println!("x = b + d_tame - d_wild = {b} + {tame_distance} - {wild_distance} = {x}");
+                }
+                if x > *b {
+                    x = a + (&x - a) % &bound;
+                    if verbose {
+                        println!("x is above b, reduced modulo {bound}: x = {x}");
+                    }
+                }
+                if x <= *b && group.exp(g, &x) == *h {
+                    return Ok(x);
+                }
+                if verbose {
+                    println!("x is not in [{a}, {b}], restarting");
+                }
+                break;
+            }
+            let i = partition(&wild, salt, k as u64) as usize;
+            wild = group.op(&wild, &jumps[i]);
+            wild_distance += &distances[i];
+        }
+        if verbose {
+            println!("wild kangaroo passed the trap, restarting");
+        }
+    }
+    Err(DlogError::NoSolution)
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+pub mod test {
+
+    use super::*;
+
+    use crate::math::gallois::GalloisField;
+    use crate::math::biggallois::BigGalloisField;
+    use crate::math::ecc::EllipticCurve;
+
+    use std::str::FromStr;
+
+    #[test]
+    fn test_bsgs_prime_field() {
+        let field = GalloisField::new(1019, false, None);
+        // 2 is a generator of F_1019*
+        for x in [0u128, 1, 17, 500, 1017] {
+            let h = field.pow(2, x);
+            assert_eq!(dlog(&field, &2, &h, x == 17), Ok(BigUint::from(x)));
+        }
+        // 4 is a square, 2 is not
+        assert_eq!(dlog(&field, &4, &2, false), Err(DlogError::NoSolution));
+    }
+
+    #[test]
+    fn test_bsgs_c2() {
+        // x is a generator of the AES field
+        let field = GalloisField::new(256, false, None);
+        assert_eq!(field.mul(0x57, 0x83), 0xc1);
+        let h = field.exp(&0x03, &BigUint::from(200u32));
+        assert_eq!(dlog(&field, &0x03, &h, true), Ok(BigUint::from(200u32)));
+
+        let field = GalloisField::new(16, false, None);
+        let h = field.exp(&0b10, &BigUint::from(11u32));
+        assert_eq!(dlog(&field, &0b10, &h, false), Ok(BigUint::from(11u32)));
+    }
+
+    #[test]
+    fn test_pohlig_hellman() {
+        // 8101 - 1 = 2^2 * 3^4 * 5^2, 6 is a generator
+        let field = GalloisField::new(8101, false, None);
+        let h = field.pow(6, 7531);
+        assert_eq!(pohlig_hellman(&field, &6, &h, &field.order_bound(), true), Ok(BigUint::from(7531u32)));
+        assert_eq!(pohlig_hellman(&field, &6, &1, &field.order_bound(), false), Ok(BigUint::zero()));
+        // 36 generates the subgroup of squares, 6 is not a square
+        assert_eq!(pohlig_hellman(&field, &36, &6, &field.order_bound(), false), Err(DlogError::NoSolution));
+        // 36 has order 4050, only a multiple of it is given
+        let h = field.pow(36, 5);
+        assert_eq!(pohlig_hellman(&field, &36, &h, &field.order_bound(), true), Ok(BigUint::from(5u32)));
+        assert_eq!(pohlig_hellman(&field, &8100, &8100, &field.order_bound(), false), Ok(BigUint::one()));
+        assert_eq!(pohlig_hellman(&field, &1, &1, &field.order_bound(), false), Ok(BigUint::zero()));
+        assert_eq!(pohlig_hellman(&field, &1, &6, &field.order_bound(), false), Err(DlogError::NoSolution));
+
+        let field = GalloisField::new(256, false, None);
+        let h = field.exp(&0x03, &BigUint::from(123u32));
+        assert_eq!(pohlig_hellman(&field, &0x03, &h, &field.order_bound(), false), Ok(BigUint::from(123u32)));
+
+        // 2^61 - 1, p - 1 = 2 * 3^2 * 5^2 * 7 * 11 * 13 * 31 * 41 * 61 * 151 * 331 * 1321
+        let p = BigUint::from_str("2305843009213693951").unwrap();
+        let field = BigGalloisField::new(p, false, None);
+        let g = BigUint::from(37u32);
+        let h = field.pow(&g, &BigUint::from_str("1234567890123456789").unwrap());
+        let x = pohlig_hellman(&field, &g, &h, &field.order_bound(), false).unwrap();
+        assert_eq!(field.pow(&g, &x), h);
+    }
+
+    #[test]
+    fn test_pohlig_hellman_ecc() {
+        // the curve has 28 = 2^2 * 7 points, (3, 10) is a generator
+        let f = GalloisField::new(23, false, None);
+        let ec = EllipticCurve::new(f, 1, 1, false).expect("ec cant be created");
+        let g = ec.new_point(3, 10).unwrap();
+        for t in 1..28u32 {
+            let h = ec.exp(&g, &BigUint::from(t));
+            assert_eq!(pohlig_hellman(&ec, &g, &h, &BigUint::from(28u32), t == 11), Ok(BigUint::from(t)));
+        }
+        assert_eq!(ec.exp(&g, &BigUint::from(28u32)), ec.identity());
+        assert_eq!(ec.exp(&g, &BigUint::from(3u32)), ec.mul(g, 3u32).unwrap());
+        assert_eq!(bsgs(&ec, &g, &ec.exp(&g, &BigUint::from(20u32)), &ec.order_bound(), false), Ok(BigUint::from(20u32)));
+    }
+
+    #[test]
+    fn test_pollard_rho_dlog() {
+        // p = 2q + 1 with q prime, 4 generates the subgroup of order q
+        let q = 1073741789u128;
+        let field = GalloisField::new(2 * q + 1, false, None);
+        assert_eq!(field.pow(4, 123456789), 436533508);
+        assert_eq!(pollard_rho_dlog(&field, &4, &436533508, &BigUint::from(q), true), Ok(BigUint::from(123456789u32)));
+
+        // composite order
+        let field = GalloisField::new(8101, false, None);
+        let h = field.pow(6, 4321);
+        assert_eq!(pollard_rho_dlog(&field, &6, &h, &field.order_bound(), false), Ok(BigUint::from(4321u32)));
+        assert_eq!(pollard_rho_dlog(&field, &36, &6, &BigUint::from(4050u32), false), Err(DlogError::NoSolution));
+        // 36 has order 4050, only a multiple of it is given
+        let h = field.pow(36, 5);
+        let x = pollard_rho_dlog(&field, &36, &h, &field.order_bound(), false).unwrap();
+        assert_eq!(field.pow(36, x.to_u128().unwrap()), h);
+
+        let f = GalloisField::new(23, false, None);
+        let ec = EllipticCurve::new(f, 1, 1, false).expect("ec cant be created");
+        let g = ec.new_point(3, 10).unwrap();
+        let h = ec.mul(g, 19u32).unwrap();
+        assert_eq!(pollard_rho_dlog(&ec, &g, &h, &BigUint::from(28u32), false), Ok(BigUint::from(19u32)));
+    }
+
+    #[test]
+    fn test_kangaroo() {
+        let q = 1073741789u128;
+        let field = GalloisField::new(2 * q + 1, false, None);
+        let x = 123456789u32;
+        let h = field.pow(4, x as u128);
+        let a = BigUint::from(x - 5000000);
+        let b = BigUint::from(x + 3000000);
+        assert_eq!(kangaroo(&field, &4, &h, &a, &b, true), Ok(BigUint::from(x)));
+        assert_eq!(kangaroo(&field, &4, &h, &b, &a, false), Err(DlogError::InvalidInterval { a: b.clone(), b: a.clone() }));
+
+        // the interval spans the whole group, so the wild kangaroo wraps around
+        let field = GalloisField::new(1000003, false, None);
+        let h = field.pow(2, 123457);
+        assert_eq!(h, 870294);
+        let b = BigUint::from(1000002u32);
+        assert_eq!(kangaroo(&field, &2, &h, &BigUint::zero(), &b, true), Ok(BigUint::from(123457u32)));
+        for x in [0u32, 1, 999, 500000, 1000001] {
+            let h = field.pow(2, x as u128);
+            assert_eq!(kangaroo(&field, &2, &h, &BigUint::zero(), &b, false), Ok(BigUint::from(x)));
+        }
+
+        let f = GalloisField::new(1019, false, None);
+        let ec = EllipticCurve::new(f, 1, 1, false).expect("ec cant be created");
+        let g = ec.new_point(0, 1).unwrap();
+        let h = ec.exp(&g, &BigUint::from(250u32));
+        assert_eq!(kangaroo(&ec, &g, &h, &BigUint::from(200u32), &BigUint::from(300u32), false),
+            Ok(BigUint::from(250u32)));
+    }
+
+    #[test]
+    fn test_bsgs_big() {
+        // 2^61 - 1 is prime
+        let p = BigUint::from_str("2305843009213693951").unwrap();
+        let field = BigGalloisField::new(p, false, None);
+        let g = BigUint::from(37u32);
+        let x = BigUint::from(987654u32);
+        let h = field.pow(&g, &x);
+        assert_eq!(bsgs(&field, &g, &h, &BigUint::from(1000000u32), false), Ok(x));
+        assert_eq!(bsgs(&field, &g, &h, &BigUint::zero(), false), Err(DlogError::InvalidOrder(BigUint::zero())));
+        assert_eq!(dlog(&field, &g, &h, false), Err(DlogError::TooLarge(field.order_bound())));
+    }
+}
@@ -11,10 +11,13 @@ use crate::cplex::printing::seperator;
 /// Source:     <https://git.cscherr.de/PlexSheep/plexcryptool/>
 
 use super::gallois::GalloisField;
+use super::group::Group;
+use super::dlog::{pohlig_hellman, pollard_rho_dlog, kangaroo};
 
 use std::fmt::Debug;
 
 use num::{Integer, Unsigned, NumCast};
+use num_bigint::BigUint;
 
 use bitvec::prelude::*;
 
@@ -199,7 +202,11 @@ impl EllipticCurve {
             if self.verbose {
                 println!("case 2");
             }
-            return Ok(self.INFINITY_POINT);
+            // the point at infinity is the neutral element
+            if p1.is_infinity_point {
+                return Ok(p2);
+            }
+            return Ok(p1);
         }
         // case 3: r_1 != r_2
         else if p1.r != p2.r {
@@ -461,6 +468,36 @@ impl EllipticCurve {
         }
     }
 
+    #[pyo3(name="pohlig_hellman", signature=(g, h, order, verbose = false))]
+    /// find t with t * g = h using pohlig-hellman, order is the order of g
+    pub fn py_pohlig_hellman(&self, g: EllipticCurvePoint, h: EllipticCurvePoint, order: BigUint,
+        verbose: bool) -> PyResult<BigUint> {
+        match pohlig_hellman(self, &g, &h, &order, verbose) {
+            Ok(t) => Ok(t),
+            Err(e) => Err(PyValueError::new_err(e.to_string()))
+        }
+    }
+
+    #[pyo3(name="rho_dlog", signature=(g, h, order, verbose = false))]
+    /// find t with t * g = h using pollard's rho, order is the order of g
+    pub fn py_rho_dlog(&self, g: EllipticCurvePoint, h: EllipticCurvePoint, order: BigUint,
+        verbose: bool) -> PyResult<BigUint> {
+        match pollard_rho_dlog(self, &g, &h, &order, verbose) {
+            Ok(t) => Ok(t),
+            Err(e) => Err(PyValueError::new_err(e.to_string()))
+        }
+    }
+
+    #[pyo3(name="kangaroo", signature=(g, h, a, b, verbose = false))]
+    /// find t in [a, b] with t * g = h using pollard's kangaroo
+    pub fn py_kangaroo(&self, g: EllipticCurvePoint, h: EllipticCurvePoint, a: BigUint, b: BigUint,
+        verbose: bool) -> PyResult<BigUint> {
+        match kangaroo(self, &g, &h, &a, &b, verbose) {
+            Ok(t) => Ok(t),
+            Err(e) => Err(PyValueError::new_err(e.to_string()))
+        }
+    }
+
     pub fn get_infinity_point(&self) -> EllipticCurvePoint {
         return self.INFINITY_POINT;
     }
@@ -474,6 +511,36 @@ impl EllipticCurve {
     }
 }
 
+/// the group of points on the curve
+impl Group for EllipticCurve {
+    type Element = EllipticCurvePoint;
+
+    fn identity(&self) -> EllipticCurvePoint {
+        self.INFINITY_POINT
+    }
+
+    fn op(&self, a: &EllipticCurvePoint, b: &EllipticCurvePoint) -> EllipticCurvePoint {
+        // the algorithms using the group print their own steps, a trace of every single
+        // addition would bury them
+        let mut quiet = self.clone();
+        quiet.verbose = false;
+        quiet.add(*a, *b).expect("error while performing point addition")
+    }
+
+    fn inv(&self, a: &EllipticCurvePoint) -> EllipticCurvePoint {
+        if a.is_infinity_point {
+            return *a;
+        }
+        EllipticCurvePoint::new(a.r, self.field.reduce(-(a.s as i128)))
+    }
+
+    /// upper bound from the hasse theorem: #E <= p + 1 + 2 * sqrt(p)
+    fn order_bound(&self) -> BigUint {
+        let p = BigUint::from(self.field.base);
+        &p + 2u8 + p.sqrt() * 2u8
+    }
+}
+
 impl std::fmt::Display for EllipticCurve{
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         if self.field.prime_base {
@@ -492,7 +559,7 @@ impl std::fmt::Display for EllipticCurve{
 }
 
 #[pyclass]
-#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
 /// represent a specific eliptic curves point
 ///
 /// PartialEq and Eq might behave badly with diffrent verbosity FIXME
@@ -514,6 +581,16 @@ impl EllipticCurvePoint {
         }
     }
 
+    /// the coordinates (r, s), None for the point at infinity
+    pub fn coordinates(&self) -> Option<(u128, u128)> {
+        if self.is_infinity_point {
+            None
+        }
+        else {
+            Some((self.r, self.s))
+        }
+    }
+
     fn __str__(&self) -> PyResult<String>   {
         Ok(format!("{}", self))
     }
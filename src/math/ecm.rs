@@ -0,0 +1,295 @@
+#![allow(dead_code)]
+/// Lenstra elliptic curve factorization (ECM)
+///
+/// Calculates on random elliptic curves over Z/nZ like [ecc](super::ecc) does over a gallois field.
+/// Point addition needs a multiplicative inverse, which does not exist for every number when n
+/// is composite. If an inverse can not be found, the gcd of that number and n is a factor of n.
+///
+/// [EcmCurve] and [EcmPoint] are kept apart from [EllipticCurve](super::ecc::EllipticCurve) on
+/// purpose: that one is built on a [GalloisField](super::gallois::GalloisField) of u128 with a
+/// prime base and treats a missing inverse as a bug (it panics). ECM works with BigInts modulo a
+/// composite n, and a missing inverse is the result it is looking for, so addition here returns
+/// the gcd of the failed denominator and n instead of a point.
+///
+/// Only stage 1 is implemented: a random point is multiplied by every prime power up to a
+/// smoothness bound B1. This finds a factor p of n if the order of the curve modulo p is B1 smooth.
+///
+/// Author:     Christoph J. Scherr <software@cscherr.de>
+/// License:    MIT
+/// Source:     <https://git.cscherr.de/PlexSheep/plexcryptool/>
+
+use crate::cplex::printing::seperator;
+use crate::math::gcd::mod_inverse;
+use crate::math::primality::is_prime;
+
+use core::fmt;
+
+use num::Integer;
+use num_bigint::{BigInt, RandBigInt};
+use num_traits::{One, Zero};
+
+use pyo3::{prelude::*, exceptions::PyArithmeticError};
+
+use primes::{Sieve, PrimeSet};
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// default smoothness bound for stage 1
+pub const DEFAULT_B1: u64 = 2000;
+/// default amount of curves to try
+pub const DEFAULT_CURVES: usize = 100;
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+#[derive(Debug)]
+/// used when no factor could be found with the given bounds
+pub struct NoFactorError {
+    /// the number that could not be factorised
+    pub n: BigInt
+}
+
+impl fmt::Display for NoFactorError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "no factor of {} found, try a higher bound or more curves", self.n)
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// a point on a curve over Z/nZ
+pub enum EcmPoint {
+    /// the point at infinity
+    Infinity,
+    /// a regular point (x, y)
+    Affine(BigInt, BigInt)
+}
+
+impl fmt::Display for EcmPoint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EcmPoint::Infinity => write!(f, "(∞ INFINITY)"),
+            EcmPoint::Affine(x, y) => write!(f, "({x}, {y})")
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// an elliptic curve y² = x³ + ax + b over Z/nZ
+pub struct EcmCurve {
+    /// the number to factorise
+    pub n: BigInt,
+    /// coefficient a
+    pub a: BigInt,
+    /// coefficient b
+    pub b: BigInt
+}
+
+impl EcmCurve {
+    /// add two points
+    ///
+    /// Err contains a nontrivial divisor of n or n itself, if an inverse was needed but does not
+    /// exist.
+    pub fn add(&self, p1: &EcmPoint, p2: &EcmPoint) -> Result<EcmPoint, BigInt> {
+        let (x1, y1, x2, y2) = match (p1, p2) {
+            (EcmPoint::Infinity, _) => return Ok(p2.clone()),
+            (_, EcmPoint::Infinity) => return Ok(p1.clone()),
+            (EcmPoint::Affine(x1, y1), EcmPoint::Affine(x2, y2)) => (x1, y1, x2, y2)
+        };
+        let m: BigInt = if x1 == x2 {
+            if (y1 + y2).mod_floor(&self.n).is_zero() {
+                return Ok(EcmPoint::Infinity);
+            }
+            // m = [3*r² + a]/[2s]
+            let inv = mod_inverse(&(y1 * 2u8), &self.n)?;
+            ((x1 * x1 * 3u8 + &self.a) * inv).mod_floor(&self.n)
+        }
+        else {
+            // m = [s_2 - s_1]/[r_2 - r_1]
+            let inv = mod_inverse(&(x2 - x1), &self.n)?;
+            ((y2 - y1) * inv).mod_floor(&self.n)
+        };
+        let x3 = (&m * &m - x1 - x2).mod_floor(&self.n);
+        let y3 = (&m * (x1 - &x3) - y1).mod_floor(&self.n);
+        Ok(EcmPoint::Affine(x3, y3))
+    }
+
+    /// multiply a point by an integer with double and add
+    pub fn mul(&self, p: &EcmPoint, t: &BigInt) -> Result<EcmPoint, BigInt> {
+        let mut h = EcmPoint::Infinity;
+        for index in (0..t.bits()).rev() {
+            h = self.add(&h, &h)?;
+            if t.bit(index) {
+                h = self.add(&h, p)?;
+            }
+        }
+        Ok(h)
+    }
+}
+
+impl fmt::Display for EcmCurve {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "y² = x³ + {}x + {} (mod {})", self.a, self.b, self.n)
+    }
+}
+
+/// choose a random curve and a point on it
+///
+/// Err contains a factor of n if the discriminant shares one with n.
+fn random_curve(n: &BigInt) -> Result<(EcmCurve, EcmPoint), BigInt> {
+    let mut rng = rand::thread_rng();
+    loop {
+        let x = rng.gen_bigint_range(&BigInt::zero(), n);
+        let y = rng.gen_bigint_range(&BigInt::zero(), n);
+        let a = rng.gen_bigint_range(&BigInt::zero(), n);
+        let b = (&y * &y - &x * &x * &x - &a * &x).mod_floor(n);
+        let disc: BigInt = (&a * &a * &a * 4u8 + &b * &b * 27u8).mod_floor(n);
+        let g = disc.gcd(n);
+        if &g == n {
+            // singular curve, try again
+            continue;
+        }
+        if !g.is_one() {
+            return Err(g);
+        }
+        return Ok((EcmCurve { n: n.clone(), a, b }, EcmPoint::Affine(x, y)));
+    }
+}
+
+/// find a nontrivial factor of n with ECM
+///
+/// tries up to `curves` random curves with stage 1 bound b1. Returns None if no factor was found.
+pub fn ecm(n: &BigInt, b1: u64, curves: usize, verbose: bool) -> Option<BigInt> {
+    if n.is_even() {
+        return Some(BigInt::from(2));
+    }
+    let primes: Vec<u64> = Sieve::new().iter().take_while(|p| *p <= b1).collect();
+    for index in 0..curves {
+        let (curve, mut p) = match random_curve(n) {
+            Ok(v) => v,
+            Err(g) => {
+                if verbose {
+                    println!("gcd(4a³ + 27b², n) = {g}");
+                }
+                return Some(g);
+            }
+        };
+        if verbose {
+            seperator();
+            println!("curve {index}: {curve}");
+            println!("P = {p}");
+        }
+        for prime in &primes {
+            // largest power of prime not bigger than b1
+            let mut pe: u64 = *prime;
+            while pe <= b1 / prime {
+                pe *= prime;
+            }
+            match curve.mul(&p, &BigInt::from(pe)) {
+                Ok(q) => {
+                    p = q;
+                }
+                Err(g) => {
+                    if &g == n {
+                        if verbose {
+                            println!("[{pe}]P: no inverse and gcd = n, curve failed");
+                        }
+                        break;
+                    }
+                    if verbose {
+                        println!("[{pe}]P: no inverse, gcd = {g}");
+                        println!("found factor {g}, {n} = {g} * {}", n / &g);
+                    }
+                    return Some(g);
+                }
+            }
+            if p == EcmPoint::Infinity {
+                if verbose {
+                    println!("[{pe}]P = {p}, curve failed");
+                }
+                break;
+            }
+        }
+        if verbose && p != EcmPoint::Infinity {
+            println!("[k]P = {p}, no factor found");
+        }
+    }
+    None
+}
+
+/// find the prime factors of n with ECM
+///
+/// returns the prime factors in ascending order together with their multiplicity.
+pub fn ecm_factors(n: &BigInt, b1: u64, curves: usize, verbose: bool)
+    -> Result<Vec<(BigInt, u32)>, NoFactorError> {
+    let mut primes: Vec<BigInt> = Vec::new();
+    let mut composites: Vec<BigInt> = vec![n.clone()];
+    while let Some(m) = composites.pop() {
+        if m <= BigInt::one() {
+            continue;
+        }
+        if is_prime(&m, false) {
+            primes.push(m);
+            continue;
+        }
+        match ecm(&m, b1, curves, verbose) {
+            Some(d) => {
+                composites.push(&m / &d);
+                composites.push(d);
+            }
+            None => {
+                return Err(NoFactorError { n: m });
+            }
+        }
+    }
+    primes.sort();
+    let mut factors: Vec<(BigInt, u32)> = Vec::new();
+    for p in primes {
+        match factors.last_mut() {
+            Some((last, count)) if *last == p => *count += 1,
+            _ => factors.push((p, 1))
+        }
+    }
+    Ok(factors)
+}
+
+#[pyfunction]
+#[pyo3(name="ecm")]
+#[pyo3(signature=(n, b1 = DEFAULT_B1, curves = DEFAULT_CURVES, verbose = false))]
+/// python wrapper for ecm_factors
+pub fn py_ecm_factors(n: BigInt, b1: u64, curves: usize, verbose: bool) -> PyResult<Vec<(BigInt, u32)>> {
+    match ecm_factors(&n, b1, curves, verbose) {
+        Ok(v) => Ok(v),
+        Err(e) => Err(PyArithmeticError::new_err(e.to_string()))
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+pub mod test {
+
+    use super::*;
+
+    #[test]
+    fn test_ecm_curve() {
+        // same curve as in the ecc tests, but over Z/13Z
+        let curve = EcmCurve { n: BigInt::from(13), a: BigInt::from(-3), b: BigInt::from(3) };
+        let p1 = EcmPoint::Affine(BigInt::from(1), BigInt::from(1));
+        let p2 = EcmPoint::Affine(BigInt::from(5), BigInt::from(3));
+        assert_eq!(curve.add(&p1, &p2), Ok(EcmPoint::Affine(BigInt::from(4), BigInt::from(4))));
+        assert_eq!(curve.mul(&p1, &BigInt::from(2)), Ok(EcmPoint::Affine(BigInt::from(11), BigInt::from(12))));
+
+        // 2 has no inverse mod 26
+        let curve = EcmCurve { n: BigInt::from(26), a: BigInt::from(-3), b: BigInt::from(3) };
+        assert_eq!(curve.add(&p1, &EcmPoint::Affine(BigInt::from(3), BigInt::from(1))), Err(BigInt::from(2)));
+    }
+
+    #[test]
+    fn test_ecm() {
+        let p = BigInt::from(1000003);
+        let q = BigInt::from(1000033);
+        let r = BigInt::from(65537);
+        let factors = ecm_factors(&(&p * &q * &r * &r), DEFAULT_B1, DEFAULT_CURVES, false).unwrap();
+        assert_eq!(factors, vec![(r, 2), (p, 1), (q, 1)]);
+        assert!(ecm(&BigInt::from(1000003 * 1000033u64), 500, 200, true).is_some());
+    }
+}
@@ -1,12 +1,24 @@
 #![allow(dead_code)]
 /// factorize a large integer
 ///
+/// Contains naive trial division and Pollard's rho method with Brent's cycle detection.
+///
 /// Author:     Christoph J. Scherr <software@cscherr.de>
 /// License:    MIT
 /// Source:     <https://git.cscherr.de/PlexSheep/plexcryptool/>
 
+use crate::math::primality::is_prime;
+use crate::cplex::printing::seperator;
+
+use num::Integer;
+use num_bigint::BigInt;
+use num_traits::{One, Zero, Signed};
+
 use pyo3::prelude::*;
 
+/// how many products are accumulated before a gcd is calculated in Brent's variant
+const RHO_BATCH_SIZE: u64 = 32;
+
 #[pyfunction]
 /// find the prime factors of n
 pub fn prime_factors(mut n: u128, verbose: bool) -> Vec<u128> {
@@ -36,3 +48,146 @@ fn test_prime_factors() {
     // see https://math.tools/numbers/prime-factors/3603234
     assert_eq!(prime_factors(3603234, true), vec![2, 3, 223, 2693]);
 }
+
+/// one run of Pollard's rho method with Brent's cycle detection
+///
+/// Iterates f(x) = x² + c mod n. Returns a nontrivial factor of n, or None if the run failed and
+/// another c should be tried.
+pub fn pollard_rho_brent(n: &BigInt, c: &BigInt, verbose: bool) -> Option<BigInt> {
+    let f = |x: &BigInt| -> BigInt { (x * x + c) % n };
+    let mut y = BigInt::from(2);
+    let mut x = y.clone();
+    let mut ys = y.clone();
+    let mut q = BigInt::one();
+    let mut g = BigInt::one();
+    let mut r: u64 = 1;
+    if verbose {
+        println!("f(x) = x² + {c} mod {n}");
+    }
+    while g.is_one() {
+        x = y.clone();
+        for _ in 0..r {
+            y = f(&y);
+        }
+        let mut k: u64 = 0;
+        while k < r && g.is_one() {
+            ys = y.clone();
+            for _ in 0..RHO_BATCH_SIZE.min(r - k) {
+                y = f(&y);
+                q = (q * (&x - &y).abs()) % n;
+            }
+            g = q.gcd(n);
+            k += RHO_BATCH_SIZE;
+            if verbose {
+                println!("r={r}\tx={x}\ty={y}\tg={g}");
+            }
+        }
+        r *= 2;
+    }
+    if &g == n {
+        // the batch skipped over the factor, go back step by step
+        if verbose {
+            println!("g = n, backtracking from y={ys}");
+        }
+        loop {
+            ys = f(&ys);
+            g = (&x - &ys).abs().gcd(n);
+            if verbose {
+                println!("x={x}\ty={ys}\tg={g}");
+            }
+            if !g.is_one() {
+                break;
+            }
+        }
+    }
+    if &g == n {
+        if verbose {
+            println!("cycle found without factor, f(x) = x² + {c} failed");
+        }
+        return None;
+    }
+    Some(g)
+}
+
+/// find the prime factors of n with Pollard's rho method
+///
+/// returns the prime factors in ascending order together with their multiplicity.
+pub fn pollard_rho_factors(n: &BigInt, verbose: bool) -> Vec<(BigInt, u32)> {
+    let mut primes: Vec<BigInt> = Vec::new();
+    let mut composites: Vec<BigInt> = vec![n.abs()];
+    while let Some(mut m) = composites.pop() {
+        while m.is_even() && !m.is_zero() {
+            m >>= 1;
+            primes.push(BigInt::from(2));
+        }
+        if m.is_one() || m.is_zero() {
+            continue;
+        }
+        if is_prime(&m, false) {
+            if verbose {
+                println!("{m} is prime");
+            }
+            primes.push(m);
+            continue;
+        }
+        if verbose {
+            seperator();
+            println!("factorising {m}");
+        }
+        let mut c = BigInt::one();
+        let d = loop {
+            if let Some(d) = pollard_rho_brent(&m, &c, verbose) {
+                break d;
+            }
+            c += 1;
+        };
+        if verbose {
+            println!("{m} = {d} * {}", &m / &d);
+        }
+        composites.push(&m / &d);
+        composites.push(d);
+    }
+    primes.sort();
+    let mut factors: Vec<(BigInt, u32)> = Vec::new();
+    for p in primes {
+        match factors.last_mut() {
+            Some((last, count)) if *last == p => *count += 1,
+            _ => factors.push((p, 1))
+        }
+    }
+    if verbose {
+        seperator();
+        println!("{n} = {}", factors.iter()
+            .map(|(p, e)| format!("{p}^{e}"))
+            .collect::<Vec<String>>()
+            .join(" * "));
+    }
+    factors
+}
+
+#[pyfunction]
+#[pyo3(name="pollard_rho")]
+#[pyo3(signature=(n, verbose = false))]
+/// python wrapper for pollard_rho_factors
+pub fn py_pollard_rho_factors(n: BigInt, verbose: bool) -> Vec<(BigInt, u32)> {
+    pollard_rho_factors(&n, verbose)
+}
+
+#[test]
+fn test_pollard_rho() {
+    let factors = pollard_rho_factors(&BigInt::from(360), true);
+    assert_eq!(factors, vec![(BigInt::from(2), 3), (BigInt::from(3), 2), (BigInt::from(5), 1)]);
+    let factors = pollard_rho_factors(&BigInt::from(3603234), true);
+    assert_eq!(factors.iter().map(|f| f.0.clone()).collect::<Vec<BigInt>>(),
+        vec![BigInt::from(2), BigInt::from(3), BigInt::from(223), BigInt::from(2693)]);
+    // product of two 28 bit primes
+    let p = BigInt::from(268435367u64);
+    let q = BigInt::from(268435399u64);
+    assert_eq!(pollard_rho_factors(&(&p * &q), false), vec![(p.clone(), 1), (q.clone(), 1)]);
+    assert_eq!(pollard_rho_factors(&(&p * &p * &q), false), vec![(p, 2), (q, 1)]);
+    // product of two 40 bit primes
+    let p = BigInt::from(1099510627651u64);
+    let q = BigInt::from(1099511627689u64);
+    assert_eq!(pollard_rho_factors(&(&p * &q), false), vec![(p, 1), (q, 1)]);
+    assert_eq!(pollard_rho_factors(&BigInt::one(), false), vec![]);
+}
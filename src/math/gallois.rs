@@ -15,18 +15,21 @@
 /// License:    MIT
 /// Source:     <https://git.cscherr.de/PlexSheep/plexcryptool/>
 
-use crate::{math::modexp, cplex::printing::seperator, math::modred::modred};
+use crate::{math::reduction::ModContext, cplex::printing::seperator, math::modred::modred, math::primality::is_prime_u128};
+use crate::math::group::Group;
+use crate::math::arith::multiplicative_order;
+use crate::math::jacobi::{jacobi, legendre};
+use crate::math::dlog::{dlog, pohlig_hellman, pollard_rho_dlog, kangaroo};
 
 use core::fmt;
 use std::fmt::Debug;
 
 use num::{Integer, NumCast};
+use num_bigint::{BigInt, BigUint};
+use num_traits::ToPrimitive;
 
 use pyo3::{prelude::*, exceptions::PyValueError};
 
-use primes::is_prime;
-
-use bitvec::prelude::*;
 
 ///////////////////////////////////////////////////////////////////////////////////////////////////
 
@@ -84,7 +87,7 @@ pub struct GalloisField {
 impl GalloisField {
     /// make a new gallois field
     pub fn new(base: u128, verbose: bool, mut relation: Option<u128>) -> Self {
-        let prime_base: bool = is_prime(base as u64);
+        let prime_base: bool = is_prime_u128(base);
         if !prime_base {
             println!("Non prime bases for a field are currently very experimental.\nUse them at your own risk! ({} is not a prime.)", base);
             if relation.is_none() {
@@ -166,9 +169,50 @@ impl GalloisField {
             }
         }
 
+    /// multiplication in the field
+    ///
+    /// if the base is a power of two, the elements are multiplied as polynomials and reduced by
+    /// the relation of the field.
+    pub fn mul(self, a: u128, b: u128) -> u128 {
+        let a: u128 = self.reduce(a);
+        let b: u128 = self.reduce(b);
+        if self.prime_base {
+            return match a.checked_mul(b) {
+                Some(r) => r % self.base,
+                None => (BigUint::from(a) * b % self.base).to_u128().expect("reduced number too large")
+            };
+        }
+        // shift and add, reducing a whenever its degree reaches the degree of the relation
+        let relation = self.relation.expect("field with non prime base has no relation");
+        let mut a = a;
+        let mut b = b;
+        let mut r: u128 = 0;
+        while b != 0 {
+            if b & 1 == 1 {
+                r ^= a;
+            }
+            b >>= 1;
+            a <<= 1;
+            if a & self.base != 0 {
+                a ^= relation;
+            }
+        }
+        r
+    }
+
+    /// a reduction context for the base, so that many exponentiations can share its constants
+    pub fn context(&self) -> ModContext {
+        ModContext::new(&BigInt::from(self.base))
+    }
+
+    /// calculate the exponent of a base in a prime field with a context from context()
+    pub fn pow_with(&self, ctx: &ModContext, base: u128, exp: u128) -> u128 {
+        ctx.pow(&BigInt::from(base), &BigInt::from(exp)).to_u128().expect("number too big")
+    }
+
     /// calculate the exponent of a base in the field
     pub fn pow(self, base: u128, exp: u128) -> u128 {
-        return modexp::modular_exponentiation_wrapper(base, exp, self.base, false);
+        self.pow_with(&self.context(), base, exp)
     }
 
     /// find the additive inverse of a number
@@ -261,25 +305,28 @@ impl GalloisField {
     pub fn sqrt(self, a: u128) -> Result<(u128, u128), NoRootError> {
         let pm1 = self.base - 1;
         let pm1_2 = pm1.checked_div(2).expect("Could not divide p-1 by 2");
-        let a_pm1_2 = modexp::modular_exponentiation_wrapper(a, pm1_2, self.base, false);
-        if self.verbose {
-            println!("p-1 = {pm1}\n[p-1]/[2] = {pm1_2}\na**([p-1]/[2]) = {a_pm1_2}");
-        }
-        if a_pm1_2 != 1 {
+        // a has a root iff the legendre symbol (a/p) is 1
+        let symbol = match legendre(&BigInt::from(a), &BigInt::from(self.base), self.verbose) {
+            Ok(s) => s,
+            Err(_) => return Err(NoRootError)
+        };
+        if symbol != 1 {
             if self.verbose {
-                println!("a**([p-1]/[2]) != 1 => a has no root.");
+                println!("({a}/{}) != 1 => a has no root.", self.base);
             }
             return Err(NoRootError);
         }
+        let ctx = self.context();
 
         // 4 | (p + 1):
-        if 4 % (self.base + 1) == 0 {
-            let w1 = a_pm1_2;
+        if (self.base + 1) % 4 == 0 {
+            let w1 = self.pow_with(&ctx, a, (self.base + 1) / 4);
             let w1 = self.reduce(w1);
             let w2 = self.a_inverse(w1);
             if self.verbose {
                 seperator();
                 println!("4 divides p+1");
+                println!("w_1 = a^([p+1]/[4]) = {a}^({}) = {w1} (mod {})", (self.base + 1) / 4, self.base);
                 println!("found sqrt of {a} as ({w1}, {w2})");
             }
             return Ok((w1, w2));
@@ -294,7 +341,7 @@ impl GalloisField {
             let mut l: u128 = 0;
             let t: u128;
             loop {
-                if pm1_2.is_multiple_of(&2u128.pow((l+1) as u32)) {
+                if Integer::is_multiple_of(&pm1_2, &2u128.pow((l+1) as u32)) {
                     l += 1;
                 }
                 else {
@@ -305,15 +352,13 @@ impl GalloisField {
                     break;
                 }
             }
-            // chose a b so that b_pm1_2 == -1
+            // chose a b so that (b/p) == -1
             let mut b: Option<u128> = None;
-            let mut b_pm1_2: u128;
-            for b_candidate in 0..self.base {
-                b_pm1_2 = modexp::modular_exponentiation_wrapper(b_candidate, pm1_2, self.base, false);
-                if self.reduce::<_, u128>(b_pm1_2) == self.reduce::<_, u128>(-1) {
+            for b_candidate in 2..self.base {
+                if jacobi(&BigInt::from(b_candidate), &BigInt::from(self.base), false) == Ok(-1) {
                     b = Some(b_candidate);
                     if self.verbose {
-                        println!("b^([p-1]/[2]) = {}^({pm1_2}) = -1 (mod {})", b.unwrap(), self.base);
+                        println!("({}/{}) = -1", b.unwrap(), self.base);
                         println!("found a b that fits the criteria: {}", b.unwrap());
                         seperator();
                     }
@@ -344,7 +389,7 @@ impl GalloisField {
                 if self.verbose {
                     println!("{index}.\tl-(i+1) = {l}-({index}+1) = {tmp}");
                 }
-                tmp = modexp::modular_exponentiation_wrapper(2, tmp, self.base, false);
+                tmp = self.pow_with(&ctx, 2, tmp);
                 if self.verbose {
                     println!("{index}.\t2^[l-(i+1)] = 2^[{l}-({index}+1)] = {tmp}");
                 }
@@ -357,11 +402,11 @@ impl GalloisField {
                     println!("{index}.\t2^[l-(i+1)]*t = 2^[{l}-({index}+1)]*t = {tmp} (mod {})", self.base);
                 }
                 // multiplication with overflow vvvvvvvvvvvvvv
-                tmp = modexp::modular_exponentiation_wrapper(a, tmp, self.base, false);
+                tmp = self.pow_with(&ctx, a, tmp);
                 if self.verbose {
                     println!("{index}.\ta^(2^[l-(i+1)]*t) = {a}^(2^[{l}-({index}+1)]*t) = {tmp}");
                 }
-                tmp *= modexp::modular_exponentiation_wrapper(b, n[index as usize], self.base, false);
+                tmp *= self.pow_with(&ctx, b, n[index as usize]);
                 tmp = self.reduce(tmp);
                 if self.verbose {
                     println!("{index}.\ta^(2^[l-(i+1)]*t) * b^(n_{index}) = {a}^(2^[{l}-({index}+1)]*{t}) * {b}^({}) = {tmp} (mod {})", 
@@ -405,12 +450,12 @@ impl GalloisField {
                 }
             }
             let exp = (t+1).checked_div(2).expect("cant divide to int");
-            let mut w1: u128 = modexp::modular_exponentiation_wrapper(a, exp, self.base, false);
+            let mut w1: u128 = self.pow_with(&ctx, a, exp);
             if self.verbose {
                 seperator();
                 println!("a^([t+1]/[2]) = {w1}");
             }
-            w1 *= modexp::modular_exponentiation_wrapper(b, n[l as usize], self.base, false);
+            w1 *= self.pow_with(&ctx, b, n[l as usize]);
             if self.verbose {
                 println!("w_1 = [a^(t+1)]/[2] * b^(n_l) = [{a}^([{t}+1])]/[2] * {b}^{} = {}", n[l as usize], w1);
             }
@@ -567,7 +612,12 @@ impl GalloisField {
         if n == 0 {
             return None;
         }
-        for ord in 2..self.base {
+        if self.prime_base {
+            return multiplicative_order(&BigInt::from(n), &BigInt::from(self.base), self.verbose)
+                .ok()
+                .and_then(|ord| ord.to_u128());
+        }
+        for ord in 1..self.base {
             if self.pow(n, ord) == 1 {
                 return Some(ord);
             }
@@ -575,6 +625,49 @@ impl GalloisField {
         panic!("No order was found, but n is not 0 and all possibilities have been tried");
     }
 
+    #[pyo3(name="mul")]
+    /// multiply two elements of the field
+    pub fn py_mul(&self, a: u128, b: u128) -> u128 {
+        self.mul(a, b)
+    }
+
+    #[pyo3(name="dlog", signature=(g, h, verbose = false))]
+    /// find x with g^x = h using baby-step giant-step
+    pub fn py_dlog(&self, g: u128, h: u128, verbose: bool) -> PyResult<BigUint> {
+        match dlog(self, &g, &h, verbose) {
+            Ok(x) => Ok(x),
+            Err(e) => Err(PyValueError::new_err(e.to_string()))
+        }
+    }
+
+    #[pyo3(name="pohlig_hellman", signature=(g, h, verbose = false))]
+    /// find x with g^x = h using pohlig-hellman
+    pub fn py_pohlig_hellman(&self, g: u128, h: u128, verbose: bool) -> PyResult<BigUint> {
+        match pohlig_hellman(self, &g, &h, &self.order_bound(), verbose) {
+            Ok(x) => Ok(x),
+            Err(e) => Err(PyValueError::new_err(e.to_string()))
+        }
+    }
+
+    #[pyo3(name="rho_dlog", signature=(g, h, order = None, verbose = false))]
+    /// find x with g^x = h using pollard's rho, order is the order of g
+    pub fn py_rho_dlog(&self, g: u128, h: u128, order: Option<BigUint>, verbose: bool) -> PyResult<BigUint> {
+        let order = order.unwrap_or_else(|| self.order_bound());
+        match pollard_rho_dlog(self, &g, &h, &order, verbose) {
+            Ok(x) => Ok(x),
+            Err(e) => Err(PyValueError::new_err(e.to_string()))
+        }
+    }
+
+    #[pyo3(name="kangaroo", signature=(g, h, a, b, verbose = false))]
+    /// find x in [a, b] with g^x = h using pollard's kangaroo
+    pub fn py_kangaroo(&self, g: u128, h: u128, a: BigUint, b: BigUint, verbose: bool) -> PyResult<BigUint> {
+        match kangaroo(self, &g, &h, &a, &b, verbose) {
+            Ok(x) => Ok(x),
+            Err(e) => Err(PyValueError::new_err(e.to_string()))
+        }
+    }
+
     #[pyo3(name="display")]
     pub fn py_display(&self, n: i128) -> String {
         self.display(n)
@@ -589,6 +682,44 @@ impl GalloisField {
     }
 }
 
+/// the multiplicative group of the field
+impl Group for GalloisField {
+    type Element = u128;
+
+    fn identity(&self) -> u128 {
+        1
+    }
+
+    fn op(&self, a: &u128, b: &u128) -> u128 {
+        self.mul(*a, *b)
+    }
+
+    fn inv(&self, a: &u128) -> u128 {
+        // a^(q-2) = a^(-1) for every element of F_q
+        self.exp(a, &BigUint::from(self.base - 2))
+    }
+
+    fn order_bound(&self) -> BigUint {
+        BigUint::from(self.base - 1)
+    }
+
+    fn exp(&self, a: &u128, k: &BigUint) -> u128 {
+        let k = k % (self.base - 1);
+        if self.prime_base {
+            return self.pow(*a, k.to_u128().expect("reduced exponent too large"));
+        }
+        // pow works on integers, GF(2^k) needs square and multiply with polynomials
+        let mut res: u128 = 1;
+        for index in (0..k.bits()).rev() {
+            res = self.mul(res, res);
+            if k.bit(index) {
+                res = self.mul(res, *a);
+            }
+        }
+        res
+    }
+}
+
 impl std::fmt::Display for GalloisField {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         write!(f, "F_{}", self.base)
@@ -608,6 +739,11 @@ pub mod test {
         assert_eq!(field.sqrt(269).expect("function says there is no root but there is"), (313, 664));
         assert_eq!(field.sqrt(524).expect("function says there is no root but there is"), (115, 862));
         assert_eq!(field.sqrt(275).expect("function says there is no root but there is"), (585, 392));
+        assert!(field.sqrt(3).is_err());
+        // 4 | 983 + 1
+        let field = GalloisField::new(983, true, None);
+        assert_eq!(field.sqrt(25).expect("function says there is no root but there is"), (978, 5));
+        assert!(field.sqrt(982).is_err());
     }
 
     #[test]
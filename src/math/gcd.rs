@@ -8,6 +8,8 @@
 /// Source:     <https://git.cscherr.de/PlexSheep/plexcryptool/>
 
 use num::Integer;
+use num_bigint::BigInt;
+use num_traits::{One, Signed};
 
 use pyo3::prelude::*;
 
@@ -54,3 +56,33 @@ fn test_alt_gcd() {
 pub fn gcd(a: u128, b: u128) -> u128 {
     a.gcd(&b)
 }
+
+/// extended euclidian algorithm for big integers
+///
+/// returns (gcd, x, y) so that a*x + b*y = gcd
+pub fn egcd_big(a: &BigInt, b: &BigInt) -> (BigInt, BigInt, BigInt) {
+    let egcd = a.extended_gcd(b);
+    (egcd.gcd, egcd.x, egcd.y)
+}
+
+/// find the multiplicative inverse of a modulo n
+///
+/// If there is no inverse, the gcd of a and n is returned as error. This is usefull for
+/// algorithms that search for factors of n.
+pub fn mod_inverse(a: &BigInt, n: &BigInt) -> Result<BigInt, BigInt> {
+    let (g, x, _) = egcd_big(&a.mod_floor(n), n);
+    if !g.abs().is_one() {
+        return Err(g.abs());
+    }
+    Ok(x.mod_floor(n))
+}
+
+#[test]
+fn test_mod_inverse() {
+    assert_eq!(mod_inverse(&BigInt::from(12), &BigInt::from(31)), Ok(BigInt::from(13)));
+    assert_eq!(mod_inverse(&BigInt::from(-19), &BigInt::from(31)), Ok(BigInt::from(13)));
+    assert_eq!(mod_inverse(&BigInt::from(6), &BigInt::from(15)), Err(BigInt::from(3)));
+    let (g, x, y) = egcd_big(&BigInt::from(240), &BigInt::from(46));
+    assert_eq!(g, BigInt::from(2));
+    assert_eq!(BigInt::from(240) * x + BigInt::from(46) * y, g);
+}
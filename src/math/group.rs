@@ -0,0 +1,55 @@
+#![allow(dead_code)]
+/// abstract groups
+///
+/// Algorithms for discrete logarithms only need a group operation, the neutral element and
+/// inverses. This module contains a small trait for that, so that the same algorithm can be used
+/// for the multiplicative group of a [GalloisField](super::gallois::GalloisField) or
+/// [BigGalloisField](super::biggallois::BigGalloisField) and other groups.
+///
+/// The group is written multiplicatively: `op` is the group operation and `exp` applies it k
+/// times.
+///
+/// Author:     Christoph J. Scherr <software@cscherr.de>
+/// License:    MIT
+/// Source:     <https://git.cscherr.de/PlexSheep/plexcryptool/>
+
+use std::fmt::Display;
+use std::hash::Hash;
+
+use num_bigint::BigUint;
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// a finite group, written multiplicatively
+pub trait Group {
+    /// elements of the group
+    type Element: Clone + Eq + Hash + Display;
+
+    /// the neutral element
+    fn identity(&self) -> Self::Element;
+
+    /// the group operation a * b
+    fn op(&self, a: &Self::Element, b: &Self::Element) -> Self::Element;
+
+    /// the inverse a^-1 of an element
+    fn inv(&self, a: &Self::Element) -> Self::Element;
+
+    /// an upper bound for the order of any element
+    ///
+    /// this is the order of the group if it is known.
+    fn order_bound(&self) -> BigUint;
+
+    /// apply the group operation k times: a^k
+    ///
+    /// uses square and multiply
+    fn exp(&self, a: &Self::Element, k: &BigUint) -> Self::Element {
+        let mut h = self.identity();
+        for index in (0..k.bits()).rev() {
+            h = self.op(&h, &h);
+            if k.bit(index) {
+                h = self.op(&h, a);
+            }
+        }
+        h
+    }
+}
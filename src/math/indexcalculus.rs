@@ -0,0 +1,293 @@
+#![allow(dead_code)]
+/// index calculus
+///
+/// Discrete logarithms in prime fields F_p for a primitive root g.
+///
+/// 1. choose a factor base of all primes l up to a bound B
+/// 2. find relations: random k where g^k mod p is B smooth, g^k = prod(l_i^e_i) (mod p). Then
+///    k = sum(e_i * log_g(l_i)) (mod p-1)
+/// 3. solve that linear system for the log_g(l_i). p-1 is not prime, so the system is solved
+///    modulo every prime power q^e of p-1 and the results are combined with the
+///    [chinese remainder theorem](super::crt).
+/// 4. find a random s where h * g^s mod p is B smooth, then
+///    log_g(h) = sum(e_i * log_g(l_i)) - s (mod p-1)
+///
+/// Author:     Christoph J. Scherr <software@cscherr.de>
+/// License:    MIT
+/// Source:     <https://git.cscherr.de/PlexSheep/plexcryptool/>
+
+use crate::cplex::printing::seperator;
+use crate::math::crt::crt;
+use crate::math::dlog::DlogError;
+use crate::math::factorise::pollard_rho_factors;
+use crate::math::gcd::mod_inverse;
+use crate::math::modexp::modular_exponentiation;
+use crate::math::primality::is_prime;
+
+use num::Integer;
+use num_bigint::{BigInt, RandBigInt};
+use num_traits::{One, Zero};
+
+use pyo3::{prelude::*, exceptions::PyValueError};
+
+use primes::{Sieve, PrimeSet};
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// how many relations are collected in addition to the size of the factor base
+pub const EXTRA_RELATIONS: usize = 10;
+/// how often more relations are collected when the system can not be solved
+pub const MAX_ROUNDS: usize = 20;
+/// how many random exponents are tried for a single relation before giving up
+const MAX_TRIES: usize = 1_000_000;
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// a relation g^k = prod(l_i^e_i) (mod p)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Relation {
+    /// the exponent k
+    pub k: BigInt,
+    /// the exponents e_i of the factor base primes
+    pub exponents: Vec<u32>,
+}
+
+/// choose a bound for the factor base
+///
+/// uses L_p(1/2, 1/2) = exp(1/2 * sqrt(ln(p) * ln(ln(p)))), but at least 30
+pub fn default_bound(p: &BigInt) -> u64 {
+    let ln_p = p.bits() as f64 * std::f64::consts::LN_2;
+    let bound = (0.5 * (ln_p * ln_p.ln()).sqrt()).exp();
+    (bound as u64).max(30)
+}
+
+/// trial division by the factor base
+///
+/// returns the exponents if n factors completely over the factor base
+fn smooth_exponents(n: &BigInt, factor_base: &[u64]) -> Option<Vec<u32>> {
+    let mut n = n.clone();
+    let mut exponents = vec![0u32; factor_base.len()];
+    for (i, l) in factor_base.iter().enumerate() {
+        let l = BigInt::from(*l);
+        while !n.is_zero() && n.is_multiple_of(&l) {
+            n /= &l;
+            exponents[i] += 1;
+        }
+    }
+    if n.is_one() {
+        Some(exponents)
+    }
+    else {
+        None
+    }
+}
+
+/// format a relation as the lecture does
+fn display_factors(exponents: &[u32], factor_base: &[u64]) -> String {
+    let parts: Vec<String> = exponents.iter().zip(factor_base)
+        .filter(|(e, _)| **e != 0)
+        .map(|(e, l)| format!("{l}^{e}"))
+        .collect();
+    if parts.is_empty() {
+        return String::from("1");
+    }
+    parts.join(" * ")
+}
+
+/// find a random k with g^k mod p smooth over the factor base
+fn find_relation(p: &BigInt, g: &BigInt, factor_base: &[u64]) -> Option<Relation> {
+    let mut rng = rand::thread_rng();
+    let pm1: BigInt = p - 1u8;
+    for _ in 0..MAX_TRIES {
+        let k = rng.gen_bigint_range(&BigInt::one(), &pm1);
+        let gk = modular_exponentiation(g.clone(), k.clone(), p.clone(), false);
+        if let Some(exponents) = smooth_exponents(&gk, factor_base) {
+            return Some(Relation { k, exponents });
+        }
+    }
+    None
+}
+
+/// solve the relations for the logs of the factor base modulo m = q^e
+///
+/// gauss-jordan elimination, only units mod q can be used as pivots. Returns None if a log is
+/// not determined by the relations.
+fn solve_mod(relations: &[Relation], columns: usize, q: &BigInt, m: &BigInt) -> Option<Vec<BigInt>> {
+    let mut rows: Vec<Vec<BigInt>> = relations.iter().map(|r| {
+        let mut row: Vec<BigInt> = r.exponents.iter().map(|e| BigInt::from(*e).mod_floor(m)).collect();
+        row.push(r.k.mod_floor(m));
+        row
+    }).collect();
+    for c in 0..columns {
+        let pivot = (c..rows.len()).find(|r| !rows[*r][c].is_multiple_of(q))?;
+        rows.swap(c, pivot);
+        let inv = mod_inverse(&rows[c][c], m).expect("pivot is a unit");
+        for v in rows[c].iter_mut() {
+            *v = (&*v * &inv).mod_floor(m);
+        }
+        let pivot_row = rows[c].clone();
+        for (r, row) in rows.iter_mut().enumerate() {
+            if r == c || row[c].is_zero() {
+                continue;
+            }
+            let factor = row[c].clone();
+            for (v, pv) in row.iter_mut().zip(&pivot_row).skip(c) {
+                *v = (&*v - &factor * pv).mod_floor(m);
+            }
+        }
+    }
+    Some((0..columns).map(|c| rows[c][columns].clone()).collect())
+}
+
+/// calculate the logs of the factor base
+///
+/// collects relations until the system can be solved modulo every prime power of p-1
+pub fn factor_base_logs(p: &BigInt, g: &BigInt, factor_base: &[u64], verbose: bool)
+    -> Result<Vec<BigInt>, DlogError> {
+    let pm1: BigInt = p - 1u8;
+    let factors = pollard_rho_factors(&pm1, false);
+    if verbose {
+        let parts: Vec<String> = factors.iter().map(|(q, e)| format!("{q}^{e}")).collect();
+        println!("p-1 = {pm1} = {}", parts.join(" * "));
+        println!("factor base: {:?}", factor_base);
+        seperator();
+        println!("relations:");
+    }
+    let mut relations: Vec<Relation> = Vec::new();
+    let mut needed = factor_base.len() + EXTRA_RELATIONS;
+    for _ in 0..MAX_ROUNDS {
+        while relations.len() < needed {
+            let relation = match find_relation(p, g, factor_base) {
+                Some(r) => r,
+                None => return Err(DlogError::NotEnoughRelations(relations.len()))
+            };
+            if verbose {
+                println!("{g}^{} = {} (mod {p})", relation.k, display_factors(&relation.exponents, factor_base));
+            }
+            relations.push(relation);
+        }
+        let mut residues: Vec<Vec<BigInt>> = Vec::new();
+        let mut moduli: Vec<BigInt> = Vec::new();
+        for (q, e) in &factors {
+            let m = q.pow(*e);
+            match solve_mod(&relations, factor_base.len(), q, &m) {
+                Some(logs) => residues.push(logs),
+                None => break
+            }
+            moduli.push(m);
+        }
+        if residues.len() < factors.len() {
+            if verbose {
+                println!("system can not be solved mod {}, collecting more relations", factors[residues.len()].0);
+            }
+            needed += EXTRA_RELATIONS;
+            continue;
+        }
+        let logs: Vec<BigInt> = (0..factor_base.len()).map(|i| {
+            let a: Vec<BigInt> = residues.iter().map(|r| r[i].clone()).collect();
+            crt(&a, &moduli, false).expect("prime powers are coprime").0
+        }).collect();
+        if verbose {
+            seperator();
+            for (l, log) in factor_base.iter().zip(&logs) {
+                println!("log_{g}({l}) = {log}");
+            }
+        }
+        return Ok(logs);
+    }
+    Err(DlogError::NotEnoughRelations(relations.len()))
+}
+
+/// index calculus
+///
+/// finds x with g^x = h (mod p), where p is prime and g is a primitive root mod p. The factor
+/// base contains all primes up to bound, a bound of 0 chooses one with [default_bound].
+pub fn index_calculus(p: &BigInt, g: &BigInt, h: &BigInt, bound: u64, verbose: bool)
+    -> Result<BigInt, DlogError> {
+    if !is_prime(p, false) {
+        return Err(DlogError::NotPrime(p.clone()));
+    }
+    let g = g.mod_floor(p);
+    let h = h.mod_floor(p);
+    if g.is_zero() || h.is_zero() {
+        return Err(DlogError::NoSolution);
+    }
+    let bound = if bound == 0 { default_bound(p) } else { bound };
+    let factor_base: Vec<u64> = Sieve::new().iter()
+        .take_while(|l| *l <= bound && BigInt::from(*l) < *p)
+        .collect();
+    if verbose {
+        seperator();
+        println!("solving {g}^x = {h} (mod {p}), B = {bound}");
+    }
+    let logs = factor_base_logs(p, &g, &factor_base, verbose)?;
+    let pm1: BigInt = p - 1u8;
+    let mut rng = rand::thread_rng();
+    for _ in 0..MAX_TRIES {
+        let s = rng.gen_bigint_range(&BigInt::zero(), &pm1);
+        let target = (&h * modular_exponentiation(g.clone(), s.clone(), p.clone(), false)).mod_floor(p);
+        let exponents = match smooth_exponents(&target, &factor_base) {
+            Some(e) => e,
+            None => continue
+        };
+        let sum: BigInt = exponents.iter().zip(&logs).map(|(e, log)| log * e).sum();
+        let x = (sum - &s).mod_floor(&pm1);
+        if verbose {
+            seperator();
+            println!("h * g^{s} = {} (mod {p})", display_factors(&exponents, &factor_base));
+            println!("x = sum(e_i * log_g(l_i)) - s = {x} (mod {pm1})");
+        }
+        // g might not be a primitive root
+        if modular_exponentiation(g.clone(), x.clone(), p.clone(), false) != h {
+            if verbose {
+                println!("g^x != h, g is not a primitive root or h is not a power of g");
+            }
+            return Err(DlogError::NoSolution);
+        }
+        return Ok(x);
+    }
+    Err(DlogError::NoSolution)
+}
+
+#[pyfunction]
+#[pyo3(name = "index_calculus")]
+#[pyo3(signature=(p, g, h, bound = 0, verbose = false))]
+/// python wrapper for index_calculus
+pub fn py_index_calculus(p: BigInt, g: BigInt, h: BigInt, bound: u64, verbose: bool) -> PyResult<BigInt> {
+    match index_calculus(&p, &g, &h, bound, verbose) {
+        Ok(x) => Ok(x),
+        Err(e) => Err(PyValueError::new_err(e.to_string()))
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+pub mod test {
+
+    use super::*;
+
+    #[test]
+    fn test_smooth_exponents() {
+        let fb = [2, 3, 5, 7];
+        assert_eq!(smooth_exponents(&BigInt::from(2 * 2 * 3 * 7 * 7), &fb), Some(vec![2, 1, 0, 2]));
+        assert_eq!(smooth_exponents(&BigInt::from(2 * 11), &fb), None);
+        assert_eq!(display_factors(&[2, 1, 0, 2], &fb), "2^2 * 3^1 * 7^2");
+    }
+
+    #[test]
+    fn test_index_calculus() {
+        // 8101 - 1 = 2^2 * 3^4 * 5^2, 6 is a primitive root
+        let p = BigInt::from(8101);
+        let g = BigInt::from(6);
+        let h = modular_exponentiation(g.clone(), BigInt::from(7531), p.clone(), false);
+        assert_eq!(index_calculus(&p, &g, &h, 30, true), Ok(BigInt::from(7531)));
+
+        // 2147483579 = 2q + 1 with q prime, 2 is a primitive root
+        let p = BigInt::from(2147483579u64);
+        let g = BigInt::from(2);
+        assert_eq!(index_calculus(&p, &g, &BigInt::from(1022731179), 0, false), Ok(BigInt::from(1234567890)));
+
+        assert_eq!(index_calculus(&BigInt::from(8100), &g, &g, 30, false), Err(DlogError::NotPrime(BigInt::from(8100))));
+    }
+}
@@ -0,0 +1,307 @@
+#![allow(dead_code)]
+/// legendre, jacobi and kronecker symbols
+///
+/// The jacobi symbol (a/n) for odd n > 0 is calculated with quadratic reciprocity, so n never has
+/// to be factored:
+///
+/// - (a/n) = (a mod n / n)
+/// - first supplement: (-1/n) = (-1)^((n-1)/2), -1 iff n = 3 (mod 4)
+/// - second supplement: (2/n) = (-1)^((n²-1)/8), -1 iff n = 3, 5 (mod 8)
+/// - reciprocity: (a/n) = (-1)^((a-1)/2 * (n-1)/2) * (n/a) for odd a, the sign flips iff
+///   a = n = 3 (mod 4)
+///
+/// For an odd prime p this is the legendre symbol: 1 if a is a quadratic residue mod p, -1 if it
+/// is not and 0 if p divides a. The kronecker symbol extends the jacobi symbol to all n.
+///
+/// Author:     Christoph J. Scherr <software@cscherr.de>
+/// License:    MIT
+/// Source:     <https://git.cscherr.de/PlexSheep/plexcryptool/>
+
+use crate::math::primality::is_prime;
+
+use core::fmt;
+
+use num::Integer;
+use num_bigint::BigInt;
+use num_traits::{One, Zero, Signed};
+
+use pyo3::{prelude::*, exceptions::PyValueError};
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// errors for the legendre and jacobi symbols
+pub enum SymbolError {
+    /// the jacobi symbol is only defined for odd n > 0
+    InvalidModulus(BigInt),
+    /// the legendre symbol is only defined for odd primes
+    NotPrime(BigInt),
+}
+
+impl fmt::Display for SymbolError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SymbolError::InvalidModulus(n) => write!(f, "{n} is not odd and positive"),
+            SymbolError::NotPrime(p) => write!(f, "{p} is not an odd prime"),
+        }
+    }
+}
+
+/// format the current state of the calculation, like -(a/n)
+fn term(sign: i8, a: &BigInt, n: &BigInt) -> String {
+    if sign < 0 {
+        format!("-({a}/{n})")
+    }
+    else {
+        format!("({a}/{n})")
+    }
+}
+
+/// print a step of the calculation and the rule that was applied
+fn step(sign: i8, a: &BigInt, n: &BigInt, rule: String) {
+    println!("= {:<40} {rule}", term(sign, a, n));
+}
+
+/// jacobi symbol (a/n) for odd n > 0
+///
+/// uses quadratic reciprocity and both supplements, the verbose output shows every rule that is
+/// applied.
+pub fn jacobi(a: &BigInt, n: &BigInt, verbose: bool) -> Result<i8, SymbolError> {
+    if !n.is_positive() || n.is_even() {
+        return Err(SymbolError::InvalidModulus(n.clone()));
+    }
+    let three = BigInt::from(3);
+    let four = BigInt::from(4);
+    let eight = BigInt::from(8);
+    let mut a = a.clone();
+    let mut n = n.clone();
+    let mut sign: i8 = 1;
+    if verbose {
+        println!("  {}", term(sign, &a, &n));
+    }
+    if a.is_negative() {
+        a = -a;
+        let r = n.mod_floor(&four);
+        if r == three {
+            sign = -sign;
+        }
+        if verbose {
+            step(sign, &a, &n, format!("(-1/{n}) = {}, {n} = {r} (mod 4)", if r == three { -1 } else { 1 }));
+        }
+    }
+    loop {
+        if a >= n {
+            a = a.mod_floor(&n);
+            if verbose {
+                step(sign, &a, &n, format!("reduce mod {n}"));
+            }
+        }
+        if a.is_zero() {
+            let result = if n.is_one() { sign } else { 0 };
+            if verbose {
+                println!("= {:<40} (0/{n}) = {}", result, if n.is_one() { 1 } else { 0 });
+            }
+            return Ok(result);
+        }
+        if a.is_one() {
+            if verbose {
+                println!("= {:<40} (1/{n}) = 1", sign);
+            }
+            return Ok(sign);
+        }
+        if a.is_even() {
+            let k = a.trailing_zeros().expect("a is not zero");
+            a >>= k;
+            let r = n.mod_floor(&eight);
+            let two: i8 = if r == three || r == BigInt::from(5) { -1 } else { 1 };
+            if two < 0 && k % 2 == 1 {
+                sign = -sign;
+            }
+            if verbose {
+                let rule = if k == 1 {
+                    format!("(2/{n}) = {two}, {n} = {r} (mod 8)")
+                }
+                else {
+                    format!("(2/{n})^{k} = ({two})^{k}, {n} = {r} (mod 8)")
+                };
+                step(sign, &a, &n, rule);
+            }
+            continue;
+        }
+        // a is odd and 1 < a < n
+        let a4 = a.mod_floor(&four);
+        let n4 = n.mod_floor(&four);
+        let flip = a4 == three && n4 == three;
+        if flip {
+            sign = -sign;
+        }
+        std::mem::swap(&mut a, &mut n);
+        if verbose {
+            let rule = if flip {
+                format!("reciprocity, {n} = {a} = 3 (mod 4)")
+            }
+            else if a4.is_one() {
+                format!("reciprocity, {n} = 1 (mod 4)")
+            }
+            else {
+                format!("reciprocity, {a} = 1 (mod 4)")
+            };
+            step(sign, &a, &n, rule);
+        }
+    }
+}
+
+/// legendre symbol (a/p) for an odd prime p
+///
+/// 1 if a is a quadratic residue mod p, -1 if it is not and 0 if p divides a
+pub fn legendre(a: &BigInt, p: &BigInt, verbose: bool) -> Result<i8, SymbolError> {
+    if *p < BigInt::from(3) || !is_prime(p, false) {
+        return Err(SymbolError::NotPrime(p.clone()));
+    }
+    let result = jacobi(a, p, verbose)?;
+    if verbose {
+        match result {
+            1 => println!("{a} is a quadratic residue mod {p}"),
+            -1 => println!("{a} is a quadratic non residue mod {p}"),
+            _ => println!("{p} divides {a}"),
+        }
+    }
+    Ok(result)
+}
+
+/// kronecker symbol (a/n) for any n
+///
+/// n = u * 2^v * m with u = ±1 and m odd, then (a/n) = (a/u) * (a/2)^v * (a/m), where
+/// (a/-1) = -1 iff a < 0 and (a/2) = 0 for even a, 1 for a = ±1 (mod 8) and -1 for a = ±3 (mod 8).
+pub fn kronecker(a: &BigInt, n: &BigInt, verbose: bool) -> i8 {
+    if n.is_zero() {
+        let result = if a.abs().is_one() { 1 } else { 0 };
+        if verbose {
+            println!("({a}/0) = {result}");
+        }
+        return result;
+    }
+    let mut sign: i8 = 1;
+    let mut m = n.abs();
+    if n.is_negative() {
+        if a.is_negative() {
+            sign = -sign;
+        }
+        if verbose {
+            println!("({a}/-1) = {}, {a} {} 0", if a.is_negative() { -1 } else { 1 }, if a.is_negative() { "<" } else { ">=" });
+        }
+    }
+    let v = m.trailing_zeros().expect("m is not zero");
+    if v > 0 {
+        m >>= v;
+        let r = a.mod_floor(&BigInt::from(8));
+        let two: i8 = if a.is_even() {
+            0
+        }
+        else if r.is_one() || r == BigInt::from(7) {
+            1
+        }
+        else {
+            -1
+        };
+        if verbose {
+            println!("({a}/2)^{v} = ({two})^{v}, {a} = {r} (mod 8)");
+        }
+        if two == 0 {
+            return 0;
+        }
+        if two < 0 && v % 2 == 1 {
+            sign = -sign;
+        }
+    }
+    if verbose && !m.is_one() {
+        println!("remaining odd part: ({a}/{m})");
+    }
+    let result = sign * jacobi(a, &m, verbose).expect("m is odd and positive");
+    if verbose {
+        println!("({a}/{n}) = {result}");
+    }
+    result
+}
+
+#[pyfunction]
+#[pyo3(name = "jacobi")]
+#[pyo3(signature=(a, n, verbose = false))]
+/// python wrapper for jacobi
+pub fn py_jacobi(a: BigInt, n: BigInt, verbose: bool) -> PyResult<i8> {
+    match jacobi(&a, &n, verbose) {
+        Ok(r) => Ok(r),
+        Err(e) => Err(PyValueError::new_err(e.to_string()))
+    }
+}
+
+#[pyfunction]
+#[pyo3(name = "legendre")]
+#[pyo3(signature=(a, p, verbose = false))]
+/// python wrapper for legendre
+pub fn py_legendre(a: BigInt, p: BigInt, verbose: bool) -> PyResult<i8> {
+    match legendre(&a, &p, verbose) {
+        Ok(r) => Ok(r),
+        Err(e) => Err(PyValueError::new_err(e.to_string()))
+    }
+}
+
+#[pyfunction]
+#[pyo3(name = "kronecker")]
+#[pyo3(signature=(a, n, verbose = false))]
+/// python wrapper for kronecker
+pub fn py_kronecker(a: BigInt, n: BigInt, verbose: bool) -> i8 {
+    kronecker(&a, &n, verbose)
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+pub mod test {
+
+    use super::*;
+
+    fn big(n: i64) -> BigInt {
+        BigInt::from(n)
+    }
+
+    #[test]
+    fn test_jacobi() {
+        assert_eq!(jacobi(&big(1001), &big(9907), true), Ok(-1));
+        assert_eq!(jacobi(&big(19), &big(45), true), Ok(1));
+        assert_eq!(jacobi(&big(8), &big(21), false), Ok(-1));
+        assert_eq!(jacobi(&big(5), &big(21), false), Ok(1));
+        assert_eq!(jacobi(&big(-3), &big(7), true), Ok(1));
+        assert_eq!(jacobi(&big(6), &big(15), false), Ok(0));
+        assert_eq!(jacobi(&big(0), &big(1), false), Ok(1));
+        assert_eq!(jacobi(&big(3), &big(8), false), Err(SymbolError::InvalidModulus(big(8))));
+        assert_eq!(jacobi(&big(3), &big(-7), false), Err(SymbolError::InvalidModulus(big(-7))));
+        // compare with euler's criterion
+        let p = big(977);
+        for a in 0..977 {
+            let euler = crate::math::modexp::modular_exponentiation(big(a), big(488), p.clone(), false);
+            let expected = if euler.is_zero() { 0 } else if euler.is_one() { 1 } else { -1 };
+            assert_eq!(jacobi(&big(a), &p, false), Ok(expected));
+        }
+    }
+
+    #[test]
+    fn test_legendre() {
+        assert_eq!(legendre(&big(269), &big(977), true), Ok(1));
+        assert_eq!(legendre(&big(3), &big(977), false), Ok(-1));
+        assert_eq!(legendre(&big(977 * 3), &big(977), false), Ok(0));
+        assert_eq!(legendre(&big(3), &big(21), false), Err(SymbolError::NotPrime(big(21))));
+        assert_eq!(legendre(&big(3), &big(2), false), Err(SymbolError::NotPrime(big(2))));
+    }
+
+    #[test]
+    fn test_kronecker() {
+        assert_eq!(kronecker(&big(5), &big(21), false), jacobi(&big(5), &big(21), false).unwrap());
+        assert_eq!(kronecker(&big(3), &big(8), true), -1);
+        assert_eq!(kronecker(&big(7), &big(8), false), 1);
+        assert_eq!(kronecker(&big(6), &big(4), false), 0);
+        assert_eq!(kronecker(&big(-1), &big(-1), true), -1);
+        assert_eq!(kronecker(&big(-5), &big(-12), true), -1);
+        assert_eq!(kronecker(&big(1), &big(0), false), 1);
+        assert_eq!(kronecker(&big(2), &big(0), false), 0);
+    }
+}
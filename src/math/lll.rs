@@ -0,0 +1,310 @@
+#![allow(dead_code)]
+/// LLL lattice reduction
+///
+/// Reduces a basis b_0, ..., b_(n-1) of a lattice in Z^m, so that the vectors are short and
+/// nearly orthogonal. All calculations are done exactly with rationals, there is no floating
+/// point error.
+///
+/// With the gram-schmidt orthogonalisation b*_i, μ_(i,j) = <b_i, b*_j> / <b*_j, b*_j> and
+/// B_i = <b*_i, b*_i>, the basis is LLL reduced if
+///
+/// - |μ_(i,j)| <= 1/2 for all j < i (size reduced)
+/// - B_k >= (δ - μ_(k,k-1)²) B_(k-1) for all k (lovász condition)
+///
+/// The first vector of a reduced basis is at most 2^((n-1)/2) times longer than the shortest
+/// vector of the lattice (for δ = 3/4). This follows algorithm 2.6.3 in Cohen, "A Course in
+/// Computational Algebraic Number Theory", which only updates μ and B instead of the b*_i.
+///
+/// Author:     Christoph J. Scherr <software@cscherr.de>
+/// License:    MIT
+/// Source:     <https://git.cscherr.de/PlexSheep/plexcryptool/>
+
+use crate::cplex::printing::seperator;
+
+use core::fmt;
+
+use num::BigRational;
+use num_bigint::BigInt;
+use num_traits::{One, Zero, Signed};
+
+use pyo3::{prelude::*, exceptions::PyValueError};
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// the usual lovász parameter δ = 3/4, as (numerator, denominator)
+pub const DEFAULT_DELTA: (i64, i64) = (3, 4);
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// errors for lattice reduction
+pub enum LllError {
+    /// the basis has no vectors
+    Empty,
+    /// the vectors do not all have the same length
+    DimensionMismatch,
+    /// the vectors are linearly dependent
+    Dependent,
+    /// δ must be in (1/4, 1]
+    InvalidDelta,
+}
+
+impl fmt::Display for LllError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LllError::Empty => write!(f, "the basis is empty"),
+            LllError::DimensionMismatch => write!(f, "the basis vectors have different lengths"),
+            LllError::Dependent => write!(f, "the basis vectors are linearly dependent"),
+            LllError::InvalidDelta => write!(f, "delta must be in (1/4, 1]"),
+        }
+    }
+}
+
+/// inner product of two integer vectors
+pub fn dot(a: &[BigInt], b: &[BigInt]) -> BigInt {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+/// inner product of an integer vector with a rational vector
+fn dot_rational(a: &[BigInt], b: &[BigRational]) -> BigRational {
+    a.iter().zip(b).fold(BigRational::zero(), |acc, (x, y)| acc + y * x)
+}
+
+/// format a vector like [1, 2, 3]
+fn display_vector(v: &[BigInt]) -> String {
+    let parts: Vec<String> = v.iter().map(|x| x.to_string()).collect();
+    format!("[{}]", parts.join(", "))
+}
+
+/// print a basis, one vector per line
+pub fn print_basis(basis: &[Vec<BigInt>]) {
+    for (i, b) in basis.iter().enumerate() {
+        println!("b_{i} = {}", display_vector(b));
+    }
+}
+
+/// state of the reduction
+struct Lll {
+    /// the basis vectors
+    b: Vec<Vec<BigInt>>,
+    /// the gram-schmidt coefficients μ_(i,j) for j < i
+    mu: Vec<Vec<BigRational>>,
+    /// the squared lengths of the gram-schmidt vectors
+    big_b: Vec<BigRational>,
+}
+
+impl Lll {
+    /// calculate the gram-schmidt orthogonalisation
+    fn new(basis: &[Vec<BigInt>]) -> Result<Self, LllError> {
+        let n = basis.len();
+        let mut star: Vec<Vec<BigRational>> = Vec::with_capacity(n);
+        let mut mu: Vec<Vec<BigRational>> = vec![vec![BigRational::zero(); n]; n];
+        let mut big_b: Vec<BigRational> = Vec::with_capacity(n);
+        for (i, b) in basis.iter().enumerate() {
+            let mut v: Vec<BigRational> = b.iter().map(|x| BigRational::from_integer(x.clone())).collect();
+            for j in 0..i {
+                let m = dot_rational(b, &star[j]) / &big_b[j];
+                for (x, y) in v.iter_mut().zip(&star[j]) {
+                    *x -= &m * y;
+                }
+                mu[i][j] = m;
+            }
+            let length = v.iter().fold(BigRational::zero(), |acc, x| acc + x * x);
+            if length.is_zero() {
+                return Err(LllError::Dependent);
+            }
+            big_b.push(length);
+            star.push(v);
+        }
+        Ok(Lll { b: basis.to_vec(), mu, big_b })
+    }
+
+    /// make |μ_(k,l)| <= 1/2 by subtracting a multiple of b_l from b_k
+    fn size_reduce(&mut self, k: usize, l: usize, verbose: bool) {
+        let half = BigRational::new(BigInt::one(), BigInt::from(2));
+        if self.mu[k][l].abs() <= half {
+            return;
+        }
+        let q = self.mu[k][l].round();
+        let qi = q.to_integer();
+        let bl = self.b[l].clone();
+        for (x, y) in self.b[k].iter_mut().zip(&bl) {
+            *x -= &qi * y;
+        }
+        self.mu[k][l] -= &q;
+        let mul = self.mu[l].clone();
+        for (x, y) in self.mu[k].iter_mut().zip(&mul).take(l) {
+            *x -= &q * y;
+        }
+        if verbose {
+            println!("b_{k} = b_{k} - {qi} * b_{l} = {}", display_vector(&self.b[k]));
+        }
+    }
+
+    /// swap b_k and b_(k-1) and update μ and B
+    fn swap(&mut self, k: usize, verbose: bool) {
+        self.b.swap(k, k - 1);
+        for j in 0..(k - 1) {
+            let t = self.mu[k][j].clone();
+            self.mu[k][j] = self.mu[k - 1][j].clone();
+            self.mu[k - 1][j] = t;
+        }
+        let m = self.mu[k][k - 1].clone();
+        let new_b = &self.big_b[k] + &m * &m * &self.big_b[k - 1];
+        self.mu[k][k - 1] = &m * &self.big_b[k - 1] / &new_b;
+        self.big_b[k] = &self.big_b[k - 1] * &self.big_b[k] / &new_b;
+        self.big_b[k - 1] = new_b;
+        for i in (k + 1)..self.b.len() {
+            let t = self.mu[i][k].clone();
+            self.mu[i][k] = &self.mu[i][k - 1] - &m * &t;
+            self.mu[i][k - 1] = t + &self.mu[k][k - 1] * &self.mu[i][k];
+        }
+        if verbose {
+            println!("lovász condition fails for k = {k}, swap b_{} and b_{k}", k - 1);
+        }
+    }
+}
+
+/// reduce a lattice basis with the LLL algorithm
+///
+/// δ is given as a fraction and must be in (1/4, 1]. The vectors must be linearly independent.
+pub fn lll(basis: &[Vec<BigInt>], delta: &BigRational, verbose: bool) -> Result<Vec<Vec<BigInt>>, LllError> {
+    if basis.is_empty() {
+        return Err(LllError::Empty);
+    }
+    if basis.iter().any(|b| b.len() != basis[0].len()) {
+        return Err(LllError::DimensionMismatch);
+    }
+    if *delta <= BigRational::new(BigInt::one(), BigInt::from(4)) || *delta > BigRational::one() {
+        return Err(LllError::InvalidDelta);
+    }
+    let mut state = Lll::new(basis)?;
+    if verbose {
+        println!("input basis, δ = {delta}:");
+        print_basis(basis);
+        seperator();
+    }
+    let n = basis.len();
+    let mut swaps: u64 = 0;
+    let mut k = 1;
+    while k < n {
+        state.size_reduce(k, k - 1, verbose);
+        let m = &state.mu[k][k - 1];
+        if state.big_b[k] < (delta - m * m) * &state.big_b[k - 1] {
+            state.swap(k, verbose);
+            swaps += 1;
+            k = 1.max(k - 1);
+        }
+        else {
+            for l in (0..(k - 1)).rev() {
+                state.size_reduce(k, l, verbose);
+            }
+            k += 1;
+        }
+    }
+    if verbose {
+        seperator();
+        println!("reduced basis after {swaps} swaps:");
+        print_basis(&state.b);
+    }
+    Ok(state.b)
+}
+
+/// check if a basis is LLL reduced
+pub fn is_reduced(basis: &[Vec<BigInt>], delta: &BigRational) -> Result<bool, LllError> {
+    let state = Lll::new(basis)?;
+    let half = BigRational::new(BigInt::one(), BigInt::from(2));
+    for k in 1..basis.len() {
+        if state.mu[k].iter().take(k).any(|m| m.abs() > half) {
+            return Ok(false);
+        }
+        let m = &state.mu[k][k - 1];
+        if state.big_b[k] < (delta - m * m) * &state.big_b[k - 1] {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+/// make a fraction from a (numerator, denominator) pair
+pub fn fraction(num: i64, den: i64) -> BigRational {
+    BigRational::new(BigInt::from(num), BigInt::from(den))
+}
+
+/// determinant of the lattice squared, the product of all B_i
+pub fn gram_determinant(basis: &[Vec<BigInt>]) -> Result<BigInt, LllError> {
+    let state = Lll::new(basis)?;
+    let det = state.big_b.iter().fold(BigRational::one(), |acc, b| acc * b);
+    // this is an integer, the determinant of the gram matrix
+    debug_assert!(det.denom().is_one());
+    Ok(det.to_integer())
+}
+
+#[pyfunction]
+#[pyo3(name = "lll")]
+#[pyo3(signature=(basis, delta = (BigInt::from(3), BigInt::from(4)), verbose = false))]
+/// python wrapper for lll, δ is given as (numerator, denominator)
+pub fn py_lll(basis: Vec<Vec<BigInt>>, delta: (BigInt, BigInt), verbose: bool) -> PyResult<Vec<Vec<BigInt>>> {
+    if delta.1.is_zero() {
+        return Err(PyValueError::new_err(LllError::InvalidDelta.to_string()));
+    }
+    match lll(&basis, &BigRational::new(delta.0, delta.1), verbose) {
+        Ok(b) => Ok(b),
+        Err(e) => Err(PyValueError::new_err(e.to_string()))
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+pub mod test {
+
+    use super::*;
+
+    fn matrix(rows: &[&[i64]]) -> Vec<Vec<BigInt>> {
+        rows.iter().map(|r| r.iter().map(|x| BigInt::from(*x)).collect()).collect()
+    }
+
+    #[test]
+    fn test_lll() {
+        let delta = fraction(DEFAULT_DELTA.0, DEFAULT_DELTA.1);
+        // example from wikipedia
+        let basis = matrix(&[&[1, 1, 1], &[-1, 0, 2], &[3, 5, 6]]);
+        let reduced = lll(&basis, &delta, true).unwrap();
+        assert_eq!(reduced, matrix(&[&[0, 1, 0], &[1, 0, 1], &[-1, 0, 2]]));
+        assert!(is_reduced(&reduced, &delta).unwrap());
+        assert!(!is_reduced(&basis, &delta).unwrap());
+        // the lattice stays the same
+        assert_eq!(gram_determinant(&reduced), gram_determinant(&basis));
+    }
+
+    #[test]
+    fn test_lll_knapsack() {
+        // a subset sum lattice, the solution x = (1, 0, 1, 0, 1) gives the short vector
+        // (2x_i - 1, 0)
+        let weights = [366, 385, 1237, 2468, 3115];
+        let target = 366 + 1237 + 3115;
+        let n = weights.len();
+        let mut basis: Vec<Vec<BigInt>> = Vec::new();
+        for (i, w) in weights.iter().enumerate() {
+            let mut row = vec![BigInt::zero(); n + 1];
+            row[i] = BigInt::from(2);
+            row[n] = BigInt::from(*w) * 100;
+            basis.push(row);
+        }
+        let mut last = vec![BigInt::one(); n + 1];
+        last[n] = BigInt::from(target) * 100;
+        basis.push(last);
+        let reduced = lll(&basis, &fraction(99, 100), false).unwrap();
+        let expected: Vec<Vec<BigInt>> = matrix(&[&[-1, 1, -1, 1, -1, 0], &[1, -1, 1, -1, 1, 0]]);
+        assert!(reduced.iter().any(|r| expected.contains(r)));
+    }
+
+    #[test]
+    fn test_lll_errors() {
+        let delta = fraction(3, 4);
+        assert_eq!(lll(&[], &delta, false), Err(LllError::Empty));
+        assert_eq!(lll(&matrix(&[&[1, 2], &[1]]), &delta, false), Err(LllError::DimensionMismatch));
+        assert_eq!(lll(&matrix(&[&[1, 2], &[2, 4]]), &delta, false), Err(LllError::Dependent));
+        assert_eq!(lll(&matrix(&[&[1, 2], &[2, 5]]), &fraction(1, 4), false), Err(LllError::InvalidDelta));
+    }
+}
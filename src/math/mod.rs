@@ -8,8 +8,23 @@
 /// Source:     <https://git.cscherr.de/PlexSheep/plexcryptool/>
 pub mod modexp;
 pub mod pm1;
+pub mod pp1;
 pub mod modred;
 pub mod gallois;
+pub mod biggallois;
 pub mod gcd;
 pub mod factorise;
 pub mod ecc;
+pub mod primality;
+pub mod ecm;
+pub mod crt;
+pub mod group;
+pub mod dlog;
+pub mod indexcalculus;
+pub mod arith;
+pub mod jacobi;
+pub mod contfrac;
+pub mod lll;
+pub mod coppersmith;
+pub mod reduction;
+pub mod modsqrt;
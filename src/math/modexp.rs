@@ -4,15 +4,163 @@
 /// Implements fast exponentiation with applied modulo. Usefull for calculations in a gallois
 /// field.
 ///
+/// The multiplications are done in a reduction context from math::reduction, so that the
+/// constants for the modulus are computed only once. There are multiple strategies to walk
+/// through the exponent e with l bits, they differ in the number of squarings and
+/// multiplications:
+///
+/// - left to right binary: square for every bit, multiply for every set bit, from the top
+/// - right to left binary: multiply the result with base^(2^i) for every set bit i
+/// - k-ary: precompute base^0 .. base^(2^k - 1), then k squarings and one multiplication for
+///   every k bit digit
+/// - sliding window: precompute only the odd powers base^1, base^3 .. base^(2^k - 1) and let
+///   windows of at most k bits start and end on set bits, runs of zeros cost only squarings
+/// - montgomery ladder: keeps R1 = R0 * base, one squaring and one multiplication for every bit
+///   no matter its value. The operation sequence does not depend on the exponent, but BigInt
+///   arithmetic itself is not constant time.
+///
 /// Author:     Christoph J. Scherr <software@cscherr.de>
 /// License:    MIT
 /// Source:     <https://git.cscherr.de/PlexSheep/plexcryptool/>
 
+use crate::cplex::printing::seperator;
+use crate::math::gcd::mod_inverse;
+use crate::math::reduction::{ModContext, Reduction, ReductionError, ReductionKind};
+
+use core::fmt;
+
 use num_bigint::BigInt;
-use num_traits::ToPrimitive;
+use num_traits::{ToPrimitive, Signed, Zero};
 use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
 
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// the largest window for the k-ary and sliding window methods, the tables have up to 2^k entries
+pub const MAX_WINDOW: usize = 16;
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// errors for modular exponentiation
+pub enum ModexpError {
+    /// the exponent is negative and the base has no inverse
+    NotInvertible(BigInt),
+    /// the requested reduction context can not be used
+    Reduction(ReductionError),
+    /// the window is larger than MAX_WINDOW
+    InvalidWindow(usize),
+}
+
+impl fmt::Display for ModexpError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ModexpError::NotInvertible(b) => write!(f, "the exponent is negative and {b} has no inverse"),
+            ModexpError::Reduction(e) => write!(f, "{e}"),
+            ModexpError::InvalidWindow(k) => write!(f, "a window of {k} bits is too large, use at most {MAX_WINDOW}"),
+        }
+    }
+}
+
+impl From<ReductionError> for ModexpError {
+    fn from(e: ReductionError) -> Self {
+        ModexpError::Reduction(e)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// strategies for the exponentiation
+pub enum ExpMethod {
+    /// left to right binary square and multiply
+    LeftToRight,
+    /// right to left binary square and multiply
+    RightToLeft,
+    /// fixed windows with a table of all powers
+    KAry,
+    /// sliding windows with a table of the odd powers
+    SlidingWindow,
+    /// montgomery ladder
+    Ladder,
+}
+
+impl ExpMethod {
+    /// get a method by name, like "sliding-window"
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().replace(['-', '_', ' '], "").as_str() {
+            "lefttoright" => Some(ExpMethod::LeftToRight),
+            "righttoleft" => Some(ExpMethod::RightToLeft),
+            "kary" => Some(ExpMethod::KAry),
+            "slidingwindow" => Some(ExpMethod::SlidingWindow),
+            "ladder" => Some(ExpMethod::Ladder),
+            _ => None
+        }
+    }
+}
+
+impl fmt::Display for ExpMethod {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ExpMethod::LeftToRight => write!(f, "left to right binary"),
+            ExpMethod::RightToLeft => write!(f, "right to left binary"),
+            ExpMethod::KAry => write!(f, "k-ary"),
+            ExpMethod::SlidingWindow => write!(f, "sliding window"),
+            ExpMethod::Ladder => write!(f, "montgomery ladder"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+/// the cost of an exponentiation, including the precomputed table
+pub struct OpCount {
+    /// number of squarings
+    pub squarings: u64,
+    /// number of multiplications
+    pub multiplications: u64,
+}
+
+impl fmt::Display for OpCount {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} squarings, {} multiplications", self.squarings, self.multiplications)
+    }
+}
+
+/// a reduction context that counts its operations
+struct Counter<'a, R: Reduction + ?Sized> {
+    /// the context that does the work
+    r: &'a R,
+    /// the operations so far
+    ops: OpCount,
+}
+
+impl<'a, R: Reduction + ?Sized> Counter<'a, R> {
+    /// square and count
+    fn sqr(&mut self, a: &BigInt) -> BigInt {
+        self.ops.squarings += 1;
+        self.r.sqr(a)
+    }
+
+    /// multiply and count
+    fn mul(&mut self, a: &BigInt, b: &BigInt) -> BigInt {
+        self.ops.multiplications += 1;
+        self.r.mul(a, b)
+    }
+
+    /// the normal value of a represented number, for the verbose output
+    fn show(&self, a: &BigInt) -> BigInt {
+        self.r.leave(a)
+    }
+}
+
+/// a good window size for an exponent with this many bits
+pub fn default_window(bits: u64) -> usize {
+    match bits {
+        0..=16 => 1,
+        17..=64 => 3,
+        65..=256 => 4,
+        257..=768 => 5,
+        _ => 6
+    }
+}
+
 /// works, but is forbidden for class
 pub fn calc_exp_in_field_lib(
     base: BigInt,
@@ -23,52 +171,294 @@ pub fn calc_exp_in_field_lib(
 
 /**
  * square and multiply
+ *
+ * panics if the field is not positive or the exponent is negative and the base has no inverse,
+ * use checked_modular_exponentiation for numbers that are not checked yet.
  */
 pub fn modular_exponentiation(
     base: BigInt,
     exp: BigInt, 
     field: BigInt,
     verbose: bool) -> BigInt {
+    match checked_modular_exponentiation(base, exp, field, verbose) {
+        Ok(res) => res,
+        Err(e) => panic!("{e}")
+    }
+}
+
+/// square and multiply, with an error instead of a panic for invalid arguments
+pub fn checked_modular_exponentiation(
+    base: BigInt,
+    exp: BigInt,
+    field: BigInt,
+    verbose: bool) -> Result<BigInt, ModexpError> {
     if verbose {
         println!("args:\nbase {base}\nexp {exp}\nfield {field}\nverbose {verbose}");
     }
-    if exp == BigInt::from(0) {
-        return BigInt::from(1);
+    if !field.is_positive() {
+        return Err(ReductionError::InvalidModulus(field).into());
     }
-    let mut instructions: Vec<bool> = bigint_to_bools(exp.clone());
-    // remove the signing bit
+    let ctx = ModContext::new(&field);
+    let (base, exp) = invert_negative(&base, &exp, &field)?;
+    Ok(pow_with(&ctx, &base, &exp, ExpMethod::LeftToRight, 0, verbose).0)
+}
 
-    instructions.reverse();
+/// modular exponentiation with a selectable strategy and reduction context
+///
+/// window is the window size for the k-ary and sliding window methods, at most [MAX_WINDOW], 0
+/// chooses one from the size of the exponent. Without a reduction kind, montgomery is used for
+/// odd and plain reduction for even moduli. Returns the result and the operation counts.
+pub fn modular_exponentiation_with(
+    base: &BigInt,
+    exp: &BigInt,
+    field: &BigInt,
+    method: ExpMethod,
+    window: usize,
+    reduction: Option<ReductionKind>,
+    verbose: bool) -> Result<(BigInt, OpCount), ModexpError> {
+    if window > MAX_WINDOW {
+        return Err(ModexpError::InvalidWindow(window));
+    }
+    let ctx = match reduction {
+        Some(kind) => ModContext::with_kind(field, kind)?,
+        None => {
+            if !field.is_positive() {
+                return Err(ReductionError::InvalidModulus(field.clone()).into());
+            }
+            ModContext::new(field)
+        }
+    };
+    let (base, exp) = invert_negative(base, exp, field)?;
+    if verbose {
+        println!("{method} with {} reduction", ctx.kind());
+        seperator();
+    }
+    Ok(pow_with(&ctx, &base, &exp, method, window, verbose))
+}
 
+/// turn base^-e into (base^-1)^e
+fn invert_negative(base: &BigInt, exp: &BigInt, field: &BigInt) -> Result<(BigInt, BigInt), ModexpError> {
+    if !exp.is_negative() {
+        return Ok((base.clone(), exp.clone()));
+    }
+    match mod_inverse(base, field) {
+        Ok(inv) => Ok((inv, -exp)),
+        Err(_) => Err(ModexpError::NotInvertible(base.clone()))
+    }
+}
+
+/// base^exp in a reduction context for exp >= 0
+///
+/// base and the result are normal numbers, the context is only used for the multiplications in
+/// between. window is used by the k-ary and sliding window methods, 0 chooses one from the size
+/// of the exponent. Panics for a negative exponent or a window larger than [MAX_WINDOW].
+pub fn pow_with<R: Reduction + ?Sized>(
+    r: &R,
+    base: &BigInt,
+    exp: &BigInt,
+    method: ExpMethod,
+    window: usize,
+    verbose: bool) -> (BigInt, OpCount) {
+    assert!(!exp.is_negative(), "the exponent must not be negative");
+    assert!(window <= MAX_WINDOW, "the window must not be larger than {MAX_WINDOW}");
+    let mut c = Counter { r, ops: OpCount::default() };
+    if exp.is_zero() {
+        return (r.leave(&r.one()), c.ops);
+    }
+    let b = r.enter(base);
+    let window = if window == 0 { default_window(exp.bits()) } else { window };
+    let res = match method {
+        ExpMethod::LeftToRight => left_to_right(&mut c, &b, exp, verbose),
+        ExpMethod::RightToLeft => right_to_left(&mut c, &b, exp, verbose),
+        ExpMethod::KAry => k_ary(&mut c, &b, exp, window, verbose),
+        ExpMethod::SlidingWindow => sliding_window(&mut c, &b, exp, window, verbose),
+        ExpMethod::Ladder => ladder(&mut c, &b, exp, verbose),
+    };
+    let res = r.leave(&res);
+    if verbose && method != ExpMethod::LeftToRight {
+        seperator();
+        println!("result {res}\n{}", c.ops);
+    }
+    (res, c.ops)
+}
+
+/// the bit i of a nonnegative number
+fn bit(exp: &BigInt, i: u64) -> bool {
+    exp.magnitude().bit(i)
+}
+
+/// left to right binary square and multiply
+fn left_to_right<R: Reduction + ?Sized>(c: &mut Counter<R>, b: &BigInt, exp: &BigInt, verbose: bool) -> BigInt {
+    let field = c.r.modulus().clone();
+    let instructions: Vec<bool> = (0..exp.bits() - 1).rev().map(|i| bit(exp, i)).collect();
     if verbose {
         println!("exponent to binary/bools (discard first bit):\n{:b}\n{:?}", exp, instructions);
     }
-
-    let mut res = base.clone();
+    let base = c.show(b);
+    let mut res = b.clone();
     for (index, instr) in instructions.iter().enumerate() {
         if !instr {
             // square
             if verbose {
-                print!("{index}. {instr} -> square:\nres = {res}^2 mod {field} = ");
-            }
-            res = res.pow(2) % &field;
-            if verbose {
-                println!("{res}");
+                print!("{index}. {instr} -> square:\nres = {}^2 mod {field} = ", c.show(&res));
             }
+            res = c.sqr(&res);
         }
         else {
             // square and multiply
             if verbose {
-                print!("{index}. {instr} -> square and multiply:\nres = {res}^2 * {base} mod {field} = ");
+                print!("{index}. {instr} -> square and multiply:\nres = {}^2 * {base} mod {field} = ", c.show(&res));
             }
-            res = (res.pow(2) * &base) % &field;
+            res = c.sqr(&res);
+            res = c.mul(&res, b);
+        }
+        if verbose {
+            println!("{}", c.show(&res));
+        }
+    }
+    if verbose {
+        println!("{}", c.ops);
+    }
+    res
+}
+
+/// right to left binary square and multiply
+fn right_to_left<R: Reduction + ?Sized>(c: &mut Counter<R>, b: &BigInt, exp: &BigInt, verbose: bool) -> BigInt {
+    let bits = exp.bits();
+    let mut power = b.clone();
+    // the first factor is taken as it is instead of multiplying it with 1
+    let mut res: Option<BigInt> = None;
+    for i in 0..bits {
+        if bit(exp, i) {
+            res = Some(match res {
+                None => power.clone(),
+                Some(res) => c.mul(&res, &power)
+            });
+        }
+        if verbose {
+            println!("{i}. {} -> base^(2^{i}) = {}, res = {}", bit(exp, i) as u8, c.show(&power),
+                c.show(res.as_ref().unwrap_or(&c.r.one())));
+        }
+        if i + 1 < bits {
+            power = c.sqr(&power);
+        }
+    }
+    res.expect("the exponent is not zero")
+}
+
+/// fixed windows of k bits with a table of base^0 .. base^(2^k - 1)
+fn k_ary<R: Reduction + ?Sized>(c: &mut Counter<R>, b: &BigInt, exp: &BigInt, k: usize, verbose: bool) -> BigInt {
+    // table[i] = base^i
+    let mut table: Vec<BigInt> = vec![c.r.one(), b.clone()];
+    for i in 2..(1usize << k) {
+        let next = c.mul(&table[i - 1], b);
+        table.push(next);
+    }
+    // digits of k bits, most significant first
+    let digits: Vec<usize> = {
+        let mut digits = Vec::new();
+        let mut i = 0;
+        while i < exp.bits() {
+            let mut d = 0;
+            for j in (0..k as u64).rev() {
+                d = (d << 1) | bit(exp, i + j) as usize;
+            }
+            digits.push(d);
+            i += k as u64;
+        }
+        digits.reverse();
+        digits
+    };
+    if verbose {
+        println!("k = {k}, table base^0 .. base^{} ({} multiplications)", table.len() - 1, c.ops.multiplications);
+        println!("digits: {digits:?}");
+    }
+    let mut res = table[digits[0]].clone();
+    for (index, d) in digits.iter().enumerate().skip(1) {
+        for _ in 0..k {
+            res = c.sqr(&res);
+        }
+        if *d != 0 {
+            res = c.mul(&res, &table[*d]);
+        }
+        if verbose {
+            println!("{index}. digit {d}: res = res^(2^{k}) * base^{d} = {}", c.show(&res));
+        }
+    }
+    res
+}
+
+/// windows of at most k bits that end on a set bit, with a table of the odd powers
+fn sliding_window<R: Reduction + ?Sized>(c: &mut Counter<R>, b: &BigInt, exp: &BigInt, k: usize, verbose: bool) -> BigInt {
+    // table[j] = base^(2j + 1)
+    let mut table: Vec<BigInt> = vec![b.clone()];
+    if k > 1 {
+        let b2 = c.sqr(b);
+        for j in 1..(1usize << (k - 1)) {
+            let next = c.mul(&table[j - 1], &b2);
+            table.push(next);
+        }
+    }
+    if verbose {
+        println!("k = {k}, table base^1, base^3 .. base^{} ({})", 2 * table.len() - 1, c.ops);
+    }
+    let mut res: Option<BigInt> = None;
+    let mut i = exp.bits() as i64 - 1;
+    while i >= 0 {
+        if !bit(exp, i as u64) {
+            let r = res.expect("the window starts on the highest bit");
+            res = Some(c.sqr(&r));
             if verbose {
-                println!("{res}");
+                println!("bit {i} = 0: res = res^2 = {}", c.show(res.as_ref().unwrap()));
+            }
+            i -= 1;
+            continue;
+        }
+        // the longest window i..=l with at most k bits that ends on a set bit
+        let mut l = (i - k as i64 + 1).max(0);
+        while !bit(exp, l as u64) {
+            l += 1;
+        }
+        let mut value = 0usize;
+        for j in (l..=i).rev() {
+            value = (value << 1) | bit(exp, j as u64) as usize;
+        }
+        let len = i - l + 1;
+        res = Some(match res {
+            None => table[value / 2].clone(),
+            Some(mut r) => {
+                for _ in 0..len {
+                    r = c.sqr(&r);
+                }
+                c.mul(&r, &table[value / 2])
             }
+        });
+        if verbose {
+            println!("bits {i}..{l} = {value:b}: res = res^(2^{len}) * base^{value} = {}", c.show(res.as_ref().unwrap()));
         }
+        i = l - 1;
     }
+    res.expect("the exponent is not zero")
+}
 
-    return res;
+/// montgomery ladder, R1 = R0 * base after every step
+fn ladder<R: Reduction + ?Sized>(c: &mut Counter<R>, b: &BigInt, exp: &BigInt, verbose: bool) -> BigInt {
+    let mut r0 = c.r.one();
+    let mut r1 = b.clone();
+    for i in (0..exp.bits()).rev() {
+        if bit(exp, i) {
+            r0 = c.mul(&r0, &r1);
+            r1 = c.sqr(&r1);
+        }
+        else {
+            r1 = c.mul(&r0, &r1);
+            r0 = c.sqr(&r0);
+        }
+        if verbose {
+            println!("{i}. {} -> R0 = {}, R1 = {}", bit(exp, i) as u8, c.show(&r0), c.show(&r1));
+        }
+    }
+    r0
 }
 
 /// quick wrapper for modular_exponentiation without BigInts
@@ -92,12 +482,15 @@ pub fn py_modular_exponentiation(
     orig_exp: i128, 
     field: i128,
     verbose: bool) -> PyResult<u128> {
-    let big_res = modular_exponentiation(
+    let big_res = match checked_modular_exponentiation(
         BigInt::from(base), 
         BigInt::from(orig_exp), 
         BigInt::from(field),
         verbose
-        );
+        ) {
+        Ok(r) => r,
+        Err(e) => return Err(PyValueError::new_err(e.to_string()))
+    };
     let res = big_res.to_u128();
     match res {
         Some(v) => {
@@ -110,7 +503,39 @@ pub fn py_modular_exponentiation(
 
 }
 
-/// Dont use this buggy mess
+#[pyfunction]
+#[pyo3(name="modular_exponentiation_with")]
+#[pyo3(signature=(base, exp, field, method = "left-to-right", window = 0, reduction = None, verbose = false))]
+/// python wrapper for modular_exponentiation_with, returns the result, squarings and multiplications
+pub fn py_modular_exponentiation_with(
+    base: BigInt,
+    exp: BigInt,
+    field: BigInt,
+    method: &str,
+    window: usize,
+    reduction: Option<&str>,
+    verbose: bool) -> PyResult<(BigInt, u64, u64)> {
+    let method = match ExpMethod::from_name(method) {
+        Some(m) => m,
+        None => return Err(PyValueError::new_err(format!("unknown method {method}")))
+    };
+    let reduction = match reduction {
+        None => None,
+        Some(name) => match ReductionKind::from_name(name) {
+            Some(k) => Some(k),
+            None => return Err(PyValueError::new_err(format!("unknown reduction {name}")))
+        }
+    };
+    match modular_exponentiation_with(&base, &exp, &field, method, window, reduction, verbose) {
+        Ok((res, ops)) => Ok((res, ops.squarings, ops.multiplications)),
+        Err(e) => Err(PyValueError::new_err(e.to_string()))
+    }
+}
+
+/// recursive binary exponentiation without a modulus
+///
+/// base^e = (base^2)^(e/2) for even e and base * (base^2)^((e-1)/2) for odd e. Negative
+/// exponents only give integers for base ±1, other bases end up as 0.
 pub fn binary_exponentiation(base: BigInt, exp: BigInt, verbose: bool) -> BigInt {
     if exp.clone() < BigInt::from(0) {
         return binary_exponentiation(1/&base, -exp, verbose);
@@ -122,34 +547,13 @@ pub fn binary_exponentiation(base: BigInt, exp: BigInt, verbose: bool) -> BigInt
         return binary_exponentiation(&base*&base, &exp/2, verbose);
     }
     else if exp.clone() % 2 == BigInt::from(1) {
-        return binary_exponentiation(&base*&base, (&exp-1)/2, verbose);
+        return &base * binary_exponentiation(&base*&base, (&exp-1)/2, verbose);
     }
     else {
         panic!("I don't know how we got here")
     }
 }
 
-fn bigint_to_bools(item: BigInt) -> Vec<bool> {
-    let mut result: Vec<bool> = Vec::new();
-    let mut modul : BigInt;
-    let mut smaller = item;
-    loop {
-        if smaller < BigInt::from(2) {
-            break;
-        }
-        modul = &smaller % BigInt::from(2);
-        smaller = &smaller / BigInt::from(2);
-        if modul == BigInt::from(1) {
-            result.push(true);
-        }
-        else {
-            result.push(false);
-        }
-    }
-    result
-}
-
-
 fn dump_bin(bytes: &Vec<u8>) {
     for byte in bytes.iter() {
         println!("{:#08b}\t| {:#02x}", byte, byte);
@@ -160,3 +564,82 @@ fn dump_bin(bytes: &Vec<u8>) {
     }
     println!();
 }
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+pub mod test {
+
+    use super::*;
+
+    use std::str::FromStr;
+
+    const METHODS: [ExpMethod; 5] = [ExpMethod::LeftToRight, ExpMethod::RightToLeft, ExpMethod::KAry,
+        ExpMethod::SlidingWindow, ExpMethod::Ladder];
+
+    #[test]
+    fn test_modular_exponentiation() {
+        assert_eq!(modular_exponentiation_wrapper(5, 117, 19, false), 1);
+        assert_eq!(modular_exponentiation_wrapper(7, 560, 561, true), 1);
+        assert_eq!(modular_exponentiation_wrapper(5, 1, 3, false), 2);
+        assert_eq!(modular_exponentiation_wrapper(5, 0, 3, false), 1);
+        assert_eq!(modular_exponentiation(BigInt::from(3), BigInt::from(-1), BigInt::from(7), false), BigInt::from(5));
+        assert_eq!(modular_exponentiation(BigInt::from(-2), BigInt::from(3), BigInt::from(10), false), BigInt::from(2));
+        // even modulus, no montgomery
+        let n = BigInt::from(1u128 << 64);
+        assert_eq!(modular_exponentiation(BigInt::from(3), BigInt::from(1000), n.clone(), false),
+            BigInt::from(3).modpow(&BigInt::from(1000), &n));
+        assert_eq!(checked_modular_exponentiation(BigInt::from(6), BigInt::from(-1), BigInt::from(9), false),
+            Err(ModexpError::NotInvertible(BigInt::from(6))));
+        assert_eq!(checked_modular_exponentiation(BigInt::from(3), BigInt::from(2), BigInt::from(-7), false),
+            Err(ReductionError::InvalidModulus(BigInt::from(-7)).into()));
+    }
+
+    #[test]
+    fn test_methods() {
+        let n = BigInt::from_str("179769313486231590772930519078902473361797697894230657273430081157732675805500963132708477322407536021120113879871393357658789768814416622492847430639474124377767893424865485276302219601246094119453082952085005768838150682342462881473913110540827237163350510684586298239947245938479716304835356329624224137859").unwrap();
+        let base = BigInt::from_str("123456789123456789123456789").unwrap();
+        let exp = BigInt::from_str("98765432109876543210987654321098765432109876543210").unwrap();
+        let expected = base.modpow(&exp, &n);
+        let bits = exp.bits();
+        let ones = exp.magnitude().count_ones();
+        for kind in [ReductionKind::Plain, ReductionKind::Barrett, ReductionKind::Montgomery] {
+            for method in METHODS {
+                let (res, ops) = modular_exponentiation_with(&base, &exp, &n, method, 0, Some(kind), false).unwrap();
+                assert_eq!(res, expected, "{method} with {kind}");
+                match method {
+                    ExpMethod::LeftToRight => assert_eq!(ops, OpCount { squarings: bits - 1, multiplications: ones - 1 }),
+                    ExpMethod::RightToLeft => assert_eq!(ops, OpCount { squarings: bits - 1, multiplications: ones - 1 }),
+                    ExpMethod::Ladder => assert_eq!(ops, OpCount { squarings: bits, multiplications: bits }),
+                    _ => assert!(ops.multiplications < ones - 1)
+                }
+            }
+        }
+        // all windows, small exponents and verbose output
+        for method in METHODS {
+            for window in 1..6 {
+                for e in [1u32, 2, 3, 31, 32, 33, 255, 256, 1000] {
+                    let (res, _) = modular_exponentiation_with(&base, &BigInt::from(e), &n, method, window, None, e == 33).unwrap();
+                    assert_eq!(res, base.modpow(&BigInt::from(e), &n), "{method}, window {window}, e = {e}");
+                }
+            }
+        }
+        assert_eq!(modular_exponentiation_with(&BigInt::from(6), &BigInt::from(-1), &BigInt::from(9), ExpMethod::KAry,
+            0, None, false), Err(ModexpError::NotInvertible(BigInt::from(6))));
+        assert_eq!(modular_exponentiation_with(&base, &exp, &BigInt::from(10), ExpMethod::Ladder, 0,
+            Some(ReductionKind::Montgomery), false), Err(ReductionError::EvenModulus(BigInt::from(10)).into()));
+        for window in [17, 30, 64] {
+            assert_eq!(modular_exponentiation_with(&BigInt::from(3), &BigInt::from(1000), &BigInt::from(1000003),
+                ExpMethod::KAry, window, None, false), Err(ModexpError::InvalidWindow(window)));
+        }
+        let (res, _) = modular_exponentiation_with(&base, &exp, &n, ExpMethod::SlidingWindow, MAX_WINDOW, None, false).unwrap();
+        assert_eq!(res, expected);
+    }
+
+    #[test]
+    fn test_binary_exponentiation() {
+        assert_eq!(binary_exponentiation(BigInt::from(3), BigInt::from(13), false), BigInt::from(1594323));
+        assert_eq!(binary_exponentiation(BigInt::from(-2), BigInt::from(7), false), BigInt::from(-128));
+        assert_eq!(binary_exponentiation(BigInt::from(-1), BigInt::from(-3), false), BigInt::from(-1));
+    }
+}
@@ -14,6 +14,9 @@ use crate::cplex::printing::seperator;
 
 use pyo3::{prelude::*, exceptions::PyException};
 
+use num_bigint::BigUint;
+use num_traits::Zero;
+
 #[test]
 fn test_modred() {
     let rel: u128 = 0x1053;
@@ -63,6 +66,45 @@ pub fn modred(mut poly: u128, relation: u128, verbose: bool) -> Result<u128, Str
     return Ok(poly);
 }
 
+/// modular reduction of a polynomial with a given relation, for polynomials of arbitrary degree
+///
+/// (the function uses the integer representations)
+pub fn modred_big(mut poly: BigUint, relation: &BigUint, verbose: bool) -> BigUint {
+    if relation.is_zero() {
+        panic!("relation can not be zero");
+    }
+    let mut diffrence: u64;
+    let mut index: usize = 0;
+    if verbose {
+        println!("relation:\t{:#x}\t", relation);
+        println!("polynomial:\t{:#x}\t", poly);
+        seperator();
+    }
+    if relation.bits() > poly.bits() {
+        if verbose {
+            println!("relation is longer than polynom, nothing to do.");
+        }
+        return poly;
+    }
+    while poly.bits() >= relation.bits() {
+        diffrence = poly.bits() - relation.bits();
+        poly ^= relation << diffrence;
+        if verbose {
+            println!("{index}:\tpoly: {:#x}\t {:#b}", poly, poly);
+        }
+        index += 1;
+    }
+    poly
+}
+
+#[test]
+fn test_modred_big() {
+    assert_eq!(modred_big(BigUint::from(0x100001u128), &BigUint::from(0x1053u128), false), BigUint::from(0x21eu128));
+    assert_eq!(modred_big(BigUint::from(0x79a5e837d0b4c33eu128), &BigUint::from(0x11fu128), false), BigUint::from(0xe2u128));
+    // x^8 + x^4 + x^3 + x + 1 reduces itself to 0
+    assert_eq!(modred_big(BigUint::from(0x11bu128), &BigUint::from(0x11bu128), false), BigUint::from(0u128));
+}
+
 #[pyfunction]
 #[pyo3(name="mordred")]
 /// python wrapper for modred
@@ -0,0 +1,495 @@
+#![allow(dead_code)]
+/// modular square roots
+///
+/// Find all x with x² = a (mod n) for a composite n with known factorisation.
+///
+/// - mod p: the tonelli-shanks loop of [BigGalloisField::sqrt] or cipolla's algorithm. Cipolla
+///   chooses t so that ω² = t² - a is not a square mod p, then x = (t + ω)^((p+1)/2) in
+///   F_p[ω] = F_p². For p = 3 (mod 4) the root is simply x = a^((p+1)/4). Both roots are x and
+///   p - x.
+/// - mod p^e: hensel lifting, if r² = a (mod p^k) then r' = r - (r² - a) * (2r)^-1 is a root mod
+///   p^(k+1). For p = 2 and odd a there are 4 roots mod 2^e for e >= 3 (a = 1 mod 8 required),
+///   r is lifted by adding 2^(k-1) when needed. If p^k divides a exactly, k has to be even and the
+///   roots are p^(k/2) times the roots of a / p^k.
+/// - mod n: every combination of roots mod the prime powers is combined with the chinese
+///   remainder theorem, so n = pq has four roots for any a coprime to n that has a root.
+///
+/// Author:     Christoph J. Scherr <software@cscherr.de>
+/// License:    MIT
+/// Source:     <https://git.cscherr.de/PlexSheep/plexcryptool/>
+
+use crate::cplex::printing::seperator;
+use crate::math::biggallois::BigGalloisField;
+use crate::math::crt::{crt, CrtError};
+use crate::math::gcd::mod_inverse;
+use crate::math::jacobi::legendre;
+use crate::math::modexp::modular_exponentiation;
+use crate::math::primality::is_prime;
+
+use core::fmt;
+
+use num::Integer;
+use num_bigint::BigInt;
+use num_traits::{One, Zero, ToPrimitive};
+
+use pyo3::{prelude::*, exceptions::PyValueError};
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// refuse to list more roots than this
+pub const MAX_ROOTS: usize = 1 << 16;
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// errors for modular square roots
+pub enum SqrtError {
+    /// a factor of n is not prime
+    NotPrime(BigInt),
+    /// no factors were given
+    Empty,
+    /// a has no square root modulo this prime power
+    NoRoot(BigInt),
+    /// there are more than MAX_ROOTS roots
+    TooManyRoots(BigInt),
+    /// combining the roots failed
+    Crt(CrtError),
+}
+
+impl fmt::Display for SqrtError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SqrtError::NotPrime(p) => write!(f, "{p} is not prime"),
+            SqrtError::Empty => write!(f, "no factors given"),
+            SqrtError::NoRoot(m) => write!(f, "a has no square root modulo {m}"),
+            SqrtError::TooManyRoots(count) => write!(f, "there are {count} roots, more than {MAX_ROOTS}"),
+            SqrtError::Crt(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl From<CrtError> for SqrtError {
+    fn from(e: CrtError) -> Self {
+        SqrtError::Crt(e)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// algorithms for square roots modulo a prime
+pub enum SqrtMethod {
+    /// the loop of BigGalloisField::sqrt
+    TonelliShanks,
+    /// cipolla's algorithm in F_p²
+    Cipolla,
+    /// a^((p+1)/4) for p = 3 (mod 4), tonelli-shanks for other primes
+    Blum,
+}
+
+impl SqrtMethod {
+    /// get a method by name, like "cipolla"
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().replace(['-', '_', ' '], "").as_str() {
+            "tonellishanks" | "tonelli" => Some(SqrtMethod::TonelliShanks),
+            "cipolla" => Some(SqrtMethod::Cipolla),
+            "blum" => Some(SqrtMethod::Blum),
+            _ => None
+        }
+    }
+}
+
+impl fmt::Display for SqrtMethod {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SqrtMethod::TonelliShanks => write!(f, "tonelli-shanks"),
+            SqrtMethod::Cipolla => write!(f, "cipolla"),
+            SqrtMethod::Blum => write!(f, "blum"),
+        }
+    }
+}
+
+/// multiply x1 + y1ω and x2 + y2ω in F_p[ω] with ω² = w
+fn mul_fp2(a: &(BigInt, BigInt), b: &(BigInt, BigInt), w: &BigInt, p: &BigInt) -> (BigInt, BigInt) {
+    let x = (&a.0 * &b.0 + &a.1 * &b.1 * w) % p;
+    let y = (&a.0 * &b.1 + &a.1 * &b.0) % p;
+    (x, y)
+}
+
+/// cipolla's algorithm, a square root of a modulo an odd prime p
+///
+/// returns the smaller of the two roots
+pub fn cipolla(a: &BigInt, p: &BigInt, verbose: bool) -> Result<BigInt, SqrtError> {
+    if *p == BigInt::from(2) || !is_prime(p, false) {
+        return Err(SqrtError::NotPrime(p.clone()));
+    }
+    let a = a.mod_floor(p);
+    if a.is_zero() {
+        return Ok(a);
+    }
+    if legendre(&a, p, false) != Ok(1) {
+        if verbose {
+            println!("({a}/{p}) != 1 => a has no root.");
+        }
+        return Err(SqrtError::NoRoot(p.clone()));
+    }
+    // half of all t work, so this ends quickly
+    let mut t = BigInt::one();
+    let w = loop {
+        let w = (&t * &t - &a).mod_floor(p);
+        if legendre(&w, p, false) == Ok(-1) {
+            break w;
+        }
+        t += 1u8;
+    };
+    let exp: BigInt = (p + 1u8) >> 1;
+    if verbose {
+        println!("t = {t}: ω² = t² - a = {w} (mod {p}) is not a square");
+        println!("x = (t + ω)^([p+1]/[2]) = ({t} + ω)^{exp}");
+    }
+    let base = (t, BigInt::one());
+    let mut res = base.clone();
+    for i in (0..exp.bits() - 1).rev() {
+        res = mul_fp2(&res, &res, &w, p);
+        if exp.bit(i) {
+            res = mul_fp2(&res, &base, &w, p);
+        }
+        if verbose {
+            println!("{}. {} -> {} + {}ω", exp.bits() - 2 - i, exp.bit(i) as u8, res.0, res.1);
+        }
+    }
+    // the ω part vanishes for the root
+    debug_assert!(res.1.is_zero());
+    let x = res.0;
+    let other: BigInt = p - &x;
+    if verbose {
+        println!("found sqrt of {a} as ({x}, {other})");
+    }
+    Ok(x.min(other))
+}
+
+/// the root a^((p+1)/4) of a modulo a prime p = 3 (mod 4)
+///
+/// its square is a^((p+1)/2) = a * a^((p-1)/2), which is a if a is a square.
+fn blum_root(a: &BigInt, p: &BigInt, verbose: bool) -> Result<BigInt, SqrtError> {
+    let exp: BigInt = (p + 1u8) >> 2;
+    let x = modular_exponentiation(a.clone(), exp.clone(), p.clone(), false);
+    if verbose {
+        println!("x = a^((p+1)/4) = {a}^{exp} = {x} (mod {p})");
+    }
+    // for a non residue this is a root of -a
+    if (&x * &x).mod_floor(p) != *a {
+        return Err(SqrtError::NoRoot(p.clone()));
+    }
+    Ok(x)
+}
+
+/// all square roots of a modulo a prime p, sorted
+pub fn sqrt_prime(a: &BigInt, p: &BigInt, method: SqrtMethod, verbose: bool) -> Result<Vec<BigInt>, SqrtError> {
+    if !is_prime(p, false) {
+        return Err(SqrtError::NotPrime(p.clone()));
+    }
+    let a = a.mod_floor(p);
+    if a.is_zero() || *p == BigInt::from(2) {
+        return Ok(vec![a]);
+    }
+    let blum = method == SqrtMethod::Blum && p.mod_floor(&BigInt::from(4)) == BigInt::from(3);
+    let x = match method {
+        SqrtMethod::Blum if blum => blum_root(&a, p, verbose)?,
+        SqrtMethod::TonelliShanks | SqrtMethod::Blum => {
+            let field = BigGalloisField::new(p.magnitude().clone(), verbose, None);
+            match field.sqrt(a.magnitude()) {
+                Ok((w1, _)) => BigInt::from(w1),
+                Err(_) => return Err(SqrtError::NoRoot(p.clone()))
+            }
+        }
+        SqrtMethod::Cipolla => cipolla(&a, p, verbose)?
+    };
+    let mut roots = vec![p - &x, x];
+    roots.sort();
+    Ok(roots)
+}
+
+/// lift a root r of a mod p^k, gcd(a, p) = 1, to a root mod p^e
+pub fn hensel_lift(a: &BigInt, r: &BigInt, p: &BigInt, k: u32, e: u32, verbose: bool) -> BigInt {
+    let mut r = r.clone();
+    let mut modulus = p.pow(k);
+    for i in k..e {
+        modulus *= p;
+        let f = (&r * &r - a).mod_floor(&modulus);
+        let inv = mod_inverse(&(&r * 2u8), &modulus).expect("2r is a unit mod p^k for odd p");
+        r = (&r - f * inv).mod_floor(&modulus);
+        if verbose {
+            println!("mod {p}^{}: r = {r}", i + 1);
+        }
+    }
+    r
+}
+
+/// all square roots of a mod 2^e for odd a, sorted
+fn sqrt_two_power(a: &BigInt, e: u32, verbose: bool) -> Result<Vec<BigInt>, SqrtError> {
+    let modulus = BigInt::one() << e;
+    let a = a.mod_floor(&modulus);
+    let needed = BigInt::from(match e { 1 => 2, 2 => 4, _ => 8 });
+    if !(&a - 1u8).is_multiple_of(&needed) {
+        return Err(SqrtError::NoRoot(modulus));
+    }
+    if e <= 2 {
+        return Ok((1..(1u32 << e)).step_by(2).map(BigInt::from).filter(|x| (x * x - &a).is_multiple_of(&modulus)).collect());
+    }
+    // r = 1 is a root mod 8, adding 2^(k-1) fixes the next bit if needed
+    let mut r = BigInt::one();
+    for k in 3..e {
+        let next = BigInt::one() << (k + 1);
+        if !(&r * &r - &a).is_multiple_of(&next) {
+            r += BigInt::one() << (k - 1);
+        }
+        if verbose {
+            println!("mod 2^{}: r = {r}", k + 1);
+        }
+    }
+    let half = BigInt::one() << (e - 1);
+    let mut roots: Vec<BigInt> = [r.clone(), -&r, &r + &half, &half - &r].iter().map(|x| x.mod_floor(&modulus)).collect();
+    roots.sort();
+    roots.dedup();
+    Ok(roots)
+}
+
+/// all square roots of a modulo p^e, sorted
+pub fn sqrt_prime_power(a: &BigInt, p: &BigInt, e: u32, method: SqrtMethod, verbose: bool)
+    -> Result<Vec<BigInt>, SqrtError> {
+    if !is_prime(p, false) {
+        return Err(SqrtError::NotPrime(p.clone()));
+    }
+    let modulus = p.pow(e);
+    let a = a.mod_floor(&modulus);
+    if verbose {
+        println!("x² = {a} (mod {p}^{e})");
+    }
+    // p^k divides a exactly
+    let mut k = 0;
+    let mut rest = a.clone();
+    while k < e && rest.is_multiple_of(p) {
+        rest /= p;
+        k += 1;
+    }
+    if k == e {
+        // every multiple of p^ceil(e/2)
+        let count = p.pow(e / 2);
+        if count > BigInt::from(MAX_ROOTS) {
+            return Err(SqrtError::TooManyRoots(count));
+        }
+        let step = p.pow(e.div_ceil(2));
+        return Ok((0..count.to_usize().unwrap()).map(|j| &step * j).collect());
+    }
+    if k % 2 == 1 {
+        if verbose {
+            println!("{p}^{k} divides a exactly and k is odd => a has no root.");
+        }
+        return Err(SqrtError::NoRoot(modulus));
+    }
+    let inner = if *p == BigInt::from(2) {
+        sqrt_two_power(&rest, e - k, verbose)
+    }
+    else {
+        sqrt_prime(&rest, p, method, verbose)
+            .map(|roots| roots.iter().map(|r| hensel_lift(&rest, r, p, 1, e - k, verbose)).collect())
+    };
+    let inner: Vec<BigInt> = match inner {
+        Ok(roots) => roots,
+        Err(SqrtError::NoRoot(_)) => return Err(SqrtError::NoRoot(modulus)),
+        Err(e) => return Err(e)
+    };
+    if k == 0 {
+        let mut roots = inner;
+        roots.sort();
+        return Ok(roots);
+    }
+    // x = p^(k/2) * (y + j * p^(e-k)) for every root y mod p^(e-k)
+    let count = p.pow(k / 2);
+    if count.clone() * inner.len() > BigInt::from(MAX_ROOTS) {
+        return Err(SqrtError::TooManyRoots(count * inner.len()));
+    }
+    let factor = p.pow(k / 2);
+    let step = p.pow(e - k);
+    if verbose {
+        println!("{p}^{k} divides a exactly, x = {factor} * (y + j * {step})");
+    }
+    let mut roots: Vec<BigInt> = Vec::new();
+    for y in &inner {
+        for j in 0..count.to_usize().unwrap() {
+            roots.push((&factor * (y + &step * j)).mod_floor(&modulus));
+        }
+    }
+    roots.sort();
+    Ok(roots)
+}
+
+/// all square roots of a modulo the product of the given primes, sorted
+///
+/// primes may repeat for prime powers, e.g. [3, 3, 5] for n = 45.
+pub fn sqrt_mod(a: &BigInt, primes: &[BigInt], method: SqrtMethod, verbose: bool) -> Result<Vec<BigInt>, SqrtError> {
+    if primes.is_empty() {
+        return Err(SqrtError::Empty);
+    }
+    let mut powers: Vec<(BigInt, u32)> = Vec::new();
+    let mut sorted = primes.to_vec();
+    sorted.sort();
+    for p in sorted {
+        match powers.last_mut() {
+            Some((q, e)) if *q == p => *e += 1,
+            _ => powers.push((p, 1))
+        }
+    }
+    let mut moduli: Vec<BigInt> = Vec::new();
+    let mut all_roots: Vec<Vec<BigInt>> = Vec::new();
+    for (p, e) in &powers {
+        if verbose {
+            seperator();
+        }
+        let roots = sqrt_prime_power(a, p, *e, method, verbose)?;
+        if verbose {
+            println!("roots mod {p}^{e}: {roots:?}");
+        }
+        moduli.push(p.pow(*e));
+        all_roots.push(roots);
+    }
+    let count: BigInt = all_roots.iter().map(|r| BigInt::from(r.len())).product();
+    if count > BigInt::from(MAX_ROOTS) {
+        return Err(SqrtError::TooManyRoots(count));
+    }
+    if verbose {
+        seperator();
+        println!("combining {count} roots with the chinese remainder theorem");
+    }
+    // every combination of roots, like the digits of a mixed radix number
+    let mut roots: Vec<BigInt> = Vec::new();
+    let mut index = vec![0usize; all_roots.len()];
+    loop {
+        let residues: Vec<BigInt> = index.iter().zip(&all_roots).map(|(i, r)| r[*i].clone()).collect();
+        let (x, _) = crt(&residues, &moduli, false)?;
+        if verbose {
+            println!("{residues:?} -> {x}");
+        }
+        roots.push(x);
+        let mut pos = 0;
+        while pos < index.len() {
+            index[pos] += 1;
+            if index[pos] < all_roots[pos].len() {
+                break;
+            }
+            index[pos] = 0;
+            pos += 1;
+        }
+        if pos == index.len() {
+            break;
+        }
+    }
+    roots.sort();
+    Ok(roots)
+}
+
+#[pyfunction]
+#[pyo3(name = "cipolla")]
+#[pyo3(signature=(a, p, verbose = false))]
+/// python wrapper for cipolla
+pub fn py_cipolla(a: BigInt, p: BigInt, verbose: bool) -> PyResult<BigInt> {
+    match cipolla(&a, &p, verbose) {
+        Ok(x) => Ok(x),
+        Err(e) => Err(PyValueError::new_err(e.to_string()))
+    }
+}
+
+#[pyfunction]
+#[pyo3(name = "hensel_lift")]
+#[pyo3(signature=(a, r, p, k, e, verbose = false))]
+/// python wrapper for hensel_lift
+pub fn py_hensel_lift(a: BigInt, r: BigInt, p: BigInt, k: u32, e: u32, verbose: bool) -> PyResult<BigInt> {
+    if p == BigInt::from(2) || !(&r * &r - &a).is_multiple_of(&p.pow(k)) || (&a % &p).is_zero() {
+        return Err(PyValueError::new_err("r must be a root of a mod p^k for an odd p and gcd(a, p) = 1"));
+    }
+    Ok(hensel_lift(&a, &r, &p, k, e, verbose))
+}
+
+#[pyfunction]
+#[pyo3(name = "sqrt_mod")]
+#[pyo3(signature=(a, primes, method = "tonelli-shanks", verbose = false))]
+/// python wrapper for sqrt_mod
+pub fn py_sqrt_mod(a: BigInt, primes: Vec<BigInt>, method: &str, verbose: bool) -> PyResult<Vec<BigInt>> {
+    let method = match SqrtMethod::from_name(method) {
+        Some(m) => m,
+        None => return Err(PyValueError::new_err(format!("unknown method {method}")))
+    };
+    match sqrt_mod(&a, &primes, method, verbose) {
+        Ok(roots) => Ok(roots),
+        Err(e) => Err(PyValueError::new_err(e.to_string()))
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+pub mod test {
+
+    use super::*;
+
+    fn big(v: &[i64]) -> Vec<BigInt> {
+        v.iter().map(|x| BigInt::from(*x)).collect()
+    }
+
+    /// all roots by trying every x
+    fn brute(a: i64, n: i64) -> Vec<BigInt> {
+        (0..n).filter(|x| (x * x - a).rem_euclid(n) == 0).map(BigInt::from).collect()
+    }
+
+    #[test]
+    fn test_cipolla() {
+        // p = 1 mod 8, where the tonelli-shanks loop is needed
+        let p = BigInt::from(1000000009);
+        for a in [2i64, 5, 10, 12345678] {
+            let a = BigInt::from(a);
+            match cipolla(&a, &p, a == BigInt::from(10)) {
+                Ok(x) => assert_eq!((&x * &x - &a).mod_floor(&p), BigInt::zero()),
+                Err(e) => assert_eq!(e, SqrtError::NoRoot(p.clone()))
+            }
+        }
+        assert_eq!(cipolla(&BigInt::from(10), &BigInt::from(13), false), Ok(BigInt::from(6)));
+        assert_eq!(cipolla(&BigInt::from(5), &BigInt::from(13), false), Err(SqrtError::NoRoot(BigInt::from(13))));
+        assert_eq!(cipolla(&BigInt::from(5), &BigInt::from(15), false), Err(SqrtError::NotPrime(BigInt::from(15))));
+        for method in [SqrtMethod::TonelliShanks, SqrtMethod::Cipolla, SqrtMethod::Blum] {
+            assert_eq!(sqrt_prime(&BigInt::from(4), &BigInt::from(41), method, false), Ok(big(&[2, 39])));
+            assert_eq!(sqrt_prime(&BigInt::from(2), &BigInt::from(23), method, false), Ok(big(&[5, 18])));
+            assert_eq!(sqrt_prime(&BigInt::from(5), &BigInt::from(23), method, false), Err(SqrtError::NoRoot(BigInt::from(23))));
+        }
+    }
+
+    #[test]
+    fn test_prime_powers() {
+        for (a, p, e) in [(2, 7, 3), (10, 3, 4), (0, 3, 3), (9, 3, 4), (18, 3, 4), (27, 3, 3), (17, 2, 5),
+            (1, 2, 1), (1, 2, 2), (3, 2, 2), (4, 2, 4), (8, 2, 4), (36, 2, 6), (44, 5, 3)] {
+            let n = (p as i64).pow(e);
+            let expected = brute(a, n);
+            let result = sqrt_prime_power(&BigInt::from(a), &BigInt::from(p), e, SqrtMethod::Cipolla, false);
+            if expected.is_empty() {
+                assert_eq!(result, Err(SqrtError::NoRoot(BigInt::from(n))), "{a} mod {p}^{e}");
+            }
+            else {
+                assert_eq!(result, Ok(expected), "{a} mod {p}^{e}");
+            }
+        }
+        assert_eq!(hensel_lift(&BigInt::from(2), &BigInt::from(3), &BigInt::from(7), 1, 3, true), BigInt::from(108));
+    }
+
+    #[test]
+    fn test_sqrt_mod() {
+        // n = pq has four roots
+        let roots = sqrt_mod(&BigInt::from(4), &big(&[7, 11]), SqrtMethod::TonelliShanks, true).unwrap();
+        assert_eq!(roots, big(&[2, 9, 68, 75]));
+        assert_eq!(sqrt_mod(&BigInt::from(4), &big(&[11, 7]), SqrtMethod::Cipolla, false), Ok(roots.clone()));
+        assert_eq!(sqrt_mod(&BigInt::from(4), &big(&[7, 11]), SqrtMethod::Blum, true), Ok(roots));
+        for (a, primes) in [(1, vec![3, 3, 5]), (4, vec![2, 2, 2, 3, 5]), (9, vec![3, 3, 7]), (2, vec![7, 17])] {
+            let n: i64 = primes.iter().product();
+            assert_eq!(sqrt_mod(&BigInt::from(a), &big(&primes), SqrtMethod::Cipolla, false), Ok(brute(a, n)), "{a} mod {n}");
+        }
+        assert_eq!(sqrt_mod(&BigInt::from(3), &big(&[7, 11]), SqrtMethod::Cipolla, false), Err(SqrtError::NoRoot(BigInt::from(7))));
+        assert_eq!(sqrt_mod(&BigInt::from(3), &[], SqrtMethod::Cipolla, false), Err(SqrtError::Empty));
+        assert_eq!(sqrt_mod(&BigInt::from(3), &big(&[9]), SqrtMethod::Cipolla, false), Err(SqrtError::NotPrime(BigInt::from(9))));
+    }
+}
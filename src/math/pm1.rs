@@ -4,200 +4,291 @@
 /// Determine the prime factors of a number with the p minus 1 method.
 /// Effecient for numbers with low ranged prime factors.
 ///
+/// Stage 1 calculates a^k mod n, where k is the product of all prime powers up to a bound B1.
+/// k is never calculated as a whole, instead a is raised to each prime power as they come out of a
+/// sieve. Stage 2 additionally allows p - 1 to have one prime factor q with B1 < q <= B2.
+///
 /// Author:     Christoph J. Scherr <software@cscherr.de>
 /// License:    MIT
 /// Source:     <https://git.cscherr.de/PlexSheep/plexcryptool/>
 
 use pyo3::{prelude::*, exceptions::PyArithmeticError};
 
-use num::integer::gcd;
+use core::fmt;
+
+use num::Integer;
 use num_bigint::BigInt;
-use num_traits::ToPrimitive;
+use num_traits::{One, Zero};
 
-use primes::{Sieve, PrimeSet, is_prime};
+use primes::{Sieve, PrimeSet};
 
-use crate::math::modexp;
+use crate::math::{primality::is_prime, reduction::{ModContext, Reduction}};
+use crate::cplex::printing::seperator;
 
-const MAX_PRIMES: u128 = 80u128;
+/// how many bases a are tried before giving up
+const MAX_BASES: u32 = 20;
 
-/// excecute the p minus one calculation
-pub fn p_minus_one(n: u128, max_prime: u128, verbose: bool) -> Result<Vec<u128>, String> {
-    if n < 3 {
-        return Err(format!("n too small: {n}"));
-    }
-    if max_prime > MAX_PRIMES {
-        return Err(format!("max_prime too large: {max_prime}"));
+///////////////////////////////////////////////////////////////////////////////////////////////////
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// errors for the p minus 1 method
+pub enum PM1Error {
+    /// n is too small to be factorised
+    NTooSmall(BigInt),
+    /// the bounds are not usable
+    InvalidBounds {
+        /// stage 1 bound
+        b1: u64,
+        /// stage 2 bound
+        b2: u64
+    },
+    /// gcd(a^k - 1, n) = 1, p - 1 is not smooth enough for any prime factor p of n
+    NoFactor {
+        /// the number that could not be factorised
+        n: BigInt,
+        /// stage 1 bound
+        b1: u64,
+        /// stage 2 bound
+        b2: u64
+    },
+    /// gcd(a^k - 1, n) = n for every tried base a, the bounds are too high for n
+    AllBasesFailed {
+        /// the number that could not be factorised
+        n: BigInt,
+        /// how many bases were tried
+        bases: u32
+    },
+}
+
+impl fmt::Display for PM1Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PM1Error::NTooSmall(n) => write!(f, "n too small: {n}"),
+            PM1Error::InvalidBounds { b1, b2 } => {
+                write!(f, "invalid bounds: B1 = {b1}, B2 = {b2}")
+            }
+            PM1Error::NoFactor { n, b1, b2 } => {
+                write!(f, "P minus one does not work for {n} with B1 = {b1}, B2 = {b2}. \
+                Use another algorithm or choose higher bounds.")
+            }
+            PM1Error::AllBasesFailed { n, bases } => {
+                write!(f, "gcd(a^k - 1, n) = n = {n} for {bases} bases. Choose lower bounds.")
+            }
+        }
     }
-    if is_prime(n as u64) {
-        return Ok(vec![n]);
+}
+
+/// largest power of prime that is not bigger than bound
+pub(crate) fn max_prime_power(prime: u64, bound: u64) -> u64 {
+    let mut pe: u64 = prime;
+    while pe <= bound / prime {
+        pe *= prime;
     }
-    let mut k_parts: Vec<(u128, u32)> = Vec::new();
-    let mut prime_parts: Vec<u128> = Vec::new();
-    // get a list of the early primes
+    pe
+}
+
+/// stage 1, raise a to every prime power up to b1
+///
+/// If check_each is set, the gcd is calculated after every prime power and the function returns
+/// as soon as it is not 1. Returns a^k mod n and g = gcd(a^k - 1, n).
+fn stage1(ctx: &ModContext, a: &BigInt, b1: u64, check_each: bool, verbose: bool) -> (BigInt, BigInt) {
+    let n = ctx.modulus();
+    let mut ak = a.clone();
+    let mut k = BigInt::one();
+    let mut g = BigInt::one();
     let mut pset = Sieve::new();
-    for (_i_prime, prime) in pset.iter().enumerate().take(max_prime as usize) {
-        let num: u128 = prime as u128;
-        if num > max_prime {
+    for prime in pset.iter() {
+        if prime > b1 {
             break;
         }
-        let mut exp: u32 = 1;
-        loop {
-            if num.pow(exp + 1) < max_prime {
-                exp += 1;
+        let pe = max_prime_power(prime, b1);
+        ak = ctx.pow(&ak, &BigInt::from(pe));
+        if verbose {
+            k *= pe;
+            println!("k at step: {k}\t(* {prime}^{})", pe.ilog(prime));
+        }
+        if check_each {
+            g = (&ak - 1u8).gcd(n);
+            if verbose {
+                println!("g = gcd(a^k - 1, n) = gcd({}, {n}) = {g}", &ak - 1);
             }
-            else {
-                break;
+            if !g.is_one() {
+                return (ak, g);
             }
         }
-        k_parts.push((num, exp));
     }
-    let mut k = 1u128;
-    for (num, exp) in k_parts.clone() {
-        k = num.pow(exp) * k;
-        if verbose {
-            println!("k at step: {k}");
-        }
+    if !check_each {
+        g = (&ak - 1u8).gcd(n);
     }
     if verbose {
-        println!("k: {k}\nk parts: {:?}", k_parts);
+        println!("a^k = {a}^{k} = {ak} (mod {n})");
+        println!("a^k - 1 = {} (mod {n})", &ak - 1);
+        println!("g = gcd(a^k - 1, n) = {g}");
     }
-    let mut a = 2u128;
-    let mut akn1: u128;
-    let mut g: u128;
-    let mut n = n;
-    let mut last_n = 0;
-    if verbose {
-        println!("=======================================================================");
+    (ak, g)
+}
+
+/// stage 2, try every prime b1 < q <= b2 as additional factor of k
+///
+/// Returns g = gcd(Q, n), where Q is the product of all a^(k*q) - 1. The products stay in the
+/// representation of the context, x * R shares the same factors with n as x because R is a unit.
+fn stage2(ctx: &ModContext, ak: &BigInt, b1: u64, b2: u64, check_each: bool, verbose: bool) -> BigInt {
+    let n = ctx.modulus();
+    let primes: Vec<u64> = Sieve::new().iter()
+        .skip_while(|p| *p <= b1)
+        .take_while(|p| *p <= b2)
+        .collect();
+    if primes.is_empty() {
+        return BigInt::one();
     }
-    loop {
-        assert!(n > 1);
-        if verbose {
-            println!("modular exponentiation with: a={a}, k={k}, n={n}");
-        }
-        akn1 = modexp::modular_exponentiation(
-            BigInt::from(a), 
-            BigInt::from(k), 
-            BigInt::from(n), 
-            false).to_u128().expect("Number too large");
-        if akn1 == 0 {
-            //return Err(format!("{a}**{k} - 1 mod {n} = 0"));
-            akn1 = n;
-        }
-        assert_ne!(akn1, 0);
-        let akn1 = akn1 - 1;
-        if akn1 == 0 {
-            a += 1;
-            continue;
+    // a^(k*q) for consecutive primes only differs by a^(k*d) with d = q_(i+1) - q_i
+    let mut steps: Vec<Option<BigInt>> = Vec::new();
+    let one = ctx.one();
+    let mut c = ctx.enter(&ctx.pow(ak, &BigInt::from(primes[0])));
+    let mut q_prod = (&c - &one).mod_floor(n);
+    let mut g = BigInt::one();
+    if check_each {
+        g = q_prod.gcd(n);
+        if !g.is_one() {
+            return g;
+        }
+    }
+    for window in primes.windows(2) {
+        let d = ((window[1] - window[0]) / 2) as usize;
+        if steps.len() <= d {
+            steps.resize(d + 1, None);
         }
-        //assert_ne!(akn1, 0);
+        if steps[d].is_none() {
+            steps[d] = Some(ctx.enter(&ctx.pow(ak, &BigInt::from(2 * d))));
+        }
+        c = ctx.mul(&c, steps[d].as_ref().unwrap());
+        q_prod = ctx.mul(&q_prod, &(&c - &one).mod_floor(n));
+        if check_each {
+            g = (&c - &one).gcd(n);
+            if verbose {
+                println!("q = {}: g = gcd(a^(k*q) - 1, n) = {g}", window[1]);
+            }
+            if !g.is_one() {
+                return g;
+            }
+        }
+    }
+    if !check_each {
+        g = q_prod.gcd(n);
         if verbose {
-            println!("a**k - 1 = {a}**{k} - 1 mod {n} = {akn1}");
+            println!("Q = prod(a^(k*q) - 1) = {} (mod {n})", ctx.leave(&q_prod));
+            println!("g = gcd(Q, n) = {g}");
         }
-        g = gcd(akn1, n);
+    }
+    g
+}
+
+/// find a single nontrivial factor of n
+pub fn p_minus_one_factor(n: &BigInt, b1: u64, b2: u64, verbose: bool) -> Result<BigInt, PM1Error> {
+    if *n < BigInt::from(4) {
+        return Err(PM1Error::NTooSmall(n.clone()));
+    }
+    if b1 < 2 {
+        return Err(PM1Error::InvalidBounds { b1, b2 });
+    }
+    if n.is_even() {
+        return Ok(BigInt::from(2));
+    }
+    // one context for every exponentiation and product mod n
+    let ctx = ModContext::new(n);
+    for base in 2..(2 + MAX_BASES) {
+        let a = BigInt::from(base);
         if verbose {
-            println!("g = gcd(akn1, n) = gcd({akn1}, {n}) = {g}");
+            seperator();
+            println!("stage 1 with: a={a}, B1={b1}, n={n}");
         }
-        if g == 1 {
+        let (ak, mut g) = stage1(&ctx, &a, b1, false, verbose);
+        if &g == n {
             if verbose {
-                println!("=======================================================================");
+                println!("g = {g} = {n} = n, checking every step");
             }
-            return Err(format!("P minus one does not work for this setup. Use another algorithm or choose a higher max prime."));
-            //return p_minus_one(n, 70, verbose);
+            g = stage1(&ctx, &a, b1, true, verbose).1;
         }
-        else if g == n {
+        if &g == n {
             if verbose {
-                println!("g = {g} = {n} = n");
                 println!("bad a, using a=a+1");
             }
-            a += 1;
+            continue;
+        }
+        if !g.is_one() {
+            return Ok(g);
+        }
+        if b2 <= b1 {
+            return Err(PM1Error::NoFactor { n: n.clone(), b1, b2 });
+        }
+        if verbose {
+            seperator();
+            println!("stage 2 with: B1={b1}, B2={b2}");
         }
-        else {
-            last_n = n;
-            n = n / g;
+        g = stage2(&ctx, &ak, b1, b2, false, verbose);
+        if &g == n {
             if verbose {
-                println!("n is prime: {n}");
-                println!("g is prime: {g}");
-                println!("last n is {last_n}");
-            }
-            if is_prime(n as u64) && is_prime(g as u64) {
-                prime_parts.push(g);
-                prime_parts.push(n);
-                break;
-            }
-            else if is_prime(g as u64) {
-                prime_parts.push(g);
-                if verbose {
-                    println!("calculating primes for {g}");
-                }
-                let primes_of = p_minus_one(g, max_prime, false);
-                if primes_of.is_err() {
-                    return  primes_of;
-                }
-                if verbose {
-                    dbg!(&primes_of);
-                }
-                prime_parts.append(&mut primes_of.unwrap());
+                println!("g = {g} = {n} = n, checking every prime");
             }
-            else if is_prime(n as u64) {
-                prime_parts.push(n);
-                if verbose {
-                    println!("calculating primes for {g}");
-                }
-                let primes_of = p_minus_one(g, max_prime, false);
-                if primes_of.is_err() {
-                    return  primes_of;
-                }
-                if verbose {
-                    dbg!(&primes_of);
-                }
-                prime_parts.append(&mut primes_of.unwrap());
-                n = g;
+            g = stage2(&ctx, &ak, b1, b2, true, verbose);
+        }
+        if &g == n {
+            if verbose {
+                println!("bad a, using a=a+1");
             }
-            else {
-                if verbose {
-                    println!("calculating primes for {g} and {n}");
-                }
-                let primes_of = p_minus_one(g, max_prime, false);
-                if primes_of.is_err() {
-                    return  primes_of;
-                }
-                if verbose {
-                    dbg!(&primes_of);
-                }
-                prime_parts.append(&mut primes_of.unwrap());
-                let primes_of = p_minus_one(n, max_prime, false);
-                if primes_of.is_err() {
-                    return  primes_of;
-                }
-                if verbose {
-                    dbg!(&primes_of);
-                }
-                prime_parts.append(&mut primes_of.unwrap());
+            continue;
+        }
+        if g.is_one() {
+            return Err(PM1Error::NoFactor { n: n.clone(), b1, b2 });
+        }
+        return Ok(g);
+    }
+    Err(PM1Error::AllBasesFailed { n: n.clone(), bases: MAX_BASES })
+}
+
+/// excecute the p minus one calculation
+///
+/// returns the distinct prime factors of n in ascending order. Stage 2 is skipped if b2 <= b1.
+pub fn p_minus_one(n: &BigInt, b1: u64, b2: u64, verbose: bool) -> Result<Vec<BigInt>, PM1Error> {
+    if *n < BigInt::from(3) {
+        return Err(PM1Error::NTooSmall(n.clone()));
+    }
+    let mut prime_parts: Vec<BigInt> = Vec::new();
+    let mut composites: Vec<BigInt> = vec![n.clone()];
+    while let Some(m) = composites.pop() {
+        if m.is_one() || m.is_zero() {
+            continue;
+        }
+        if is_prime(&m, false) {
+            if verbose {
+                println!("{m} is prime");
             }
+            prime_parts.push(m);
+            continue;
         }
+        let g = p_minus_one_factor(&m, b1, b2, verbose)?;
         if verbose {
-            println!("=======================================================================");
+            println!("{m} = {g} * {}", &m / &g);
         }
-        if last_n == n {
-            panic!("last n is the same as current n");
-        }
-    }
-    if prime_parts.len() == 0 {
-        //return Err(format!("Found no results?"));
+        composites.push(&m / &g);
+        composites.push(g);
     }
     prime_parts.sort();
     prime_parts.dedup();
-    return Ok(prime_parts);
+    if verbose {
+        seperator();
+    }
+    Ok(prime_parts)
 }
 
 #[pyfunction]
 #[pyo3(name = "p_minus_one")]
+#[pyo3(signature=(n, b1, verbose = false, b2 = 0))]
 /// python wrapper for p_minus_one
-pub fn py_p_minus_one(n: u128, max_prime: u128, verbose: bool)-> PyResult<Vec<u128>> {
-    let res = p_minus_one(n, max_prime, verbose);
+pub fn py_p_minus_one(n: BigInt, b1: u64, verbose: bool, b2: u64)-> PyResult<Vec<BigInt>> {
+    let res = p_minus_one(&n, b1, b2, verbose);
     match res {
         Ok(vec) => Ok(vec),
-        Err(e) => Err(PyArithmeticError::new_err(e))
+        Err(e) => Err(PyArithmeticError::new_err(e.to_string()))
     }
 }
 
@@ -211,3 +302,42 @@ pub fn alt_gcd(mut a: u128, mut b: u128) -> u128 {
     }
     return a;
 }
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+pub mod test {
+
+    use super::*;
+
+    #[test]
+    fn test_p_minus_one() {
+        // 1733 - 1 = 2^2 * 433 is not smooth, 2003 - 1 = 2 * 7 * 11 * 13 is.
+        let n = BigInt::from(1733 * 2003);
+        assert_eq!(p_minus_one_factor(&n, 20, 0, true), Ok(BigInt::from(2003)));
+        assert_eq!(p_minus_one(&BigInt::from(360), 20, 0, false),
+            Ok(vec![BigInt::from(2), BigInt::from(3), BigInt::from(5)]));
+        assert_eq!(p_minus_one(&BigInt::from(1733), 20, 0, false), Ok(vec![BigInt::from(1733)]));
+        assert!(matches!(p_minus_one(&BigInt::from(2), 20, 0, false), Err(PM1Error::NTooSmall(_))));
+    }
+
+    #[test]
+    fn test_p_minus_one_stage2() {
+        // 1733 - 1 = 2^2 * 433, 1709 - 1 = 2^2 * 7 * 61
+        let n = BigInt::from(1733 * 1709);
+        assert_eq!(p_minus_one_factor(&n, 10, 0, false),
+            Err(PM1Error::NoFactor { n: n.clone(), b1: 10, b2: 0 }));
+        assert_eq!(p_minus_one_factor(&n, 10, 100, true), Ok(BigInt::from(1709)));
+        assert_eq!(p_minus_one(&n, 10, 500, false), Ok(vec![BigInt::from(1709), BigInt::from(1733)]));
+    }
+
+    #[test]
+    fn test_p_minus_one_big() {
+        // p - 1 = 2^5 * 3^2 * 5^2 * 7 * 11 * 13 * 17 * 19 * 23 * 29 * 31 * 37 * 41 * 43 * 47, q - 1 is not smooth
+        let p = BigInt::from(2u64.pow(5) * 3u64.pow(2) * 5u64.pow(2) * 7 * 11 * 13 * 17 * 19 * 23 * 29 * 31 * 37) * 41 * 43 * 47 + 1;
+        assert!(is_prime(&p, false));
+        let q = BigInt::from(1099511627791u64);
+        let n = &p * &q;
+        assert_eq!(p_minus_one_factor(&n, 50, 0, false), Ok(p));
+    }
+}
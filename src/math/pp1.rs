@@ -0,0 +1,258 @@
+#![allow(dead_code)]
+/// Williams p plus 1 method
+///
+/// Determine the prime factors of a number with the p plus 1 method.
+/// Works like the [p minus 1 method](super::pm1), but finds a prime factor p of n if p + 1 is
+/// smooth, even if p - 1 is not.
+///
+/// Instead of a^k, the Lucas sequence V_k(A) with V_0 = 2, V_1 = A and V_j = A * V_(j-1) - V_(j-2)
+/// is calculated mod n. If A² - 4 is a quadratic non residue mod p and p + 1 divides k, then
+/// V_k(A) = 2 (mod p). As that can not be known beforehand, multiple values for A are tried.
+///
+/// Author:     Christoph J. Scherr <software@cscherr.de>
+/// License:    MIT
+/// Source:     <https://git.cscherr.de/PlexSheep/plexcryptool/>
+
+use pyo3::{prelude::*, exceptions::PyArithmeticError};
+
+use core::fmt;
+
+use num::Integer;
+use num_bigint::BigInt;
+use num_traits::{One, Zero};
+
+use primes::{Sieve, PrimeSet};
+
+use crate::math::primality::is_prime;
+use crate::math::pm1::max_prime_power;
+use crate::cplex::printing::seperator;
+
+/// starting values A that are tried one after another
+const STARTING_VALUES: [u64; 8] = [3, 4, 5, 6, 7, 8, 9, 10];
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// errors for the p plus 1 method
+pub enum PP1Error {
+    /// n is too small to be factorised
+    NTooSmall(BigInt),
+    /// the bounds are not usable
+    InvalidBounds {
+        /// stage 1 bound
+        b1: u64,
+        /// stage 2 bound
+        b2: u64
+    },
+    /// gcd(V_k(A) - 2, n) was 1 or n for every starting value A
+    NoFactor {
+        /// the number that could not be factorised
+        n: BigInt,
+        /// stage 1 bound
+        b1: u64,
+        /// stage 2 bound
+        b2: u64
+    },
+}
+
+impl fmt::Display for PP1Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PP1Error::NTooSmall(n) => write!(f, "n too small: {n}"),
+            PP1Error::InvalidBounds { b1, b2 } => {
+                write!(f, "invalid bounds: B1 = {b1}, B2 = {b2}")
+            }
+            PP1Error::NoFactor { n, b1, b2 } => {
+                write!(f, "P plus one does not work for {n} with B1 = {b1}, B2 = {b2}. \
+                Use another algorithm or choose other bounds.")
+            }
+        }
+    }
+}
+
+/// calculate the Lucas sequence element V_m(a) mod n
+///
+/// Uses a ladder over the bits of m that keeps the pair (V_j, V_(j+1)):
+/// V_2j = V_j² - 2 and V_(2j+1) = V_j * V_(j+1) - a
+pub fn lucas_v(a: &BigInt, m: &BigInt, n: &BigInt, verbose: bool) -> BigInt {
+    if m.is_zero() {
+        return BigInt::from(2);
+    }
+    let mut x = a.mod_floor(n);
+    let mut y: BigInt = (a * a - 2u8).mod_floor(n);
+    let mut j = BigInt::one();
+    for index in (0..m.bits() - 1).rev() {
+        if m.bit(index) {
+            x = (&x * &y - a).mod_floor(n);
+            y = (&y * &y - 2u8).mod_floor(n);
+            j = 2 * j + 1;
+        }
+        else {
+            y = (&x * &y - a).mod_floor(n);
+            x = (&x * &x - 2u8).mod_floor(n);
+            j *= 2;
+        }
+        if verbose {
+            println!("{} -> V_{j} = {x}, V_{} = {y}", m.bit(index) as u8, &j + 1);
+        }
+    }
+    x
+}
+
+/// find a single nontrivial factor of n
+pub fn p_plus_one_factor(n: &BigInt, b1: u64, b2: u64, verbose: bool) -> Result<BigInt, PP1Error> {
+    if *n < BigInt::from(4) {
+        return Err(PP1Error::NTooSmall(n.clone()));
+    }
+    if b1 < 2 {
+        return Err(PP1Error::InvalidBounds { b1, b2 });
+    }
+    if n.is_even() {
+        return Ok(BigInt::from(2));
+    }
+    let stage1_primes: Vec<u64> = Sieve::new().iter().take_while(|p| *p <= b1).collect();
+    let stage2_primes: Vec<u64> = Sieve::new().iter()
+        .skip_while(|p| *p <= b1)
+        .take_while(|p| *p <= b2)
+        .collect();
+    for start in STARTING_VALUES {
+        let mut v = BigInt::from(start);
+        if verbose {
+            seperator();
+            println!("stage 1 with: A={v}, B1={b1}, n={n}");
+        }
+        let mut g = BigInt::one();
+        for prime in &stage1_primes {
+            let pe = max_prime_power(*prime, b1);
+            if verbose {
+                println!("V_{pe}(V) with V = {v}:");
+            }
+            v = lucas_v(&v, &BigInt::from(pe), n, verbose);
+            g = (&v - 2u8).gcd(n);
+            if verbose {
+                println!("g = gcd(V - 2, n) = gcd({}, {n}) = {g}", &v - 2u8);
+            }
+            if !g.is_one() {
+                break;
+            }
+        }
+        if &g == n {
+            if verbose {
+                println!("g = {g} = {n} = n, bad A, trying the next one");
+            }
+            continue;
+        }
+        if !g.is_one() {
+            return Ok(g);
+        }
+        if !stage2_primes.is_empty() {
+            if verbose {
+                seperator();
+                println!("stage 2 with: B1={b1}, B2={b2}");
+            }
+            let mut q_prod = BigInt::one();
+            for q in &stage2_primes {
+                let vq = lucas_v(&v, &BigInt::from(*q), n, false);
+                q_prod = (q_prod * (vq - 2u8)).mod_floor(n);
+            }
+            g = q_prod.gcd(n);
+            if verbose {
+                println!("Q = prod(V_q(V) - 2) = {q_prod} (mod {n})");
+                println!("g = gcd(Q, n) = {g}");
+            }
+            if !g.is_one() && &g != n {
+                return Ok(g);
+            }
+        }
+        if verbose {
+            println!("no factor found with A={start}");
+        }
+    }
+    Err(PP1Error::NoFactor { n: n.clone(), b1, b2 })
+}
+
+/// excecute the p plus one calculation
+///
+/// returns the distinct prime factors of n in ascending order. Stage 2 is skipped if b2 <= b1.
+pub fn p_plus_one(n: &BigInt, b1: u64, b2: u64, verbose: bool) -> Result<Vec<BigInt>, PP1Error> {
+    if *n < BigInt::from(3) {
+        return Err(PP1Error::NTooSmall(n.clone()));
+    }
+    let mut prime_parts: Vec<BigInt> = Vec::new();
+    let mut composites: Vec<BigInt> = vec![n.clone()];
+    while let Some(m) = composites.pop() {
+        if m.is_one() || m.is_zero() {
+            continue;
+        }
+        if is_prime(&m, false) {
+            if verbose {
+                println!("{m} is prime");
+            }
+            prime_parts.push(m);
+            continue;
+        }
+        let g = p_plus_one_factor(&m, b1, b2, verbose)?;
+        if verbose {
+            println!("{m} = {g} * {}", &m / &g);
+        }
+        composites.push(&m / &g);
+        composites.push(g);
+    }
+    prime_parts.sort();
+    prime_parts.dedup();
+    if verbose {
+        seperator();
+    }
+    Ok(prime_parts)
+}
+
+#[pyfunction]
+#[pyo3(name = "p_plus_one")]
+#[pyo3(signature=(n, b1, verbose = false, b2 = 0))]
+/// python wrapper for p_plus_one
+pub fn py_p_plus_one(n: BigInt, b1: u64, verbose: bool, b2: u64)-> PyResult<Vec<BigInt>> {
+    match p_plus_one(&n, b1, b2, verbose) {
+        Ok(vec) => Ok(vec),
+        Err(e) => Err(PyArithmeticError::new_err(e.to_string()))
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+pub mod test {
+
+    use super::*;
+
+    use crate::math::pm1::p_minus_one_factor;
+
+    #[test]
+    fn test_lucas_v() {
+        // V_j(3) = 2, 3, 7, 18, 47, 123, 322, 843, ...
+        let seq = [2, 3, 7, 18, 47, 123, 322, 843];
+        for (j, vj) in seq.iter().enumerate() {
+            assert_eq!(lucas_v(&BigInt::from(3), &BigInt::from(j), &BigInt::from(1000), true), BigInt::from(*vj));
+        }
+    }
+
+    #[test]
+    fn test_p_plus_one() {
+        // 100547 + 1 = 2^2 * 3^3 * 7^2 * 19, but 100547 - 1 has a large prime factor
+        // 1000039 + 1 and 1000039 - 1 both have large prime factors
+        let p = BigInt::from(100547);
+        let q = BigInt::from(1000039);
+        let n = &p * &q;
+        assert!(p_minus_one_factor(&n, 50, 0, false).is_err());
+        assert_eq!(p_plus_one_factor(&n, 50, 0, true), Ok(p.clone()));
+        assert_eq!(p_plus_one(&n, 50, 0, false), Ok(vec![p.clone(), q.clone()]));
+        assert_eq!(p_plus_one_factor(&n, 10, 100, false), Err(PP1Error::NoFactor { n: n.clone(), b1: 10, b2: 100 }));
+    }
+
+    #[test]
+    fn test_p_plus_one_stage2() {
+        // 100151 + 1 = 2^3 * 3^2 * 13 * 107
+        let p = BigInt::from(100151);
+        let n = &p * BigInt::from(1000039);
+        assert_eq!(p_plus_one_factor(&n, 20, 0, false), Err(PP1Error::NoFactor { n: n.clone(), b1: 20, b2: 0 }));
+        assert_eq!(p_plus_one_factor(&n, 20, 110, true), Ok(p));
+    }
+}
@@ -0,0 +1,403 @@
+#![allow(dead_code)]
+/// primality tests
+///
+/// Implements trial division, the Miller-Rabin test and the Baillie-PSW test.
+///
+/// For numbers below 3.317 * 10^24 (that includes all u64), Miller-Rabin with the first thirteen
+/// primes as bases is deterministic. Larger numbers are tested with Baillie-PSW (a strong probable prime test to
+/// base 2 followed by a strong Lucas probable prime test), for which no counterexample is known.
+/// Miller-Rabin with random bases is also available for BigInts.
+///
+/// Author:     Christoph J. Scherr <software@cscherr.de>
+/// License:    MIT
+/// Source:     <https://git.cscherr.de/PlexSheep/plexcryptool/>
+
+use crate::cplex::printing::seperator;
+use crate::math::modexp;
+use crate::math::jacobi::jacobi;
+
+use num::Integer;
+use num_bigint::{BigInt, RandBigInt};
+use num_traits::{One, Zero, Signed, ToPrimitive};
+
+use pyo3::prelude::*;
+
+use primes::{Sieve, PrimeSet};
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// bases that make Miller-Rabin deterministic for all n < [DETERMINISTIC_LIMIT]
+pub const DETERMINISTIC_BASES: [u64; 13] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41];
+/// smallest strong pseudoprime to all [DETERMINISTIC_BASES], about 3.317 * 10^24
+pub const DETERMINISTIC_LIMIT: u128 = 3317044064679887385961981;
+/// primes up to this limit are used in trial division before the more expensive tests
+pub const TRIAL_DIVISION_LIMIT: u64 = 1000;
+/// default amount of rounds for Miller-Rabin with random bases
+pub const DEFAULT_ROUNDS: usize = 20;
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// find the smallest prime factor of n that is not bigger than limit
+///
+/// returns None if there is no such factor. n itself is not considered a factor.
+pub fn trial_division(n: &BigInt, limit: u64, verbose: bool) -> Option<u64> {
+    let mut pset = Sieve::new();
+    for prime in pset.iter() {
+        if prime > limit {
+            break;
+        }
+        let p = BigInt::from(prime);
+        if &p * &p > *n {
+            break;
+        }
+        if n.is_multiple_of(&p) {
+            if verbose {
+                println!("{n} = {prime} * {}", n / &p);
+            }
+            return Some(prime);
+        }
+    }
+    if verbose {
+        println!("no prime factor <= {limit} found for {n}");
+    }
+    None
+}
+
+/// check if a single base a proves that n is composite
+///
+/// n must be odd and > 3. Returns true if a is a witness for the compositeness of n, false if
+/// n is a strong probable prime to base a.
+pub fn miller_rabin_witness(n: &BigInt, a: &BigInt, verbose: bool) -> bool {
+    let one = BigInt::one();
+    let nm1: BigInt = n - 1;
+    // n - 1 = 2^s * d with d odd
+    let s: u64 = nm1.trailing_zeros().expect("n - 1 can not be zero");
+    let d: BigInt = &nm1 >> s;
+    let mut x = modexp::modular_exponentiation(a.clone(), d.clone(), n.clone(), false);
+    if verbose {
+        println!("n-1 = {nm1} = 2^{s} * {d}");
+        println!("x_0 = a^d = {a}^{d} = {x} (mod {n})");
+    }
+    if x == one || x == nm1 {
+        if verbose {
+            println!("x_0 = ±1 => {n} is a strong probable prime to base {a}");
+        }
+        return false;
+    }
+    for r in 1..s {
+        x = x.pow(2) % n;
+        if verbose {
+            println!("x_{r} = x_{}² = {x} (mod {n})", r - 1);
+        }
+        if x == nm1 {
+            if verbose {
+                println!("x_{r} = -1 => {n} is a strong probable prime to base {a}");
+            }
+            return false;
+        }
+        if x == one {
+            // nontrivial root of 1 found, -1 can not follow anymore
+            break;
+        }
+    }
+    if verbose {
+        println!("{a} is a witness: {n} is composite");
+    }
+    true
+}
+
+/// Miller-Rabin test with the given bases
+///
+/// Only use this for numbers > 3, use [is_prime] otherwise.
+pub fn miller_rabin_bases(n: &BigInt, bases: &[BigInt], verbose: bool) -> bool {
+    if n.is_even() {
+        if verbose {
+            println!("{n} is even");
+        }
+        return false;
+    }
+    let nm1: BigInt = n - 1;
+    for a in bases {
+        // bases >= n - 1 are not meaningful, they are equivalent to a smaller base or -1
+        let a = a.mod_floor(n);
+        if a < BigInt::from(2) || a >= nm1 {
+            continue;
+        }
+        if verbose {
+            seperator();
+            println!("testing base a = {a}");
+        }
+        if miller_rabin_witness(n, &a, verbose) {
+            return false;
+        }
+    }
+    if verbose {
+        seperator();
+        println!("{n} is a strong probable prime to all bases");
+    }
+    true
+}
+
+/// deterministic Miller-Rabin test for u64
+pub fn miller_rabin_u64(n: u64, verbose: bool) -> bool {
+    miller_rabin_u128(n as u128, verbose)
+}
+
+/// Miller-Rabin test with the [DETERMINISTIC_BASES]
+///
+/// The result is only guaranteed for n < [DETERMINISTIC_LIMIT], use [bpsw] for larger numbers.
+pub fn miller_rabin_u128(n: u128, verbose: bool) -> bool {
+    if n < 4 {
+        return n > 1;
+    }
+    let bases: Vec<BigInt> = DETERMINISTIC_BASES.iter().map(|b| BigInt::from(*b)).collect();
+    miller_rabin_bases(&BigInt::from(n), &bases, verbose)
+}
+
+/// Miller-Rabin test with random bases
+///
+/// The chance of a composite n passing is at most 4^(-rounds).
+pub fn miller_rabin(n: &BigInt, rounds: usize, verbose: bool) -> bool {
+    if *n < BigInt::from(4) {
+        return *n > BigInt::one();
+    }
+    if n.is_even() {
+        return false;
+    }
+    let mut rng = rand::thread_rng();
+    let low = BigInt::from(2);
+    let high: BigInt = n - 1;
+    let bases: Vec<BigInt> = (0..rounds).map(|_| rng.gen_bigint_range(&low, &high)).collect();
+    miller_rabin_bases(n, &bases, verbose)
+}
+
+/// halve x modulo an odd n
+fn half_mod(x: BigInt, n: &BigInt) -> BigInt {
+    let x = if x.is_odd() { x + n } else { x };
+    let x: BigInt = x >> 1;
+    x.mod_floor(n)
+}
+
+/// strong Lucas probable prime test with the parameters of Selfridge
+///
+/// n must be odd, > 3 and must not be a perfect square.
+pub fn strong_lucas(n: &BigInt, verbose: bool) -> bool {
+    // find the first D in 5, -7, 9, -11, ... with (D/n) = -1
+    let mut d = BigInt::from(5);
+    loop {
+        let j = jacobi(&d, n, false).expect("n is odd and positive");
+        if j == -1 {
+            break;
+        }
+        if j == 0 && d.abs() != *n {
+            if verbose {
+                println!("gcd({d}, {n}) > 1 => {n} is composite");
+            }
+            return false;
+        }
+        d = if d.is_positive() { -(d + 2u8) } else { -(d - 2u8) };
+    }
+    let p = BigInt::one();
+    let q: BigInt = (1 - &d) / 4;
+    if verbose {
+        println!("Selfridge parameters: D = {d}, P = {p}, Q = {q}");
+    }
+    // n + 1 = 2^s * k with k odd
+    let np1: BigInt = n + 1;
+    let s: u64 = np1.trailing_zeros().expect("n + 1 can not be zero");
+    let k: BigInt = &np1 >> s;
+    if verbose {
+        println!("n+1 = {np1} = 2^{s} * {k}");
+    }
+
+    // compute U_k and V_k from the most significant bit downwards
+    let mut u = BigInt::one();
+    let mut v = p.clone();
+    let mut qk = q.mod_floor(n);
+    for index in (0..k.bits() - 1).rev() {
+        // double
+        u = (&u * &v).mod_floor(n);
+        v = (&v * &v - &qk * 2u8).mod_floor(n);
+        qk = (&qk * &qk).mod_floor(n);
+        if k.bit(index) {
+            // increment
+            let nu = half_mod(&p * &u + &v, n);
+            let nv = half_mod(&d * &u + &p * &v, n);
+            u = nu;
+            v = nv;
+            qk = (&qk * &q).mod_floor(n);
+        }
+    }
+    if verbose {
+        println!("U_k = {u}, V_k = {v} (mod {n})");
+    }
+    if u.is_zero() || v.is_zero() {
+        if verbose {
+            println!("U_k = 0 or V_k = 0 => {n} is a strong Lucas probable prime");
+        }
+        return true;
+    }
+    for r in 1..s {
+        v = (&v * &v - &qk * 2u8).mod_floor(n);
+        qk = (&qk * &qk).mod_floor(n);
+        if verbose {
+            println!("V_(k*2^{r}) = {v} (mod {n})");
+        }
+        if v.is_zero() {
+            if verbose {
+                println!("V_(k*2^{r}) = 0 => {n} is a strong Lucas probable prime");
+            }
+            return true;
+        }
+    }
+    if verbose {
+        println!("{n} is not a strong Lucas probable prime => composite");
+    }
+    false
+}
+
+/// Baillie-PSW primality test
+///
+/// No composite number passing this test is known.
+pub fn bpsw(n: &BigInt, verbose: bool) -> bool {
+    if *n < BigInt::from(4) {
+        return *n > BigInt::one();
+    }
+    if n.is_even() {
+        return false;
+    }
+    if verbose {
+        println!("Baillie-PSW: Miller-Rabin with base 2");
+    }
+    if !miller_rabin_bases(n, &[BigInt::from(2)], verbose) {
+        return false;
+    }
+    let root = n.sqrt();
+    if &root * &root == *n {
+        if verbose {
+            println!("{n} = {root}² is a perfect square");
+        }
+        return false;
+    }
+    if verbose {
+        seperator();
+        println!("Baillie-PSW: strong Lucas test");
+    }
+    strong_lucas(n, verbose)
+}
+
+/// check if n is prime
+///
+/// uses trial division for small factors, deterministic Miller-Rabin for n < [DETERMINISTIC_LIMIT]
+/// and Baillie-PSW for anything larger.
+pub fn is_prime(n: &BigInt, verbose: bool) -> bool {
+    if *n < BigInt::from(2) {
+        if verbose {
+            println!("{n} < 2 is not prime");
+        }
+        return false;
+    }
+    if let Some(factor) = trial_division(n, TRIAL_DIVISION_LIMIT, verbose) {
+        if verbose {
+            println!("{factor} divides {n} => composite");
+        }
+        return false;
+    }
+    if *n < BigInt::from(TRIAL_DIVISION_LIMIT * TRIAL_DIVISION_LIMIT) {
+        if verbose {
+            println!("no factor <= sqrt({n}) => prime");
+        }
+        return true;
+    }
+    match n.to_u128().filter(|small| *small < DETERMINISTIC_LIMIT) {
+        Some(small) => miller_rabin_u128(small, verbose),
+        None => bpsw(n, verbose)
+    }
+}
+
+/// check if a u128 is prime
+pub fn is_prime_u128(n: u128) -> bool {
+    is_prime(&BigInt::from(n), false)
+}
+
+#[pyfunction]
+#[pyo3(name="is_prime")]
+#[pyo3(signature=(n, verbose = false))]
+/// python wrapper for is_prime
+pub fn py_is_prime(n: BigInt, verbose: bool) -> bool {
+    is_prime(&n, verbose)
+}
+
+#[pyfunction]
+#[pyo3(name="miller_rabin")]
+#[pyo3(signature=(n, rounds = DEFAULT_ROUNDS, verbose = false))]
+/// python wrapper for miller_rabin
+pub fn py_miller_rabin(n: BigInt, rounds: usize, verbose: bool) -> bool {
+    miller_rabin(&n, rounds, verbose)
+}
+
+#[pyfunction]
+#[pyo3(name="bpsw")]
+#[pyo3(signature=(n, verbose = false))]
+/// python wrapper for bpsw
+pub fn py_bpsw(n: BigInt, verbose: bool) -> bool {
+    bpsw(&n, verbose)
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+pub mod test {
+
+    use super::*;
+
+    use std::str::FromStr;
+
+    #[test]
+    fn test_is_prime_small() {
+        let primes: Vec<u64> = Sieve::new().iter().take_while(|p| *p < 20000).collect();
+        for n in 0..20000u64 {
+            assert_eq!(is_prime(&BigInt::from(n), false), primes.contains(&n), "wrong result for {n}");
+        }
+    }
+
+    #[test]
+    fn test_pseudoprimes() {
+        // carmichael numbers and strong pseudoprimes to base 2
+        for n in [561u64, 1105, 1729, 2047, 3277, 4033, 4681, 8321, 3215031751, 3825123056546413051] {
+            assert!(!is_prime(&BigInt::from(n), false), "{n} is composite");
+            assert!(!bpsw(&BigInt::from(n), false), "{n} is composite");
+        }
+        // strong pseudoprime to base 2 and 3
+        assert!(!miller_rabin_u64(1373653, true));
+        // strong pseudoprime to the first 12 prime bases, but larger than u64
+        let psp = BigInt::from_str("318665857834031151167461").unwrap();
+        assert!(!is_prime(&psp, false));
+        assert!(!miller_rabin_u128(318665857834031151167461, false));
+        // strong pseudoprime to all deterministic bases, left to Baillie-PSW
+        assert!(miller_rabin_u128(DETERMINISTIC_LIMIT, false));
+        assert!(!is_prime(&BigInt::from(DETERMINISTIC_LIMIT), false));
+    }
+
+    #[test]
+    fn test_is_prime_big() {
+        // 2^127 - 1 and 2^521 - 1 are mersenne primes
+        assert!(is_prime(&((BigInt::one() << 127) - 1), false));
+        assert!(is_prime(&((BigInt::one() << 521) - 1), false));
+        assert!(!is_prime(&((BigInt::one() << 128) + 1), false));
+        assert!(is_prime_u128(18446744073709551557));
+        assert!(!is_prime_u128(18446744073709551557 * 3));
+        assert!(miller_rabin(&((BigInt::one() << 127) - 1), DEFAULT_ROUNDS, false));
+        // product of two 64 bit primes
+        let n = BigInt::from(18446744073709551557u128) * BigInt::from(18446744073709551533u128);
+        assert!(!is_prime(&n, false));
+        assert!(!miller_rabin(&n, DEFAULT_ROUNDS, false));
+    }
+
+    #[test]
+    fn test_trial_division() {
+        assert_eq!(trial_division(&BigInt::from(3603234), 1000, true), Some(2));
+        assert_eq!(trial_division(&BigInt::from(223 * 2693), 1000, true), Some(223));
+        assert_eq!(trial_division(&BigInt::from(2693), 1000, true), None);
+    }
+}
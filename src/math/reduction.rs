@@ -0,0 +1,615 @@
+#![allow(dead_code)]
+/// modular reduction contexts
+///
+/// Repeated multiplications modulo the same n, like in an exponentiation, can reuse constants
+/// that only depend on n. Every context works on its own representation of the residues: convert
+/// with enter, multiply and square as often as needed and convert back with leave.
+///
+/// - plain: (a * b) mod n with a full division every time, the representation is a itself
+/// - barrett: precomputes μ = floor(4^k / n) for a k bit n and replaces the division by two
+///   multiplications and shifts: q = ((x >> (k-1)) * μ) >> (k+1), r = x - q * n, then at most
+///   two subtractions of n. The representation is a itself. Without truncated products the two
+///   full multiplications are slower than the division of num-bigint, so this is only used when
+///   asked for.
+/// - montgomery: for odd n and R = 2^(64s) > n, represents a as aR mod n. The product of aR and
+///   bR is reduced with REDC(T) = T * R^-1 mod n, which only needs multiplications, the lowest
+///   64 bit word and shifts by whole words. This works on 64 bit limbs, first the full product,
+///   then the reduction word by word (separated operand scanning).
+///
+/// Author:     Christoph J. Scherr <software@cscherr.de>
+/// License:    MIT
+/// Source:     <https://git.cscherr.de/PlexSheep/plexcryptool/>
+
+use core::fmt;
+use std::cmp::Ordering;
+
+use num::Integer;
+use num_bigint::{BigInt, BigUint};
+use num_traits::{One, Signed};
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// errors for reduction contexts
+pub enum ReductionError {
+    /// the modulus must be positive
+    InvalidModulus(BigInt),
+    /// montgomery reduction needs an odd modulus
+    EvenModulus(BigInt),
+}
+
+impl fmt::Display for ReductionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReductionError::InvalidModulus(n) => write!(f, "the modulus must be positive, not {n}"),
+            ReductionError::EvenModulus(n) => write!(f, "montgomery reduction needs an odd modulus, {n} is even"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// the available reduction contexts
+pub enum ReductionKind {
+    /// a full division for every reduction
+    Plain,
+    /// barrett reduction
+    Barrett,
+    /// montgomery multiplication, odd moduli only
+    Montgomery,
+}
+
+impl ReductionKind {
+    /// get a kind by name, like "montgomery"
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "plain" => Some(ReductionKind::Plain),
+            "barrett" => Some(ReductionKind::Barrett),
+            "montgomery" => Some(ReductionKind::Montgomery),
+            _ => None
+        }
+    }
+}
+
+impl fmt::Display for ReductionKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReductionKind::Plain => write!(f, "plain"),
+            ReductionKind::Barrett => write!(f, "barrett"),
+            ReductionKind::Montgomery => write!(f, "montgomery"),
+        }
+    }
+}
+
+/// multiplication modulo a fixed n in some representation
+pub trait Reduction {
+    /// the modulus n
+    fn modulus(&self) -> &BigInt;
+    /// convert any integer into the representation
+    fn enter(&self, a: &BigInt) -> BigInt;
+    /// convert back to the least nonnegative residue
+    fn leave(&self, a: &BigInt) -> BigInt;
+    /// the representation of 1
+    fn one(&self) -> BigInt;
+    /// multiply two represented numbers
+    fn mul(&self, a: &BigInt, b: &BigInt) -> BigInt;
+    /// square a represented number
+    fn sqr(&self, a: &BigInt) -> BigInt {
+        self.mul(a, a)
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// reduction with a division for every product
+pub struct Plain {
+    /// the modulus
+    n: BigInt,
+}
+
+impl Plain {
+    /// make a context for n
+    pub fn new(n: &BigInt) -> Result<Self, ReductionError> {
+        if !n.is_positive() {
+            return Err(ReductionError::InvalidModulus(n.clone()));
+        }
+        Ok(Plain { n: n.clone() })
+    }
+}
+
+impl Reduction for Plain {
+    fn modulus(&self) -> &BigInt {
+        &self.n
+    }
+
+    fn enter(&self, a: &BigInt) -> BigInt {
+        a.mod_floor(&self.n)
+    }
+
+    fn leave(&self, a: &BigInt) -> BigInt {
+        a.clone()
+    }
+
+    fn one(&self) -> BigInt {
+        BigInt::one() % &self.n
+    }
+
+    fn mul(&self, a: &BigInt, b: &BigInt) -> BigInt {
+        (a * b) % &self.n
+    }
+
+    fn sqr(&self, a: &BigInt) -> BigInt {
+        a.pow(2) % &self.n
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// barrett reduction
+pub struct Barrett {
+    /// the modulus
+    n: BigInt,
+    /// bit length of n
+    k: u64,
+    /// floor(4^k / n)
+    mu: BigInt,
+}
+
+impl Barrett {
+    /// make a context for n
+    pub fn new(n: &BigInt) -> Result<Self, ReductionError> {
+        if !n.is_positive() {
+            return Err(ReductionError::InvalidModulus(n.clone()));
+        }
+        let k = n.bits();
+        let mu = (BigInt::one() << (2 * k)) / n;
+        Ok(Barrett { n: n.clone(), k, mu })
+    }
+
+    /// reduce 0 <= x < 4^k
+    pub fn reduce(&self, x: &BigInt) -> BigInt {
+        let q: BigInt = ((x >> (self.k - 1)) * &self.mu) >> (self.k + 1);
+        let mut r: BigInt = x - q * &self.n;
+        while r >= self.n {
+            r -= &self.n;
+        }
+        r
+    }
+}
+
+impl Reduction for Barrett {
+    fn modulus(&self) -> &BigInt {
+        &self.n
+    }
+
+    fn enter(&self, a: &BigInt) -> BigInt {
+        a.mod_floor(&self.n)
+    }
+
+    fn leave(&self, a: &BigInt) -> BigInt {
+        a.clone()
+    }
+
+    fn one(&self) -> BigInt {
+        BigInt::one() % &self.n
+    }
+
+    fn mul(&self, a: &BigInt, b: &BigInt) -> BigInt {
+        self.reduce(&(a * b))
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// montgomery multiplication
+pub struct Montgomery {
+    /// the modulus
+    n: BigInt,
+    /// the limbs of n, least significant first
+    limbs: Vec<u64>,
+    /// -n^-1 mod 2^64
+    n0_inv: u64,
+    /// R^2 mod n as limbs
+    r2: Vec<u64>,
+    /// R mod n, the representation of 1
+    r1: BigInt,
+}
+
+impl Montgomery {
+    /// make a context for an odd n
+    pub fn new(n: &BigInt) -> Result<Self, ReductionError> {
+        if !n.is_positive() {
+            return Err(ReductionError::InvalidModulus(n.clone()));
+        }
+        if n.is_even() {
+            return Err(ReductionError::EvenModulus(n.clone()));
+        }
+        let limbs = n.magnitude().to_u64_digits();
+        let s = limbs.len();
+        // newton iteration for n_0^-1 mod 2^64, every step doubles the correct bits
+        let mut inv: u64 = 1;
+        for _ in 0..6 {
+            inv = inv.wrapping_mul(2u64.wrapping_sub(limbs[0].wrapping_mul(inv)));
+        }
+        let r1: BigInt = (BigInt::one() << (64 * s)) % n;
+        let r2: BigInt = (BigInt::one() << (128 * s)) % n;
+        let mut ctx = Montgomery { n: n.clone(), limbs, n0_inv: inv.wrapping_neg(), r2: Vec::new(), r1 };
+        ctx.r2 = ctx.to_limbs(&r2);
+        Ok(ctx)
+    }
+
+    /// the limbs of 0 <= a < n, padded to the length of n
+    fn to_limbs(&self, a: &BigInt) -> Vec<u64> {
+        let mut limbs = a.magnitude().to_u64_digits();
+        limbs.resize(self.limbs.len(), 0);
+        limbs
+    }
+
+    /// make a number from limbs
+    fn from_limbs(limbs: &[u64]) -> BigInt {
+        let digits: Vec<u32> = limbs.iter().flat_map(|l| [*l as u32, (l >> 32) as u32]).collect();
+        BigInt::from(BigUint::from_slice(&digits))
+    }
+
+    /// the full product a * b with 2s + 1 limbs, the top one stays free for redc_limbs
+    fn product(a: &[u64], b: &[u64]) -> Vec<u64> {
+        let s = a.len();
+        let mut t: Vec<u64> = vec![0; 2 * s + 1];
+        for (i, &ai) in a.iter().enumerate() {
+            let mut carry: u128 = 0;
+            for (tj, &bj) in t[i..i + s].iter_mut().zip(b) {
+                let x = *tj as u128 + (ai as u128) * (bj as u128) + carry;
+                *tj = x as u64;
+                carry = x >> 64;
+            }
+            t[i + s] = carry as u64;
+        }
+        t
+    }
+
+    /// the full square a^2, every product a_i * a_j with i != j is computed only once
+    fn square(a: &[u64]) -> Vec<u64> {
+        let s = a.len();
+        let mut t: Vec<u64> = vec![0; 2 * s + 1];
+        for (i, &ai) in a.iter().enumerate() {
+            let mut carry: u128 = 0;
+            for (tj, &aj) in t[2 * i + 1..i + s].iter_mut().zip(&a[i + 1..]) {
+                let x = *tj as u128 + (ai as u128) * (aj as u128) + carry;
+                *tj = x as u64;
+                carry = x >> 64;
+            }
+            t[i + s] = carry as u64;
+        }
+        // double the off diagonal part and add the squares a_i^2
+        let mut top: u64 = 0;
+        for tj in t[..2 * s].iter_mut() {
+            let next = *tj >> 63;
+            *tj = (*tj << 1) | top;
+            top = next;
+        }
+        let mut carry: u128 = 0;
+        for (i, &ai) in a.iter().enumerate() {
+            let sq = (ai as u128) * (ai as u128);
+            let x = t[2 * i] as u128 + (sq as u64) as u128 + carry;
+            t[2 * i] = x as u64;
+            let x = t[2 * i + 1] as u128 + (sq >> 64) + (x >> 64);
+            t[2 * i + 1] = x as u64;
+            carry = x >> 64;
+        }
+        t
+    }
+
+    /// T * R^-1 mod n for T < n * R given as 2s + 1 limbs
+    ///
+    /// every step adds m * n * 2^(64i) with m chosen so that the limb i becomes 0, after s steps
+    /// the upper half is congruent to T * R^-1 and smaller than 2n.
+    fn redc_limbs(&self, mut t: Vec<u64>) -> Vec<u64> {
+        let n = &self.limbs;
+        let s = n.len();
+        for i in 0..s {
+            let m = t[i].wrapping_mul(self.n0_inv) as u128;
+            let mut carry: u128 = 0;
+            for (tj, &nj) in t[i..i + s].iter_mut().zip(n) {
+                let x = *tj as u128 + m * (nj as u128) + carry;
+                *tj = x as u64;
+                carry = x >> 64;
+            }
+            for tj in t[i + s..].iter_mut() {
+                if carry == 0 {
+                    break;
+                }
+                let x = *tj as u128 + carry;
+                *tj = x as u64;
+                carry = x >> 64;
+            }
+        }
+        let mut r = t.split_off(s);
+        // r < 2n, subtract n once if needed
+        if r[s] != 0 || compare(&r[..s], n) != Ordering::Less {
+            let mut borrow = false;
+            for (rj, &nj) in r.iter_mut().zip(n) {
+                let (d, b1) = rj.overflowing_sub(nj);
+                let (d, b2) = d.overflowing_sub(borrow as u64);
+                *rj = d;
+                borrow = b1 || b2;
+            }
+        }
+        r.truncate(s);
+        r
+    }
+
+    /// a * b * R^-1 mod n
+    fn mont_mul(&self, a: &[u64], b: &[u64]) -> Vec<u64> {
+        self.redc_limbs(Montgomery::product(a, b))
+    }
+
+    /// REDC for a represented number, multiplies with R^-1
+    pub fn redc(&self, a: &BigInt) -> BigInt {
+        let mut t = self.to_limbs(a);
+        t.resize(2 * self.limbs.len() + 1, 0);
+        Montgomery::from_limbs(&self.redc_limbs(t))
+    }
+}
+
+/// compare two numbers of the same number of limbs
+fn compare(a: &[u64], b: &[u64]) -> Ordering {
+    for (x, y) in a.iter().rev().zip(b.iter().rev()) {
+        match x.cmp(y) {
+            Ordering::Equal => continue,
+            other => return other
+        }
+    }
+    Ordering::Equal
+}
+
+impl Reduction for Montgomery {
+    fn modulus(&self) -> &BigInt {
+        &self.n
+    }
+
+    fn enter(&self, a: &BigInt) -> BigInt {
+        let a = self.to_limbs(&a.mod_floor(&self.n));
+        Montgomery::from_limbs(&self.mont_mul(&a, &self.r2))
+    }
+
+    fn leave(&self, a: &BigInt) -> BigInt {
+        self.redc(a)
+    }
+
+    fn one(&self) -> BigInt {
+        self.r1.clone()
+    }
+
+    fn mul(&self, a: &BigInt, b: &BigInt) -> BigInt {
+        Montgomery::from_limbs(&self.mont_mul(&self.to_limbs(a), &self.to_limbs(b)))
+    }
+
+    fn sqr(&self, a: &BigInt) -> BigInt {
+        Montgomery::from_limbs(&self.redc_limbs(Montgomery::square(&self.to_limbs(a))))
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// any reduction context, so that one can be stored and reused
+pub enum ModContext {
+    /// a full division for every reduction
+    Plain(Plain),
+    /// barrett reduction
+    Barrett(Barrett),
+    /// montgomery multiplication
+    Montgomery(Montgomery),
+}
+
+impl ModContext {
+    /// the fastest context for n: montgomery for odd n, plain otherwise
+    ///
+    /// panics if n is not positive, like a division by zero would. Use [ModContext::with_kind]
+    /// for a modulus that is not checked yet.
+    pub fn new(n: &BigInt) -> Self {
+        let kind = if n.is_odd() { ReductionKind::Montgomery } else { ReductionKind::Plain };
+        ModContext::with_kind(n, kind).expect("the modulus must be positive")
+    }
+
+    /// a context of a specific kind
+    pub fn with_kind(n: &BigInt, kind: ReductionKind) -> Result<Self, ReductionError> {
+        Ok(match kind {
+            ReductionKind::Plain => ModContext::Plain(Plain::new(n)?),
+            ReductionKind::Barrett => ModContext::Barrett(Barrett::new(n)?),
+            ReductionKind::Montgomery => ModContext::Montgomery(Montgomery::new(n)?),
+        })
+    }
+
+    /// the kind of this context
+    pub fn kind(&self) -> ReductionKind {
+        match self {
+            ModContext::Plain(_) => ReductionKind::Plain,
+            ModContext::Barrett(_) => ReductionKind::Barrett,
+            ModContext::Montgomery(_) => ReductionKind::Montgomery,
+        }
+    }
+
+    /// base^exp mod n for exp >= 0, the arguments and result are not represented
+    pub fn pow(&self, base: &BigInt, exp: &BigInt) -> BigInt {
+        crate::math::modexp::pow_with(self, base, exp, crate::math::modexp::ExpMethod::SlidingWindow, 0, false).0
+    }
+
+    /// a * b mod n, the arguments and result are not represented
+    pub fn mul_mod(&self, a: &BigInt, b: &BigInt) -> BigInt {
+        self.leave(&self.mul(&self.enter(a), &self.enter(b)))
+    }
+}
+
+impl Reduction for ModContext {
+    fn modulus(&self) -> &BigInt {
+        match self {
+            ModContext::Plain(c) => c.modulus(),
+            ModContext::Barrett(c) => c.modulus(),
+            ModContext::Montgomery(c) => c.modulus(),
+        }
+    }
+
+    fn enter(&self, a: &BigInt) -> BigInt {
+        match self {
+            ModContext::Plain(c) => c.enter(a),
+            ModContext::Barrett(c) => c.enter(a),
+            ModContext::Montgomery(c) => c.enter(a),
+        }
+    }
+
+    fn leave(&self, a: &BigInt) -> BigInt {
+        match self {
+            ModContext::Plain(c) => c.leave(a),
+            ModContext::Barrett(c) => c.leave(a),
+            ModContext::Montgomery(c) => c.leave(a),
+        }
+    }
+
+    fn one(&self) -> BigInt {
+        match self {
+            ModContext::Plain(c) => c.one(),
+            ModContext::Barrett(c) => c.one(),
+            ModContext::Montgomery(c) => c.one(),
+        }
+    }
+
+    fn mul(&self, a: &BigInt, b: &BigInt) -> BigInt {
+        match self {
+            ModContext::Plain(c) => c.mul(a, b),
+            ModContext::Barrett(c) => c.mul(a, b),
+            ModContext::Montgomery(c) => c.mul(a, b),
+        }
+    }
+
+    fn sqr(&self, a: &BigInt) -> BigInt {
+        match self {
+            ModContext::Plain(c) => c.sqr(a),
+            ModContext::Barrett(c) => c.sqr(a),
+            ModContext::Montgomery(c) => c.sqr(a),
+        }
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+pub mod test {
+
+    use super::*;
+
+    use std::str::FromStr;
+
+    use num_traits::Zero;
+
+    fn check<R: Reduction>(r: &R, a: &BigInt, b: &BigInt) {
+        let n = r.modulus();
+        let expected = (a * b).mod_floor(n);
+        assert_eq!(r.leave(&r.mul(&r.enter(a), &r.enter(b))), expected);
+        assert_eq!(r.leave(&r.sqr(&r.enter(a))), (a * a).mod_floor(n));
+        assert_eq!(r.leave(&r.one()), BigInt::one() % n);
+        assert_eq!(r.leave(&r.enter(a)), a.mod_floor(n));
+    }
+
+    #[test]
+    fn test_contexts() {
+        let n = BigInt::from_str("7893835550250389359297216688661450288929008118668349638445540095793204089892549599578948620842920833647521401008507076106394071770921505744490479825984079").unwrap();
+        let a = BigInt::from_str("1234567890123456789012345678901234567890123456789012345678901234567890").unwrap();
+        let b: BigInt = &n - 1u8;
+        for kind in [ReductionKind::Plain, ReductionKind::Barrett, ReductionKind::Montgomery] {
+            let ctx = ModContext::with_kind(&n, kind).unwrap();
+            check(&ctx, &a, &b);
+            check(&ctx, &-&a, &BigInt::zero());
+            check(&ctx, &b, &b);
+            assert_eq!(ctx.pow(&a, &BigInt::from(65537)), a.modpow(&BigInt::from(65537), &n));
+        }
+        // small and single limb moduli
+        for n in [3u64, 101, 65537, u64::MAX] {
+            let n = BigInt::from(n);
+            let ctx = ModContext::new(&n);
+            assert_eq!(ctx.kind(), ReductionKind::Montgomery);
+            check(&ctx, &BigInt::from(12345), &(&n - 2u8));
+        }
+        let ctx = ModContext::new(&BigInt::from(1u64 << 40));
+        assert_eq!(ctx.kind(), ReductionKind::Plain);
+        check(&ctx, &BigInt::from(u64::MAX), &BigInt::from(987654321));
+        assert_eq!(Montgomery::new(&BigInt::from(10)), Err(ReductionError::EvenModulus(BigInt::from(10))));
+        assert_eq!(Barrett::new(&BigInt::zero()), Err(ReductionError::InvalidModulus(BigInt::zero())));
+    }
+
+    #[test]
+    fn test_barrett_edges() {
+        for n in [1u64, 2, 3, 255, 256, 257, 1 << 40, u64::MAX] {
+            let n = BigInt::from(n);
+            let ctx = Barrett::new(&n).unwrap();
+            let top: BigInt = (BigInt::one() << (2 * n.bits())) - 1u8;
+            for x in [BigInt::zero(), BigInt::one(), &n - 1u8, n.clone(), &n * &n - 1u8, &n * &n, &top - &n, top] {
+                assert_eq!(ctx.reduce(&x), x.mod_floor(&n), "{x} mod {n}");
+            }
+            check(&ctx, &(&n - 1u8), &(&n - 1u8));
+        }
+    }
+
+    #[test]
+    fn test_montgomery_limbs() {
+        let moduli = [
+            // top limbs of 1 and all ones
+            (BigInt::one() << 64) + 13u8,
+            (BigInt::one() << 128) - 1u8,
+            (BigInt::one() << 128) + 1u8,
+            (BigInt::one() << 191) + 1u8,
+            // 2^255 - 19
+            (BigInt::one() << 255) - 19u8,
+            BigInt::from_str("340282366920938463463374607431768211297").unwrap(),
+        ];
+        for n in moduli {
+            let ctx = Montgomery::new(&n).unwrap();
+            let s = n.magnitude().to_u64_digits().len();
+            assert!(s > 1);
+            for a in [BigInt::one(), BigInt::from(u64::MAX), &n >> 1, &n - 2u8, &n - 1u8] {
+                check(&ctx, &a, &(&n - 1u8));
+                let limbs = ctx.to_limbs(&a);
+                assert_eq!(Montgomery::square(&limbs), Montgomery::product(&limbs, &limbs), "{a}^2");
+                // REDC(a * R) = a
+                let ar: BigInt = (&a << (64 * s)).mod_floor(&n);
+                assert_eq!(ctx.redc(&ar), a.mod_floor(&n));
+            }
+            let e = BigInt::from_str("1000000000000000000000000007").unwrap();
+            assert_eq!(ModContext::Montgomery(ctx).pow(&BigInt::from(3), &e), BigInt::from(3).modpow(&e, &n));
+        }
+    }
+
+    /// time 2048 bit exponentiations in every context and check that ModContext::new picks the
+    /// fastest one, only meaningful in release mode:
+    /// cargo test --release -- --ignored bench_contexts --nocapture
+    #[test]
+    #[ignore]
+    fn bench_contexts() {
+        use crate::math::modexp::{pow_with, ExpMethod};
+        use num_bigint::RandBigInt;
+        use std::time::{Duration, Instant};
+
+        let mut rng = rand::thread_rng();
+        let base = rng.gen_bigint(2048).abs();
+        let exp = rng.gen_bigint(2048).abs();
+        for parity in [1u8, 0] {
+            let n: BigInt = (rng.gen_bigint(2047).abs() << 1) + (BigInt::one() << 2047) + parity;
+            let kinds: Vec<ReductionKind> = [ReductionKind::Plain, ReductionKind::Barrett, ReductionKind::Montgomery]
+                .into_iter().filter(|k| parity == 1 || *k != ReductionKind::Montgomery).collect();
+            let contexts: Vec<ModContext> = kinds.iter().map(|k| ModContext::with_kind(&n, *k).unwrap()).collect();
+            // the best of some interleaved runs, so that noise hits every context alike
+            let mut times: Vec<Duration> = vec![Duration::MAX; kinds.len()];
+            for _ in 0..10 {
+                for (ctx, best) in contexts.iter().zip(times.iter_mut()) {
+                    let start = Instant::now();
+                    pow_with(ctx, &base, &exp, ExpMethod::LeftToRight, 0, false);
+                    *best = (*best).min(start.elapsed());
+                }
+            }
+            for (kind, t) in kinds.iter().zip(&times) {
+                println!("{} modulus, {kind}: {t:?}", if parity == 1 { "odd" } else { "even" });
+            }
+            // allow some noise between runs
+            let chosen = times[kinds.iter().position(|k| *k == ModContext::new(&n).kind()).unwrap()];
+            let fastest = *times.iter().min().unwrap();
+            assert!(chosen <= fastest * 11 / 10, "ModContext::new does not pick the fastest context");
+        }
+    }
+}
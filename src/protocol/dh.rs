@@ -0,0 +1,540 @@
+#![allow(dead_code)]
+/// # diffie-hellman key exchange
+///
+/// Alice and Bob agree on a group with a generator g of order q. Alice sends A = g^a, Bob sends
+/// B = g^b, both calculate the shared key K = B^a = A^b = g^ab. Finally Alice sends a hash of K,
+/// so that Bob can confirm they have the same key. On an elliptic curve the powers are point
+/// multiplications, A = a * G.
+///
+/// Nothing authenticates the public values, so Mallory can substitute them: she sends her own
+/// g^m1 to Bob and g^m2 to Alice, shares one key with each of them and forwards the key
+/// confirmation with her other key. Neither Alice nor Bob notice anything.
+///
+/// If q has a small factor t, Mallory can also confine the key to the subgroup of order t by
+/// raising both public values to q / t. Alice and Bob still get the same key, but it is one of
+/// only t elements, and Mallory finds it by comparing the key confirmation with all of them.
+/// A generator of prime order prevents this.
+///
+/// ___
+/// Author:     Christoph J. Scherr <software@cscherr.de>
+/// License:    MIT
+/// Source:     <https://git.cscherr.de/PlexSheep/plexcryptool/>
+
+use crate::cplex::printing::seperator;
+use crate::algo::oaep::hex;
+use crate::math::ecc::{EllipticCurve, EllipticCurvePoint};
+use crate::math::gallois::GalloisField;
+use crate::math::modexp::modular_exponentiation;
+use crate::math::primality::is_prime;
+
+use core::fmt;
+
+use num::Integer;
+use num_bigint::{BigInt, BigUint, RandBigInt};
+use num_traits::{One, ToPrimitive};
+use sha2::{Digest, Sha256};
+
+use pyo3::{prelude::*, exceptions::PyValueError};
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Mallory gives up confining the key to subgroups larger than this
+pub const MAX_SUBGROUP: u64 = 1 << 20;
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// errors for the key exchange
+pub enum DhError {
+    /// the modulus is not prime
+    NotPrime(BigUint),
+    /// the curve could not be created
+    InvalidCurve(String),
+    /// the generator is not an element of the group
+    InvalidGenerator(String),
+    /// the order is smaller than 2 or g^order is not the identity
+    InvalidOrder(BigUint),
+    /// the subgroup order does not divide the order of g
+    InvalidSubgroup(u64),
+    /// the subgroup is too large for Mallory to search it
+    TooLarge(u64),
+}
+
+impl fmt::Display for DhError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DhError::NotPrime(p) => write!(f, "{p} is not prime"),
+            DhError::InvalidCurve(e) => write!(f, "invalid curve: {e}"),
+            DhError::InvalidGenerator(g) => write!(f, "{g} is not an element of the group"),
+            DhError::InvalidOrder(q) => write!(f, "{q} is not the order of g"),
+            DhError::InvalidSubgroup(t) => write!(f, "{t} does not divide the order of g"),
+            DhError::TooLarge(t) => write!(f, "a subgroup of order {t} is too large, at most {MAX_SUBGROUP}"),
+        }
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// a cyclic group for the key exchange
+pub trait DhGroup {
+    /// elements of the group
+    type Element: Clone + Eq + fmt::Display;
+
+    /// the generator g
+    fn generator(&self) -> Self::Element;
+
+    /// the order q of g
+    fn order(&self) -> &BigUint;
+
+    /// e^k, or k * e on a curve
+    fn power(&self, e: &Self::Element, k: &BigUint, verbose: bool) -> Self::Element;
+}
+
+/// the multiplicative group of F_p, powers with [modular_exponentiation]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldGroup {
+    /// the prime
+    pub p: BigInt,
+    /// the generator
+    pub g: BigInt,
+    /// the order of g
+    pub order: BigUint,
+}
+
+impl FieldGroup {
+    /// make a new group, without an order g is assumed to generate all of F_p*
+    pub fn new(p: BigInt, g: BigInt, order: Option<BigUint>) -> Result<Self, DhError> {
+        let p_big = p.to_biguint().unwrap_or_default();
+        if !is_prime(&p, false) {
+            return Err(DhError::NotPrime(p_big));
+        }
+        if g <= BigInt::one() || g >= p {
+            return Err(DhError::InvalidGenerator(g.to_string()));
+        }
+        let order = order.unwrap_or_else(|| p_big - 1u8);
+        if order < BigUint::from(2u8)
+            || !modular_exponentiation(g.clone(), BigInt::from(order.clone()), p.clone(), false).is_one() {
+            return Err(DhError::InvalidOrder(order));
+        }
+        Ok(FieldGroup { p, g, order })
+    }
+}
+
+impl DhGroup for FieldGroup {
+    type Element = BigInt;
+
+    fn generator(&self) -> BigInt {
+        self.g.clone()
+    }
+
+    fn order(&self) -> &BigUint {
+        &self.order
+    }
+
+    fn power(&self, e: &BigInt, k: &BigUint, verbose: bool) -> BigInt {
+        let result = modular_exponentiation(e.clone(), BigInt::from(k.clone()), self.p.clone(), false);
+        if verbose {
+            println!("{e}^{k} mod {} = {result}", self.p);
+        }
+        result
+    }
+}
+
+/// the points of an elliptic curve, multiplications with [EllipticCurve::mul]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CurveGroup {
+    /// the curve
+    pub curve: EllipticCurve,
+    /// the generator
+    pub g: EllipticCurvePoint,
+    /// the order of g
+    pub order: BigUint,
+}
+
+impl CurveGroup {
+    /// use the curve y² = x³ + ax + b over F_p with a point g of the given order
+    pub fn new(p: u128, a: i128, b: i128, g: (u128, u128), order: BigUint) -> Result<Self, DhError> {
+        let field = GalloisField::new(p, false, None);
+        if !field.prime_base {
+            return Err(DhError::NotPrime(BigUint::from(p)));
+        }
+        let curve = match EllipticCurve::new(field, a, b, false) {
+            Ok(c) => c,
+            Err(e) => return Err(DhError::InvalidCurve(e))
+        };
+        let g = match curve.new_point(g.0, g.1) {
+            Ok(g) => g,
+            Err(_) => return Err(DhError::InvalidGenerator(format!("({}, {})", g.0, g.1)))
+        };
+        CurveGroup::from_curve(curve, g, order)
+    }
+
+    /// use an existing curve and point
+    pub fn from_curve(curve: EllipticCurve, g: EllipticCurvePoint, order: BigUint) -> Result<Self, DhError> {
+        if !curve.check_point(g, false) || g.coordinates().is_none() {
+            return Err(DhError::InvalidGenerator(g.to_string()));
+        }
+        let valid = match order.to_usize() {
+            Some(n) => n >= 2 && curve.mul(g, n).map(|h| h.coordinates().is_none()).unwrap_or(false),
+            None => false
+        };
+        if !valid {
+            return Err(DhError::InvalidOrder(order));
+        }
+        Ok(CurveGroup { curve, g, order })
+    }
+}
+
+impl DhGroup for CurveGroup {
+    type Element = EllipticCurvePoint;
+
+    fn generator(&self) -> EllipticCurvePoint {
+        self.g
+    }
+
+    fn order(&self) -> &BigUint {
+        &self.order
+    }
+
+    fn power(&self, e: &EllipticCurvePoint, k: &BigUint, verbose: bool) -> EllipticCurvePoint {
+        let t = k.to_usize().expect("scalars are smaller than the order");
+        let result = self.curve.mul(*e, t).expect("the point is on the curve");
+        if verbose {
+            println!("{t} * {e} = {result}");
+        }
+        result
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// what Mallory does
+pub enum Adversary {
+    /// there is no Mallory, the channel is only eavesdropped
+    Absent,
+    /// Mallory substitutes both public values with her own
+    Substitute,
+    /// Mallory confines the key to the subgroup of this order
+    Confine(u64),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// the participants
+pub enum Party {
+    /// the initiator
+    Alice,
+    /// the responder
+    Bob,
+    /// the attacker in the middle
+    Mallory,
+}
+
+impl fmt::Display for Party {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Party::Alice => write!(f, "Alice"),
+            Party::Bob => write!(f, "Bob"),
+            Party::Mallory => write!(f, "Mallory"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// a message on the channel
+pub struct Message {
+    /// the sender
+    pub from: Party,
+    /// the receiver
+    pub to: Party,
+    /// what the receiver thinks the message is
+    pub name: String,
+    /// the content
+    pub value: String,
+}
+
+impl fmt::Display for Message {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} -> {}: {} = {}", self.from, self.to, self.name, self.value)
+    }
+}
+
+/// the result of a key exchange
+pub struct Transcript<E> {
+    /// all messages in the order they were sent
+    pub messages: Vec<Message>,
+    /// the key that Alice calculated
+    pub alice_key: E,
+    /// the key that Bob calculated
+    pub bob_key: E,
+    /// true if Bob accepted the key confirmation
+    pub confirmed: bool,
+    /// the keys that Mallory learned
+    pub mallory_keys: Vec<E>,
+}
+
+impl<E: fmt::Display> fmt::Display for Transcript<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for message in &self.messages {
+            writeln!(f, "{message}")?;
+        }
+        writeln!(f, "Alice: K = {}", self.alice_key)?;
+        write!(f, "Bob: K = {} ({})", self.bob_key, if self.confirmed { "confirmed" } else { "rejected" })?;
+        for key in &self.mallory_keys {
+            write!(f, "\nMallory: K = {key}")?;
+        }
+        Ok(())
+    }
+}
+
+/// the key confirmation, sha256 of the key
+fn confirmation<E: fmt::Display>(key: &E) -> String {
+    hex(&Sha256::digest(key.to_string().as_bytes()))
+}
+
+/// a random exponent in [1, q)
+fn random_secret(order: &BigUint) -> BigUint {
+    rand::thread_rng().gen_biguint_range(&BigUint::one(), order)
+}
+
+/// records the messages and prints them as they are sent
+struct Channel {
+    /// everything that was sent
+    messages: Vec<Message>,
+    /// print the messages
+    verbose: bool,
+}
+
+impl Channel {
+    /// send a message
+    fn send(&mut self, from: Party, to: Party, name: &str, value: String) {
+        let message = Message { from, to, name: name.to_string(), value };
+        if self.verbose {
+            println!("{message}");
+        }
+        self.messages.push(message);
+    }
+}
+
+/// simulate a key exchange between Alice and Bob, with Mallory in the middle
+pub fn exchange<G: DhGroup>(group: &G, adversary: Adversary, verbose: bool) -> Result<Transcript<G::Element>, DhError> {
+    let order = group.order().clone();
+    if let Adversary::Confine(t) = adversary {
+        if t < 2 || !order.is_multiple_of(&BigUint::from(t)) {
+            return Err(DhError::InvalidSubgroup(t));
+        }
+        if t > MAX_SUBGROUP {
+            return Err(DhError::TooLarge(t));
+        }
+    }
+    let g = group.generator();
+    let mut channel = Channel { messages: Vec::new(), verbose };
+    let mut mallory_keys = Vec::new();
+
+    // Mallory's own secrets for the substitution
+    let (m1, m2) = (random_secret(&order), random_secret(&order));
+    // raising to this confines an element to the subgroup of order t
+    let cofactor = match adversary {
+        Adversary::Confine(t) => &order / t,
+        _ => BigUint::one()
+    };
+
+    if verbose {
+        println!("Alice:");
+    }
+    let a = random_secret(&order);
+    let big_a = group.power(&g, &a, verbose);
+    channel.send(Party::Alice, Party::Bob, "A", big_a.to_string());
+    let bob_receives = match adversary {
+        Adversary::Absent => big_a.clone(),
+        Adversary::Substitute => {
+            if verbose {
+                println!("Mallory:");
+            }
+            let m = group.power(&g, &m1, verbose);
+            channel.send(Party::Mallory, Party::Bob, "A", m.to_string());
+            m
+        }
+        Adversary::Confine(_) => {
+            if verbose {
+                println!("Mallory:");
+            }
+            let m = group.power(&big_a, &cofactor, verbose);
+            channel.send(Party::Mallory, Party::Bob, "A", m.to_string());
+            m
+        }
+    };
+
+    if verbose {
+        println!("Bob:");
+    }
+    let b = random_secret(&order);
+    let big_b = group.power(&g, &b, verbose);
+    channel.send(Party::Bob, Party::Alice, "B", big_b.to_string());
+    let alice_receives = match adversary {
+        Adversary::Absent => big_b.clone(),
+        Adversary::Substitute => {
+            if verbose {
+                println!("Mallory:");
+            }
+            let m = group.power(&g, &m2, verbose);
+            channel.send(Party::Mallory, Party::Alice, "B", m.to_string());
+            m
+        }
+        Adversary::Confine(_) => {
+            if verbose {
+                println!("Mallory:");
+            }
+            let m = group.power(&big_b, &cofactor, verbose);
+            channel.send(Party::Mallory, Party::Alice, "B", m.to_string());
+            m
+        }
+    };
+
+    if verbose {
+        println!("Alice:");
+    }
+    let alice_key = group.power(&alice_receives, &a, verbose);
+    let alice_confirm = confirmation(&alice_key);
+    channel.send(Party::Alice, Party::Bob, "H(K)", alice_confirm.clone());
+    if verbose {
+        println!("Bob:");
+    }
+    let bob_key = group.power(&bob_receives, &b, verbose);
+    let bob_receives_confirm = match adversary {
+        Adversary::Substitute => {
+            if verbose {
+                println!("Mallory:");
+            }
+            // one key with Alice, one with Bob
+            let with_alice = group.power(&big_a, &m2, verbose);
+            let with_bob = group.power(&big_b, &m1, verbose);
+            let forwarded = confirmation(&with_bob);
+            channel.send(Party::Mallory, Party::Bob, "H(K)", forwarded.clone());
+            mallory_keys.push(with_alice);
+            mallory_keys.push(with_bob);
+            forwarded
+        }
+        Adversary::Confine(t) => {
+            if verbose {
+                println!("Mallory:");
+            }
+            // the key is one of the t powers of g^(q/t), try all of them
+            let h = group.power(&g, &cofactor, verbose);
+            for i in 0..t {
+                let candidate = group.power(&h, &BigUint::from(i), false);
+                if confirmation(&candidate) == alice_confirm {
+                    if verbose {
+                        println!("H(K) matches candidate {i} = {candidate}");
+                    }
+                    mallory_keys.push(candidate);
+                    break;
+                }
+            }
+            alice_confirm
+        }
+        Adversary::Absent => alice_confirm
+    };
+    let confirmed = bob_receives_confirm == confirmation(&bob_key);
+    if verbose {
+        seperator();
+    }
+    Ok(Transcript { messages: channel.messages, alice_key, bob_key, confirmed, mallory_keys })
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// the adversary from the python arguments
+fn py_adversary(mallory: bool, confine: Option<u64>) -> Adversary {
+    match (mallory, confine) {
+        (_, Some(t)) => Adversary::Confine(t),
+        (true, None) => Adversary::Substitute,
+        (false, None) => Adversary::Absent
+    }
+}
+
+#[pyfunction]
+#[pyo3(name = "dh")]
+#[pyo3(signature=(p, g, order = None, mallory = false, confine = None, verbose = false))]
+/// python wrapper for exchange in F_p, returns the keys of Alice, Bob and Mallory
+pub fn py_dh(p: BigInt, g: BigInt, order: Option<BigUint>, mallory: bool, confine: Option<u64>,
+    verbose: bool) -> PyResult<(BigInt, BigInt, Vec<BigInt>)> {
+    let result = FieldGroup::new(p, g, order)
+        .and_then(|group| exchange(&group, py_adversary(mallory, confine), verbose));
+    match result {
+        Ok(t) => Ok((t.alice_key, t.bob_key, t.mallory_keys)),
+        Err(e) => Err(PyValueError::new_err(e.to_string()))
+    }
+}
+
+#[pyfunction]
+#[pyo3(name = "ecdh")]
+#[pyo3(signature=(curve, g, order, mallory = false, confine = None, verbose = false))]
+/// python wrapper for exchange on a curve, returns the keys of Alice, Bob and Mallory
+pub fn py_ecdh(curve: EllipticCurve, g: EllipticCurvePoint, order: BigUint, mallory: bool,
+    confine: Option<u64>, verbose: bool) -> PyResult<(EllipticCurvePoint, EllipticCurvePoint, Vec<EllipticCurvePoint>)> {
+    let result = CurveGroup::from_curve(curve, g, order)
+        .and_then(|group| exchange(&group, py_adversary(mallory, confine), verbose));
+    match result {
+        Ok(t) => Ok((t.alice_key, t.bob_key, t.mallory_keys)),
+        Err(e) => Err(PyValueError::new_err(e.to_string()))
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+pub mod test {
+
+    use super::*;
+
+    #[test]
+    fn test_dh_field() {
+        // 2 generates F_467*, 466 = 2 * 233
+        let group = FieldGroup::new(BigInt::from(467), BigInt::from(2), None).unwrap();
+        let t = exchange(&group, Adversary::Absent, true).unwrap();
+        assert_eq!(t.alice_key, t.bob_key);
+        assert!(t.confirmed);
+        assert_eq!(t.messages.len(), 3);
+        assert!(t.mallory_keys.is_empty());
+
+        let t = exchange(&group, Adversary::Substitute, true).unwrap();
+        assert!(t.confirmed);
+        assert_eq!(t.mallory_keys, vec![t.alice_key.clone(), t.bob_key.clone()]);
+        assert_eq!(t.messages.len(), 6);
+
+        let t = exchange(&group, Adversary::Confine(2), true).unwrap();
+        assert_eq!(t.alice_key, t.bob_key);
+        assert!(t.alice_key == BigInt::one() || t.alice_key == BigInt::from(466));
+        assert_eq!(t.messages.len(), 5);
+        assert_eq!(t.mallory_keys, vec![t.alice_key.clone()]);
+
+        assert_eq!(exchange(&group, Adversary::Confine(3), false).err(), Some(DhError::InvalidSubgroup(3)));
+        assert_eq!(FieldGroup::new(BigInt::from(467), BigInt::from(2), Some(BigUint::from(233u8))).err(),
+            Some(DhError::InvalidOrder(BigUint::from(233u8))));
+        assert!(FieldGroup::new(BigInt::from(468), BigInt::from(5), None).is_err());
+    }
+
+    #[test]
+    fn test_dh_curve() {
+        // y² = x³ + 2x + 2 over F_17, (5, 1) generates all 19 points
+        let group = CurveGroup::new(17, 2, 2, (5, 1), BigUint::from(19u8)).unwrap();
+        let t = exchange(&group, Adversary::Absent, true).unwrap();
+        assert_eq!(t.alice_key, t.bob_key);
+        let t = exchange(&group, Adversary::Substitute, true).unwrap();
+        assert!(t.confirmed);
+        assert_eq!(t.mallory_keys, vec![t.alice_key, t.bob_key]);
+        // a prime order leaves no small subgroup
+        assert_eq!(exchange(&group, Adversary::Confine(2), false).err(), Some(DhError::InvalidSubgroup(2)));
+        assert!(CurveGroup::new(17, 2, 2, (5, 1), BigUint::from(18u8)).is_err());
+
+        // y² = x³ + x + 1 over F_5 has 9 points, (0, 1) generates them and (2, ±1) have order 3
+        let group = CurveGroup::new(5, 1, 1, (0, 1), BigUint::from(9u8)).unwrap();
+        let t = exchange(&group, Adversary::Confine(3), true).unwrap();
+        assert_eq!(t.alice_key, t.bob_key);
+        assert!(t.confirmed);
+        assert_eq!(t.messages.len(), 5);
+        let identity = group.power(&group.generator(), group.order(), false);
+        assert_eq!(group.power(&t.alice_key, &BigUint::from(3u8), false), identity);
+        assert_eq!(t.mallory_keys, vec![t.alice_key]);
+    }
+}
@@ -0,0 +1,10 @@
+/// # protocol module
+///
+/// Simulations of cryptographic protocols between Alice and Bob, with an optional attacker in the
+/// middle. Every message that is sent can be printed, so the runs can be followed step by step.
+///
+/// ___
+/// Author:     Christoph J. Scherr <software@cscherr.de>
+/// License:    MIT
+/// Source:     <https://git.cscherr.de/PlexSheep/plexcryptool/>
+pub mod dh;